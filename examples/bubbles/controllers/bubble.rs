@@ -1,7 +1,7 @@
 use crate::{
     qp_assets::RCamera2D,
     qp_core::{now_secs, random::Random},
-    qp_ecs::components::{CQuad, CTag, CTransform2D, CVelocity2D},
+    qp_ecs::components::{CQuad, CShape, CTag, CTransform2D, CVelocity2D},
     qp_schemas::SchemaSprite,
     Controller, FrameResult, QPError, Schema, VersionedIndex, World,
 };
@@ -98,9 +98,15 @@ fn spawn(rand: &mut Random, world: &mut World) -> Result<VersionedIndex, QPError
     this_schema.quad = quad;
     this_schema.color = color;
     this_schema.tag = "sprite".into();
-    this_schema.texture = Some("Bubble.png".into());
 
     let id = this_schema.build_entity(&mut world.registry)?;
+    world.registry.entity_manager.add(
+        &id,
+        CShape::Circle {
+            radius: 100.0,
+            softness: 4.0,
+        },
+    );
 
     Ok(id)
 }
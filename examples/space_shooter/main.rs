@@ -4,17 +4,36 @@ extern crate quipi;
 pub use quipi::prelude::*;
 use quipi::{
     asset_manager::assets::{camera::OrthographicCameraParams, RCamera2D, RShader},
-    core::prelude::{random::Random, trig::magnitude2d_squared, Interval, Timer},
+    audio::{s_stop_all_sounds, s_update_sound_emitters, QPAudio},
+    core::prelude::{
+        math2d::distance_squared,
+        random::{Random, RandomSource},
+        Countdown, Interval, Timer,
+    },
     ecs::prelude::components::CTransform2D,
-    gfx::prelude::{ShaderUniforms, SpriteRenderer, SPRITE_FRAG, SPRITE_VERT},
-    schemas::sprite::TextureAtlas,
+    gfx::prelude::{
+        DebugRenderer, InstancedSpriteRenderer, RenderGraph, ShaderUniforms, SpriteRenderer,
+        SPRITE_FRAG, SPRITE_INSTANCED_VERT, SPRITE_VERT,
+    },
+    physics::prelude::{
+        attach_point, s_apply_damage, s_apply_velocity_2d, s_clamp_velocity, s_color_by_value,
+        s_obb_overlap, s_respawn, s_shatter, s_update_shards, Aabb, ShatterParams, SpatialHash2D,
+        SpawnPattern, Wave, WaveScheduler, Weapon,
+    },
+    schemas::sprite::{SchemaSprite, SchemaTextureAtlas, TextureAtlas},
 };
 
 use qp_ecs::components::*;
-use sdl2::{event::WindowEvent, keyboard::Keycode};
+use sdl2::{
+    event::WindowEvent,
+    keyboard::{Keycode, Scancode},
+};
 
 pub static WIDTH: u32 = 1600;
 pub static HEIGHT: u32 = 900;
+pub static LIVES: u32 = 3;
+// asteroid speed in units per second
+pub static ASTEROID_SPEED: f32 = 60.0;
 
 pub fn run() -> Result<(), QPError> {
     let mut app = App::init("Space Shooter", WIDTH, HEIGHT, 348756)?;
@@ -31,6 +50,11 @@ pub fn run() -> Result<(), QPError> {
         )?,
     )?;
 
+    app.world.registry.asset_manager.load_asset(
+        "instanced_shader",
+        RShader::from_str(SPRITE_INSTANCED_VERT, SPRITE_FRAG, vec![])?,
+    )?;
+
     app.world.registry.asset_manager.load_asset(
         "space_tilesheet",
         qp_assets::RTexture {
@@ -39,12 +63,28 @@ pub fn run() -> Result<(), QPError> {
         },
     )?;
 
+    app.world
+        .viewport
+        .set_aspect(Some(WIDTH as f32 / HEIGHT as f32));
+
+    // the same bullet and asteroid movement at any frame rate
+    app.set_fixed_timestep(1.0 / 60.0, 5);
+
     let game = GameController::new(&mut app)?;
     app.register_controller(game);
 
-    let renderer = SpriteRenderer::new(&mut app.world.registry, "camera", "shader")?;
+    // hundreds of identical stars, drawn behind everything else in one call
+    let stars =
+        InstancedSpriteRenderer::new(&mut app.world.registry, "camera", "instanced_shader", "star")?;
+    app.register_pass("stars", RenderGraph::BACKGROUND, stars)?;
+
+    let mut renderer = SpriteRenderer::new(&mut app.world.registry, "camera", "shader")?;
+    renderer.skip_tag("star");
     app.register_renderer(renderer);
 
+    let debug_renderer = DebugRenderer::new(&mut app.world.registry, "camera", "shader")?;
+    app.register_renderer(debug_renderer);
+
     app.run((0.1, 0.1, 0.1, 1.0))
 }
 
@@ -66,11 +106,14 @@ pub struct GameController {
     stars: Vec<Star>,
 
     asteroid_spawn_interval: Interval,
-    bullet_spawn_interval: Interval,
+    asteroid_waves: WaveScheduler,
     star_spawn_interval: Interval,
 
     game_over: bool,
+    lives: u32,
     firing: bool,
+    weapon: Weapon,
+    bullet_grid: SpatialHash2D,
 }
 
 impl GameController {
@@ -120,16 +163,19 @@ impl GameController {
             stars,
             score_interval: Interval::new(1.0),
             asteroid_spawn_interval: Interval::new(1.0),
-            bullet_spawn_interval: Interval::new(0.2),
+            asteroid_waves: Asteroid::waves(&mut app.world.registry)?,
             star_spawn_interval: Interval::new(0.2),
             game_over: false,
+            lives: LIVES,
             firing: false,
+            weapon: Bullet::weapon(&mut app.world.registry)?,
+            bullet_grid: SpatialHash2D::new(64.0),
         })
     }
 
     fn reset(&mut self, registry: &mut GlobalRegistry) {
         for bullet in self.bullets.iter() {
-            registry.entity_manager.set_to_delete(bullet.index);
+            self.weapon.release(registry, bullet.index);
         }
         for asteroid in self.asteroids.iter() {
             registry.entity_manager.set_to_delete(asteroid.index);
@@ -138,40 +184,50 @@ impl GameController {
         self.bullets.clear();
         self.asteroids.clear();
 
+        s_stop_all_sounds(registry, &mut self.ship.audio);
+
         self.asteroid_spawn_interval.check();
-        self.bullet_spawn_interval.check();
+        self.asteroid_waves.reset();
 
         self.score.score = 0;
+        self.lives = LIVES;
         self.game_over = false;
     }
 
-    fn spawn_bullet(&mut self, registry: &mut GlobalRegistry) -> Result<(), QPError> {
-        if !self.bullet_spawn_interval.check() || !self.firing {
+    fn spawn_bullet(&mut self, world: &mut World) -> Result<(), QPError> {
+        self.weapon.tick(world.delta);
+        if !self.firing || world.registry.entity_manager.is_disabled(&self.ship.index) {
             return Ok(());
         }
 
-        let Some(ship) = registry
+        let Some(rotate) = world
+            .registry
             .entity_manager
             .get::<CTransform2D>(&self.ship.index)
+            .map(|ship| ship.rotate)
         else {
             return Ok(());
         };
-        let direction = ship.direction();
-        let position = ship.translate + (direction * 28.0);
-        let bullet = Bullet::new(registry, position, direction, ship.rotate);
+        let Some(translate) = attach_point(&world.registry, &self.ship.index, "muzzle") else {
+            return Ok(());
+        };
+        let muzzle = CTransform2D {
+            translate,
+            rotate,
+            ..CTransform2D::default()
+        };
 
-        self.bullets.push(bullet?);
+        if let Some(index) = self.weapon.try_fire(world, &muzzle)? {
+            self.bullets.push(Bullet::new(&mut world.registry, index));
+
+            // a pad that can't rumble, or none at all, is fine
+            let _ = world.input.rumble(0.0, 0.3, 60);
+        }
 
         Ok(())
     }
 
     fn spawn_asteroid(&mut self, world: &mut World) -> Result<(), QPError> {
-        if !self.asteroid_spawn_interval.check() {
-            return Ok(());
-        }
-
-        let (_x, _y, width, height) = world.viewport.get_dimensions();
-
         let ship = world
             .registry
             .entity_manager
@@ -180,20 +236,35 @@ impl GameController {
 
         let ship_pos = ship.translate;
 
-        let x_pos = self.rand.range(
-            ship_pos.x as i32 - (width / 2),
-            ship_pos.x as i32 + (width / 2),
-        ) as f32;
+        for index in self
+            .asteroid_waves
+            .update(&mut world.registry, world.delta, ship_pos)?
+        {
+            let asteroid = Asteroid::adopt(
+                &mut world.registry,
+                index,
+                ship_pos,
+                (self.rand.random() + 1.0) * 2.0,
+                self.rand.random() * 2.0 * glm::pi::<f32>(),
+                &mut world.rand,
+            );
+            self.asteroids.push(asteroid);
+        }
+
+        if !self.asteroid_spawn_interval.check() {
+            return Ok(());
+        }
+
+        let (_x, _y, width, height) = world.viewport.get_dimensions();
 
-        let y_pos = self.rand.range(
-            ship_pos.y as i32 - (height / 2),
-            ship_pos.y as i32 + (height / 2),
-        ) as f32;
+        let position = self
+            .rand
+            .point_in_rect(ship_pos, glm::vec2(width as f32, height as f32));
 
         let asteroid = Asteroid::new(
             &mut world.registry,
             ship_pos,
-            glm::vec2(x_pos, y_pos),
+            position,
             (self.rand.random() + 1.0) * 2.0,
             self.rand.random() * 2.0 * glm::pi::<f32>(),
             &mut world.rand,
@@ -204,9 +275,8 @@ impl GameController {
     }
 
     fn spawn_star(&mut self, world: &mut World) -> Result<(), QPError> {
-        if !self.star_spawn_interval.check() {
-            return Ok(());
-        }
+        // a slow frame spawns the stars it missed
+        let count = self.star_spawn_interval.ticks(world.delta);
 
         let ship_transform = world
             .registry
@@ -215,9 +285,10 @@ impl GameController {
             .unwrap();
 
         let ship_pos = ship_transform.translate;
-        let star = Star::new(world, ship_pos)?;
-
-        self.stars.push(star);
+        for _ in 0..count {
+            let star = Star::new(world, ship_pos)?;
+            self.stars.push(star);
+        }
 
         Ok(())
     }
@@ -225,43 +296,25 @@ impl GameController {
 
 impl Controller for GameController {
     fn update(&mut self, world: &mut World) -> FrameResult {
-        for event in world.events.iter() {
-            match event {
-                Event::Quit { .. } => {
-                    return FrameResult::Quit;
-                }
-                Event::KeyDown {
-                    keycode: Some(Keycode::Escape),
-                    ..
-                } => {
-                    return FrameResult::Quit;
-                }
-                Event::KeyDown {
-                    keycode: Some(Keycode::Space),
-                    repeat: false,
-                    ..
-                } => {
-                    if !self.game_over {
-                        self.firing = true;
-                    }
-                }
-                Event::KeyDown {
-                    keycode: Some(Keycode::Return),
-                    repeat: false,
-                    ..
-                } => {
-                    if self.game_over {
-                        self.reset(&mut world.registry);
-                    }
-                }
-                Event::KeyUp {
-                    keycode: Some(Keycode::Space),
-                    ..
-                } => {
-                    self.firing = false;
-                }
-                _ => (),
-            };
+        if world.events.iter().any(|event| matches!(event, Event::Quit { .. })) {
+            return FrameResult::Quit;
+        }
+
+        let input = &world.input;
+        if input.was_pressed(Keycode::Escape) {
+            return FrameResult::Quit;
+        }
+        if input.was_pressed(Keycode::Space) && !self.game_over {
+            self.firing = true;
+        }
+        if input.was_released(Keycode::Space) {
+            self.firing = false;
+        }
+        if input.was_pressed(Keycode::Return) && self.game_over {
+            self.reset(&mut world.registry);
+        }
+        if input.was_pressed(Keycode::F3) {
+            world.debug_mode = !world.debug_mode;
         }
 
         self.score.update(world);
@@ -272,6 +325,25 @@ impl Controller for GameController {
             return FrameResult::None;
         }
 
+        for bullet in self.bullets.iter_mut() {
+            if bullet.alive {
+                bullet.update(world);
+            }
+        }
+
+        // bullets are bucketed so each asteroid only tests the ones near it
+        self.bullet_grid.clear();
+        for bullet in self.bullets.iter().filter(|bullet| bullet.alive) {
+            let em = &world.registry.entity_manager;
+            if let (Some(transform), Some(quad)) = (
+                em.get::<CTransform2D>(&bullet.index),
+                em.get::<CQuad>(&bullet.index),
+            ) {
+                self.bullet_grid
+                    .insert(bullet.index, Aabb::from_quad(transform, quad));
+            }
+        }
+
         for asteroid in self.asteroids.iter_mut() {
             if !asteroid.alive {
                 continue;
@@ -285,40 +357,80 @@ impl Controller for GameController {
                 .unwrap()
                 .clone();
 
-            if asteroid.check_collision(&mut world.registry, &ship_transform, 32.0) {
-                self.game_over = true;
+            // a dead ship is disabled until it respawns, see s_respawn
+            let ship_alive = world
+                .registry
+                .entity_manager
+                .get::<CHealth>(&self.ship.index)
+                .is_some_and(|health| !health.is_dead());
+            if ship_alive && asteroid.check_collision(&mut world.registry, &ship_transform, 32.0) {
+                let _ = world.input.rumble(1.0, 1.0, 400);
+                s_apply_damage(&mut world.registry, &self.ship.index, 1.0, 0.0);
+
+                self.lives -= 1;
+                self.game_over = self.lives == 0;
             };
 
-            // check for collision with bullet
-            for bullet in self.bullets.iter_mut() {
-                if !bullet.alive {
-                    continue;
+            // check for collision with the bullets around it
+            let em = &world.registry.entity_manager;
+            let nearby = match (
+                em.get::<CTransform2D>(&asteroid.index),
+                em.get::<CQuad>(&asteroid.index),
+            ) {
+                (Some(transform), Some(quad)) if asteroid.alive => {
+                    let bounds = Aabb::from_quad(transform, quad);
+                    self.bullet_grid.query_aabb(bounds.min, bounds.max)
                 }
+                _ => vec![],
+            };
 
+            for bullet in nearby {
                 let bullet_transform = world
                     .registry
                     .entity_manager
-                    .get::<CTransform2D>(&bullet.index)
+                    .get::<CTransform2D>(&bullet)
                     .unwrap()
                     .clone();
+                let bullet_half = world
+                    .registry
+                    .entity_manager
+                    .get::<CQuad>(&bullet)
+                    .map_or(glm::vec2(0.0, 0.0), |quad| {
+                        glm::vec2(quad.width, quad.height) / 2.0
+                    });
 
-                if asteroid.check_collision(&mut world.registry, &bullet_transform, 16.0) {
+                if asteroid.check_hit(&mut world.registry, &bullet_transform, bullet_half) {
                     self.score.score += 5;
                 };
-
-                // update bullet while we're at it
-                bullet.update(world);
             }
 
             asteroid.update(world);
         }
 
+        // the asteroids stay on screen under the game over text, without
+        // running out of time
+        if self.game_over {
+            for asteroid in self.asteroids.iter_mut() {
+                asteroid.lifetime.pause();
+            }
+        }
+
+        for bullet in self.bullets.iter().filter(|bullet| !bullet.alive) {
+            self.weapon.release(&mut world.registry, bullet.index);
+        }
+        self.bullets.retain(|bullet| bullet.alive);
+
         for star in self.stars.iter_mut() {
             star.update(world);
         }
 
+        s_respawn(&mut world.registry, world.delta);
+        s_color_by_value(&mut world.registry);
+        s_update_shards(&mut world.registry, world.delta);
+        s_apply_velocity_2d(&mut world.registry, world.delta);
+
         self.spawn_asteroid(world).unwrap();
-        self.spawn_bullet(&mut world.registry).unwrap();
+        self.spawn_bullet(world).unwrap();
         self.spawn_star(world).unwrap();
 
         self.ship.update(world);
@@ -369,16 +481,8 @@ impl Controller for Camera {
                     win_event: WindowEvent::Resized(w, h),
                     ..
                 } => {
-                    world.viewport.set_dimensions(0, 0, *w, *h);
-
-                    if let Some(camera) = world.registry.asset_manager.get_mut::<RCamera2D>(self.id)
-                    {
-                        camera.params.right = *w as f32;
-                        camera.params.top = *h as f32;
-
-                        camera.view = camera.calc_view_matrix();
-                        camera.projection = camera.calc_projection_matrix();
-                    }
+                    // the camera keeps its size, the letterbox absorbs the difference
+                    world.viewport.fit_window(*w, *h);
                 }
                 _ => (),
             };
@@ -405,10 +509,9 @@ pub struct Ship {
     pub index: VersionedIndex,
     pub thruster: VersionedIndex,
 
+    audio: QPAudio,
     acceleration: f32,
-    max_velocity: f32,
     thrust: bool,
-    thruster_offset: glm::Vec2,
 }
 
 impl Ship {
@@ -436,7 +539,10 @@ impl Ship {
             translate: glm::vec2(0.0, 0.0),
             ..CTransform2D::default()
         };
-        let thruster_offset = glm::vec2(0.0, 26.0);
+        let attach_points = CAttachPoints::new(&[
+            ("muzzle", glm::vec2(0.0, 28.0)),
+            ("thruster", glm::vec2(0.0, -26.0)),
+        ]);
         let mut sprite = CSprite::new(
             &quad,
             Some(glm::vec4(1.0, 1.0, 1.0, 1.0)),
@@ -454,38 +560,47 @@ impl Ship {
             })
             .with(CVelocity2D { x: 0.0, y: 0.0 })
             .with(CTransform2D {
-                translate: ship_transform.translate - thruster_offset,
+                translate: attach_points
+                    .resolve("thruster", &ship_transform)
+                    .unwrap_or(ship_transform.translate),
                 rotate: ship_transform.rotate,
                 scale: glm::vec2(0.5, 0.5),
                 ..CTransform2D::default()
             })
             .with(sprite)
+            .with(CSoundEmitter::new("assets/audio/jingles_NES00.ogg", true, 0.5))
             .build();
 
         let index = EntityBuilder::create(&mut world.registry.entity_manager)
-            .with(CTag {
-                tag: "ship".to_string(),
+            .with_bundle(qp_ecs::Sprite2DBundle {
+                tag: CTag {
+                    tag: "ship".to_string(),
+                },
+                transform: ship_transform,
+                sprite: CSprite::new(
+                    &quad,
+                    Some(glm::vec4(0.8, 0.2, 0.0, 1.0)),
+                    Some(TextureAtlas {
+                        texture: texture_id,
+                        texture_dims: texture.texture_dims,
+                        active_texture: glm::vec2(6.0, 5.0),
+                    }),
+                ),
+                velocity: CVelocity2D { x: 0.0, y: 0.0 },
             })
-            .with(CVelocity2D { x: 0.0, y: 0.0 })
-            .with(ship_transform)
-            .with(CSprite::new(
-                &quad,
-                Some(glm::vec4(0.8, 0.2, 0.0, 1.0)),
-                Some(TextureAtlas {
-                    texture: texture_id,
-                    texture_dims: texture.texture_dims,
-                    active_texture: glm::vec2(6.0, 5.0),
-                }),
-            ))
+            .with(CVelocityClamp2D { max_speed: 200.0 })
+            .with(CHealth::new(1.0))
+            .with(CRespawn::new(ship_transform.translate, 2.0))
+            .with(CAudioListener)
+            .with(attach_points)
             .build();
 
         Ok(Self {
             index,
             thruster,
+            audio: QPAudio::new()?,
             acceleration: 20.0,
-            max_velocity: 200.0,
             thrust: false,
-            thruster_offset,
         })
     }
 }
@@ -495,76 +610,50 @@ impl Controller for Ship {
         let (_x, _y, width, height) = world.viewport.get_dimensions();
 
         for event in world.events.iter() {
-            match event {
-                Event::MouseMotion { x, y, .. } => {
-                    let x = x - width / 2;
-                    let y = (y - height / 2) * -1;
-                    let angle = qp_core::trig::angle(
-                        &glm::vec3(0.0, 1.0, 0.0),
-                        &glm::vec3(x as f32, y as f32, 0.0),
-                    );
-
-                    if let Some(transform) = world
-                        .registry
-                        .entity_manager
-                        .get_mut::<CTransform2D>(&self.index)
-                    {
-                        transform.rotate = match x > 0 {
-                            true => {
-                                let angle = (2.0 * glm::pi::<f32>()) - angle;
-
-                                angle
-                            }
-                            false => angle,
-                        };
-                    }
-                }
-                Event::KeyDown {
-                    keycode: Some(Keycode::W),
-                    repeat: false,
-                    ..
-                } => {
-                    if let Some(thruster) = world
-                        .registry
-                        .entity_manager
-                        .get_mut::<CSprite>(&self.thruster)
-                    {
-                        thruster.skip = false;
-                    }
-                    self.thrust = true;
-                }
-                Event::KeyUp {
-                    keycode: Some(Keycode::W),
-                    repeat: false,
-                    ..
-                } => {
-                    if let Some(thruster) = world
-                        .registry
-                        .entity_manager
-                        .get_mut::<CSprite>(&self.thruster)
-                    {
-                        thruster.skip = true;
-                    }
-
-                    self.thrust = false;
+            if let Event::MouseMotion { .. } = event {
+                let mouse = world.input.mouse_in_viewport(&world.viewport);
+                let x = mouse.x - width as f32 / 2.0;
+                let y = (mouse.y - height as f32 / 2.0) * -1.0;
+                let angle = qp_core::trig::angle(&glm::vec3(0.0, 1.0, 0.0), &glm::vec3(x, y, 0.0));
+
+                if let Some(transform) = world
+                    .registry
+                    .entity_manager
+                    .get_mut::<CTransform2D>(&self.index)
+                {
+                    transform.rotate = match x > 0.0 {
+                        true => {
+                            let angle = (2.0 * glm::pi::<f32>()) - angle;
+
+                            angle
+                        }
+                        false => angle,
+                    };
                 }
-                _ => (),
-            };
+            }
+        }
+
+        // thrust while W is held, but not while waiting to respawn
+        self.thrust = world.input.is_scancode_down(Scancode::W)
+            && !world.registry.entity_manager.is_disabled(&self.index);
+        if let Some(thruster) = world
+            .registry
+            .entity_manager
+            .get_mut::<CSprite>(&self.thruster)
+        {
+            thruster.skip = !self.thrust;
         }
 
         // match thruster transform to ship
-        let transform = world
+        let rotate = world
             .registry
             .entity_manager
             .get::<CTransform2D>(&self.index)
-            .unwrap();
-
-        let translate = transform.translate.clone();
-        let rotate = transform.rotate.clone();
-        let offset = glm::vec2(
-            self.thruster_offset.y * transform.direction().x,
-            self.thruster_offset.y * transform.direction().y,
-        );
+            .unwrap()
+            .rotate;
+        let Some(translate) = attach_point(&world.registry, &self.index, "thruster") else {
+            return FrameResult::None;
+        };
 
         let Some(thruster) = world
             .registry
@@ -574,45 +663,55 @@ impl Controller for Ship {
             return FrameResult::None;
         };
 
-        thruster.translate = translate - offset;
+        thruster.translate = translate;
         thruster.rotate = rotate;
 
+        if let Some(emitter) = world
+            .registry
+            .entity_manager
+            .get_mut::<CSoundEmitter>(&self.thruster)
+        {
+            emitter.playing = self.thrust;
+        }
+
+        s_update_sound_emitters(&mut world.registry, &mut self.audio, width as f32 / 2.0);
+
+        // the ship only moves while thrusting
         if !self.thrust {
+            if let Some(velocity) = world
+                .registry
+                .entity_manager
+                .get_mut::<CVelocity2D>(&self.index)
+            {
+                *velocity = CVelocity2D::default();
+            }
+
             return FrameResult::None;
         }
 
-        // calculate velocity based on direction and acceleration
-        let Some(velocity) = world
+        // accelerate the way the ship is facing, the clamp keeps it under
+        // max speed whichever way that is
+        let Some(direction) = world
             .registry
             .entity_manager
-            .get_mut::<CVelocity2D>(&self.index)
+            .get::<CTransform2D>(&self.index)
+            .map(|transform| transform.direction())
         else {
             return FrameResult::None;
         };
 
-        velocity.x = (velocity.x + self.acceleration).min(self.max_velocity);
-        velocity.y = (velocity.y + self.acceleration).min(self.max_velocity);
-
-        // apply velocity to translation
         let Some(velocity) = world
             .registry
             .entity_manager
-            .get::<CVelocity2D>(&self.index)
+            .get_mut::<CVelocity2D>(&self.index)
         else {
             return FrameResult::None;
         };
 
-        let velocity = velocity.clone();
+        velocity.x += direction.x * self.acceleration;
+        velocity.y += direction.y * self.acceleration;
 
-        if let Some(transform) = world
-            .registry
-            .entity_manager
-            .get_mut::<CTransform2D>(&self.index)
-        {
-            let direction = transform.direction();
-            transform.translate.x += velocity.x * world.delta * direction.x;
-            transform.translate.y += velocity.y * world.delta * direction.y;
-        }
+        s_clamp_velocity(&mut world.registry);
 
         FrameResult::None
     }
@@ -627,12 +726,11 @@ pub struct Bullet {
 }
 
 impl Bullet {
-    pub fn new(
-        registry: &mut GlobalRegistry,
-        position: glm::Vec2,
-        direction: glm::Vec2,
-        angle: f32,
-    ) -> Result<Self, QPError> {
+    /**
+     * the ship's gun. the sprite is drawn bigger than the hitbox that
+     * `Bullet::new` gives it
+     */
+    pub fn weapon(registry: &mut GlobalRegistry) -> Result<Weapon, QPError> {
         let texture_id = registry
             .asset_manager
             .get_asset_id("space_tilesheet")
@@ -643,67 +741,54 @@ impl Bullet {
             .get::<qp_assets::RTexture>(texture_id)
             .ok_or(QPError::SpriteTextureDoesntExist)?;
 
-        let quad = CQuad {
-            width: 32.0,
-            height: 32.0,
-            center_x: 0.0,
-            center_y: 0.0,
+        let projectile = SchemaSprite {
+            tag: "bullet".to_string(),
+            quad: CQuad {
+                width: 32.0,
+                height: 32.0,
+                center_x: 0.0,
+                center_y: 0.0,
+            },
+            color: glm::vec4(1.0, 1.0, 1.0, 1.0),
+            texture: Some(SchemaTextureAtlas {
+                texture: "space_tilesheet".to_string(),
+                texture_dims: Some(texture.texture_dims),
+                active_texture: glm::vec2(1.0, 5.0),
+            }),
+            ..SchemaSprite::default()
         };
 
-        let speed = 250.0;
-
-        let index = EntityBuilder::create(&mut registry.entity_manager)
-            .with(CTag {
-                tag: "bullet".to_string(),
-            })
-            .with(CTransform2D {
-                translate: position,
-                rotate: angle,
-                ..CTransform2D::default()
-            })
-            .with(CVelocity2D {
-                x: speed * direction.x,
-                y: speed * direction.y,
-            })
-            .with(CSprite::new(
-                &quad,
-                Some(glm::vec4(1.0, 1.0, 1.0, 1.0)),
-                Some(TextureAtlas {
-                    texture: texture_id,
-                    texture_dims: texture.texture_dims,
-                    active_texture: glm::vec2(1.0, 5.0),
-                }),
-            ))
-            .build();
+        Ok(Weapon::new(projectile, 250.0, 0.2).with_pool_size(16))
+    }
 
-        let timer = Timer::new();
+    pub fn new(registry: &mut GlobalRegistry, index: VersionedIndex) -> Self {
+        registry.entity_manager.add(
+            &index,
+            CQuad {
+                width: 8.0,
+                height: 16.0,
+                ..CQuad::default()
+            },
+        );
+        registry
+            .entity_manager
+            .add(&index, CMotionBlur { scale: 0.002 });
 
-        Ok(Self {
+        Self {
             index,
-            timer,
+            timer: Timer::new(),
             lifetime: 1.5,
             alive: true,
-        })
+        }
     }
 
     pub fn update(&mut self, world: &mut World) -> bool {
-        let Some(velocity) = world
-            .registry
-            .entity_manager
-            .get::<CVelocity2D>(&self.index)
-        else {
-            return false;
-        };
-
-        let velocity = glm::vec2(velocity.x * world.delta, velocity.y * world.delta);
-
-        if let Some(transform) = world
+        if world
             .registry
             .entity_manager
-            .get_mut::<CTransform2D>(&self.index)
+            .get::<CTransform2D>(&self.index)
+            .is_none()
         {
-            transform.translate += velocity
-        } else {
             return false;
         }
 
@@ -717,8 +802,8 @@ impl Bullet {
 
         sprite.color.w = 1.0 - (elapsed / self.lifetime);
 
+        // spent bullets go back to the weapon, see `GameController::update`
         if elapsed > self.lifetime {
-            world.registry.entity_manager.set_to_delete(self.index);
             self.alive = false
         }
 
@@ -731,19 +816,15 @@ struct Asteroid {
     rotation_step: f32,
     scale: f32,
     alive: bool,
-    lifetime: f32,
-    timer: Timer,
+    lifetime: Countdown,
 }
 
 impl Asteroid {
-    pub fn new(
-        registry: &mut GlobalRegistry,
-        ship_pos: glm::Vec2,
-        position: glm::Vec2,
-        scale: f32,
-        rotate: f32,
-        rand: &mut Random,
-    ) -> Result<Self, QPError> {
+    /**
+     * what every asteroid is spawned as, before `Asteroid::adopt` sets it
+     * flying. the sprite is drawn bigger than the hitbox that adopt gives it
+     */
+    pub fn prefab(registry: &mut GlobalRegistry) -> Result<SchemaSprite, QPError> {
         let texture_id = registry
             .asset_manager
             .get_asset_id("space_tilesheet")
@@ -754,49 +835,123 @@ impl Asteroid {
             .get::<qp_assets::RTexture>(texture_id)
             .ok_or(QPError::SpriteTextureDoesntExist)?;
 
-        let quad = CQuad {
-            width: 32.0,
-            height: 32.0,
-            ..CQuad::default()
+        Ok(SchemaSprite {
+            tag: "asteroid".to_string(),
+            quad: CQuad {
+                width: 32.0,
+                height: 32.0,
+                ..CQuad::default()
+            },
+            color: glm::vec4(0.9, 0.9, 0.9, 1.0),
+            texture: Some(SchemaTextureAtlas {
+                texture: "space_tilesheet".to_string(),
+                texture_dims: Some(texture.texture_dims),
+                active_texture: glm::vec2(0.0, 1.0),
+            }),
+            ..SchemaSprite::default()
+        })
+    }
+
+    /**
+     * bursts on top of the steady trickle, closing in from every side
+     */
+    pub fn waves(registry: &mut GlobalRegistry) -> Result<WaveScheduler, QPError> {
+        let prefab = Self::prefab(registry)?;
+        let ring = |time: f32, count: usize| Wave {
+            time,
+            prefab: prefab.clone(),
+            count,
+            pattern: SpawnPattern::Ring { radius: 450.0 },
         };
 
-        let direction = (ship_pos - position).normalize();
+        Ok(WaveScheduler::new(
+            vec![
+                ring(10.0, 6),
+                Wave {
+                    time: 20.0,
+                    prefab: prefab.clone(),
+                    count: 8,
+                    pattern: SpawnPattern::Scatter {
+                        size: glm::vec2(WIDTH as f32, HEIGHT as f32),
+                    },
+                },
+                ring(30.0, 10),
+                ring(45.0, 16),
+            ],
+            1234,
+        ))
+    }
 
-        let index = EntityBuilder::create(&mut registry.entity_manager)
-            .with(CTag {
-                tag: "asteroid".to_string(),
-            })
-            .with(CTransform2D {
-                translate: position,
-                rotate,
-                scale: glm::vec2(scale, scale),
-                ..CTransform2D::default()
-            })
-            .with(CVelocity2D {
-                x: direction.x * (rand.random() + 1.0),
-                y: direction.y * (rand.random() + 1.0),
-            })
-            .with(CSprite::new(
-                &quad,
-                Some(glm::vec4(0.9, 0.9, 0.9, 1.0)),
-                Some(TextureAtlas {
-                    texture: texture_id,
-                    texture_dims: texture.texture_dims,
-                    active_texture: glm::vec2(0.0, 1.0),
-                }),
-            ))
-            .build();
+    pub fn new(
+        registry: &mut GlobalRegistry,
+        ship_pos: glm::Vec2,
+        position: glm::Vec2,
+        scale: f32,
+        rotate: f32,
+        rand: &mut impl RandomSource,
+    ) -> Result<Self, QPError> {
+        let mut prefab = Self::prefab(registry)?;
+        prefab.transform.translate = position;
+        let index = prefab.build_entity(registry)?;
 
-        let timer = Timer::new();
+        Ok(Self::adopt(registry, index, ship_pos, scale, rotate, rand))
+    }
 
-        Ok(Self {
+    /**
+     * sends a spawned prefab at the ship
+     */
+    pub fn adopt(
+        registry: &mut GlobalRegistry,
+        index: VersionedIndex,
+        ship_pos: glm::Vec2,
+        scale: f32,
+        rotate: f32,
+        rand: &mut impl RandomSource,
+    ) -> Self {
+        let em = &mut registry.entity_manager;
+        let mut position = glm::vec2(0.0, 0.0);
+        if let Some(transform) = em.get_mut::<CTransform2D>(&index) {
+            transform.rotate = rotate;
+            transform.scale = glm::vec2(scale, scale);
+            position = transform.translate;
+        }
+
+        let direction = (ship_pos - position).normalize();
+
+        em.add(
+            &index,
+            CQuad {
+                width: 24.0,
+                height: 24.0,
+                ..CQuad::default()
+            },
+        );
+        em.add(
+            &index,
+            CVelocity2D {
+                x: direction.x * (rand.random() + 1.0) * ASTEROID_SPEED,
+                y: direction.y * (rand.random() + 1.0) * ASTEROID_SPEED,
+            },
+        );
+        // fades to red and out over its lifetime
+        em.add(
+            &index,
+            CColorByValue {
+                value: 0.0,
+                gradient: vec![
+                    (0.0, glm::vec4(0.9, 1.0, 1.0, 1.0)),
+                    (1.0, glm::vec4(0.9, 0.0, 0.0, 0.0)),
+                ],
+            },
+        );
+
+        Self {
             index,
             rotation_step: (rand.random() + 0.7) * 2.0,
             alive: true,
             scale,
-            lifetime: 5.0,
-            timer,
-        })
+            lifetime: Countdown::new(5.0),
+        }
     }
 
     pub fn check_collision(
@@ -812,7 +967,35 @@ impl Asteroid {
 
         let offset = 10.0;
         let threshold = obj_radius + (self.scale * 16.0) - offset;
-        if magnitude2d_squared(&transform.translate, &obj.translate) < threshold.powf(2.0) {
+        if distance_squared(&transform.translate, &obj.translate) < threshold.powf(2.0) {
+            registry.entity_manager.set_to_delete(self.index);
+            self.alive = false;
+
+            return true;
+        }
+
+        false
+    }
+
+    /**
+     * exact hit test against the asteroid's rotated hitbox
+     */
+    pub fn check_hit(
+        &mut self,
+        registry: &mut GlobalRegistry,
+        obj: &CTransform2D,
+        obj_half: glm::Vec2,
+    ) -> bool {
+        let (Some(transform), Some(quad)) = (
+            registry.entity_manager.get::<CTransform2D>(&self.index),
+            registry.entity_manager.get::<CQuad>(&self.index),
+        ) else {
+            return false;
+        };
+
+        let half = glm::vec2(quad.width, quad.height) / 2.0;
+        if s_obb_overlap(transform, half, obj, obj_half) {
+            s_shatter(registry, &self.index, &ShatterParams::default());
             registry.entity_manager.set_to_delete(self.index);
             self.alive = false;
 
@@ -825,42 +1008,28 @@ impl Asteroid {
 
 impl Controller for Asteroid {
     fn update(&mut self, world: &mut World) -> FrameResult {
-        let elapsed = self.timer.elapsed();
-
-        if elapsed > self.lifetime {
+        if self.lifetime.advance(world.delta) <= 0.0 {
             world.registry.entity_manager.set_to_delete(self.index);
             self.alive = false;
 
             return FrameResult::None;
         }
 
-        let velocity = world
-            .registry
-            .entity_manager
-            .get::<CVelocity2D>(&self.index)
-            .unwrap()
-            .clone();
-
         if let Some(transform) = world
             .registry
             .entity_manager
             .get_mut::<CTransform2D>(&self.index)
         {
-            transform.translate.x += velocity.x;
-            transform.translate.y += velocity.y;
-
             transform.rotate += world.delta * self.rotation_step;
         }
 
-        let sprite = world
+        if let Some(color) = world
             .registry
             .entity_manager
-            .get_mut::<CSprite>(&self.index)
-            .unwrap();
-
-        sprite.color.y = 1.0 - (elapsed / self.lifetime);
-        sprite.color.z = 1.0 - (elapsed / self.lifetime);
-        sprite.color.w = 1.0 - (elapsed / self.lifetime);
+            .get_mut::<CColorByValue>(&self.index)
+        {
+            color.value = 1.0 - self.lifetime.fraction_remaining();
+        }
 
         FrameResult::None
     }
@@ -876,15 +1045,9 @@ struct Star {
 impl Star {
     pub fn new(world: &mut World, ship_pos: glm::Vec2) -> Result<Self, QPError> {
         let (_x, _y, width, height) = world.viewport.get_dimensions();
-        let x_pos = world.rand.range(
-            ship_pos.x as i32 - (width / 2),
-            ship_pos.x as i32 + (width / 2),
-        ) as f32;
-
-        let y_pos = world.rand.range(
-            ship_pos.y as i32 - (height / 2),
-            ship_pos.y as i32 + (height / 2),
-        ) as f32;
+        let position = world
+            .rand
+            .point_in_rect(ship_pos, glm::vec2(width as f32, height as f32));
 
         let texture_id = world
             .registry
@@ -904,12 +1067,15 @@ impl Star {
             ..CQuad::default()
         };
 
+        let depth = world.rand.random() * 0.8;
+
         let index = EntityBuilder::create(&mut world.registry.entity_manager)
             .with(CTag {
-                tag: "asteroid".to_string(),
+                tag: "star".to_string(),
             })
+            .with(CParallaxDepth(depth))
             .with(CTransform2D {
-                translate: glm::vec2(x_pos, y_pos),
+                translate: position,
                 scale: glm::vec2(1.0, 1.0),
                 ..CTransform2D::default()
             })
@@ -982,13 +1148,23 @@ impl Controller for Score {
     fn update(&mut self, world: &mut World) -> FrameResult {
         let (_x, _y, _width, height) = world.viewport.get_dimensions();
         world.text_buffer.push(qp_gfx::QPText {
-            text: format!("score: {}", self.score),
+            text: "score: ".into(),
             pos: glm::vec2(20.0, height as f32 - 40.0),
             style: qp_gfx::QPTextStyle {
                 font: self.font,
                 color: glm::vec4(1.0, 1.0, 1.0, 1.0),
                 scale: 0.4,
+                ..qp_gfx::QPTextStyle::default()
             },
+            spans: vec![qp_gfx::QPTextSpan {
+                text: self.score.to_string(),
+                style: qp_gfx::QPTextStyle {
+                    font: self.font,
+                    color: glm::vec4(1.0, 0.85, 0.2, 1.0),
+                    scale: 0.4,
+                    ..qp_gfx::QPTextStyle::default()
+                },
+            }],
         });
 
         FrameResult::None
@@ -1008,23 +1184,40 @@ impl GameOver {
 impl Controller for GameOver {
     fn update(&mut self, world: &mut World) -> FrameResult {
         let (_x, _y, width, height) = world.viewport.get_dimensions();
+        let center = glm::vec2(width as f32, height as f32) / 2.0;
+
+        let title = qp_gfx::QPTextStyle {
+            font: self.font,
+            color: glm::vec4(1.0, 1.0, 1.0, 0.6),
+            scale: 2.0,
+            align: qp_gfx::TextAlign::Center,
+            ..qp_gfx::QPTextStyle::default()
+        };
+
+        // the hint goes one title line below the title
+        let title_height = world
+            .registry
+            .asset_manager
+            .get::<qp_assets::RFont>(self.font)
+            .map_or(50.0, |font| font.measure("Game Over", &title).y);
+
         world.text_buffer.push(qp_gfx::QPText {
             text: "Game Over".into(),
-            pos: glm::vec2((width as f32 / 2.0) - 300.0, height as f32 / 2.0),
-            style: qp_gfx::QPTextStyle {
-                font: self.font,
-                color: glm::vec4(1.0, 1.0, 1.0, 0.6),
-                scale: 2.0,
-            },
+            pos: center,
+            style: title,
+            spans: vec![],
         });
         world.text_buffer.push(qp_gfx::QPText {
             text: "Press Enter to start again".into(),
-            pos: glm::vec2((width as f32 / 2.0) - 160.0, (height as f32 / 2.0) - 50.0),
+            pos: center - glm::vec2(0.0, title_height),
             style: qp_gfx::QPTextStyle {
                 font: self.font,
                 color: glm::vec4(0.8, 0.8, 0.8, 1.0),
                 scale: 0.5,
+                align: qp_gfx::TextAlign::Center,
+                ..qp_gfx::QPTextStyle::default()
             },
+            spans: vec![],
         });
 
         FrameResult::None
@@ -1043,15 +1236,23 @@ impl DebugInfoText {
 
 impl Controller for DebugInfoText {
     fn update(&mut self, world: &mut World) -> FrameResult {
-        let entity_count = world.registry.entity_manager.count();
+        let stats = world.registry.stats();
+        let largest = stats
+            .largest(3)
+            .iter()
+            .map(|store| format!("{} {}/{}", store.name, store.len, store.capacity))
+            .collect::<Vec<_>>()
+            .join(", ");
         world.text_buffer.push(qp_gfx::QPText {
-            text: format!("entities: {}", entity_count),
+            text: format!("entities: {} ({})", stats.entities, largest),
             pos: glm::vec2(20.0, 20.0),
             style: qp_gfx::QPTextStyle {
                 font: self.font,
                 color: glm::vec4(1.0, 1.0, 1.0, 1.0),
                 scale: 0.4,
+                ..qp_gfx::QPTextStyle::default()
             },
+            spans: vec![],
         });
 
         FrameResult::None
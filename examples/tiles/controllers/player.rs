@@ -1,7 +1,7 @@
 use crate::{
     qp_assets::{tilemap::ValidTile, RTileMap},
     qp_ecs::components::{CQuad, CTransform2D},
-    qp_schemas::SchemaSprite,
+    qp_schemas::{SchemaSprite, SchemaTextureAtlas},
     GlobalRegistry, Schema, VersionedIndex,
 };
 use quipi::{
@@ -44,7 +44,7 @@ impl PlayerController {
         this_schema.transform = transform;
         this_schema.quad = quad;
         this_schema.tag = "sprite".into();
-        this_schema.texture = Some("Player.png".into());
+        this_schema.texture = Some(SchemaTextureAtlas::new("Player.png"));
 
         let id = this_schema.build_entity(registry)?;
 
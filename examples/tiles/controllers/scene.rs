@@ -122,36 +122,43 @@ impl Controller for DebugInfoText {
             font: self.font,
             color: glm::vec4(1.0, 1.0, 1.0, 1.0),
             scale: 0.4,
+            ..QPTextStyle::default()
         };
         world.text_buffer.push(QPText {
             text: format!("entities: {}", entity_count),
             pos: glm::vec2(20.0, 20.0),
             style: style.clone(),
+            spans: vec![],
         });
         world.text_buffer.push(QPText {
             text: format!("draw calls: {}", world.debug_info.draw_calls),
             pos: glm::vec2(20.0, 40.0),
             style: style.clone(),
+            spans: vec![],
         });
         world.text_buffer.push(QPText {
             text: format!("render ms: {}", world.debug_info.render_ms),
             pos: glm::vec2(20.0, 60.0),
             style: style.clone(),
+            spans: vec![],
         });
         world.text_buffer.push(QPText {
             text: format!("controller ms: {}", world.debug_info.controller_ms),
             pos: glm::vec2(20.0, 80.0),
             style: style.clone(),
+            spans: vec![],
         });
         world.text_buffer.push(QPText {
             text: format!("fps: {}", world.debug_info.fps as u32),
             pos: glm::vec2(20.0, 100.0),
             style: style.clone(),
+            spans: vec![],
         });
         world.text_buffer.push(QPText {
             text: format!("ms: {}", world.debug_info.frame_ms as u32),
             pos: glm::vec2(20.0, 120.0),
             style: style.clone(),
+            spans: vec![],
         });
 
         FrameResult::None
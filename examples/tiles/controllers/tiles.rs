@@ -8,7 +8,7 @@ use quipi::{
 use crate::{
     qp_assets::RTileMap,
     qp_core::{now_secs, random::Random},
-    qp_ecs::components::{CQuad, CSprite, CTransform2D},
+    qp_ecs::components::{CQuad, CSprite, CStatic, CTransform2D},
     GlobalRegistry, VersionedIndex,
 };
 
@@ -92,6 +92,8 @@ fn tile(x: u32, y: u32, tile_val: u16, registry: &mut GlobalRegistry) -> Version
 
     let entity = registry.entity_manager.create();
     registry.entity_manager.add(&entity, transform);
+    registry.entity_manager.add(&entity, quad.clone());
+    registry.entity_manager.add(&entity, CStatic);
     registry.entity_manager.add(
         &entity,
         CSprite::new(
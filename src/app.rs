@@ -1,10 +1,14 @@
-use crate::platform::opengl;
 use crate::platform::sdl2;
 use crate::prelude::qp_gfx;
 use crate::prelude::qp_gfx::Viewport;
 use crate::prelude::World;
-use crate::prelude::{qp_gfx::TextRenderer, QPError};
+use crate::prelude::{
+    qp_gfx::{ClearColor, ClearConfig, PixelPerfectCamera, RenderGraph, TextRenderer},
+    QPError,
+};
 use crate::QPResult;
+use ::sdl2::{event::Event, keyboard::Keycode};
+use std::{cell::Cell, rc::Rc};
 
 #[cfg(feature = "qp_profiling")]
 use crate::prelude::QPProfiler;
@@ -16,8 +20,16 @@ pub struct App {
     #[cfg(feature = "qp_profiling")]
     profiler: QPProfiler,
 
-    controllers: Vec<Box<dyn Controller>>,
-    renderers: Vec<Box<dyn Renderer>>,
+    /**
+     * the renderers, drawn in pass order. text is drawn in the
+     * `RenderGraph::UI` pass
+     */
+    pub render_graph: RenderGraph,
+
+    // see `set_pixel_perfect`
+    pixel_perfect: Option<PixelPerfectCamera>,
+
+    controllers: Vec<RegisteredController>,
 }
 
 impl App {
@@ -33,7 +45,8 @@ impl App {
         // let audio = QPAudio::new()?;
         // audio.play();
 
-        let world = World::new(viewport, seed)?;
+        let mut world = World::new(viewport, seed)?;
+        world.window = sdl2::QPWindowState::new(title);
 
         Ok(Self {
             winapi,
@@ -42,40 +55,126 @@ impl App {
             #[cfg(feature = "qp_profiling")]
             profiler: QPProfiler::new(),
 
+            render_graph: RenderGraph::new(),
+            pixel_perfect: None,
             controllers: vec![],
-            renderers: vec![],
         })
     }
 
-    pub fn register_controller(&mut self, controller: impl Controller + 'static) {
-        self.controllers.push(Box::new(controller));
+    /**
+     * the handle can be kept around to switch the controller off and on later
+     */
+    pub fn register_controller(&mut self, controller: impl Controller + 'static) -> ControllerHandle {
+        let registered = RegisteredController::new(controller);
+        let handle = registered.handle.clone();
+
+        self.controllers.push(registered);
+
+        handle
+    }
+
+    /**
+     * like `register_controller`, under a name that `replace_controller` can
+     * find it by. names are unique
+     */
+    pub fn register_named_controller(
+        &mut self,
+        name: &str,
+        controller: impl Controller + 'static,
+    ) -> QPResult<ControllerHandle> {
+        register_named(&mut self.controllers, name, controller)
+    }
+
+    /**
+     * swaps the named controller for a new one, for iterating on gameplay
+     * without a restart. the new one runs at the same point in the update
+     * order and keeps the old one's handle, so disabling it still works
+     */
+    pub fn replace_controller(
+        &mut self,
+        name: &str,
+        controller: impl Controller + 'static,
+    ) -> QPResult<()> {
+        replace_named(&mut self.controllers, name, controller)
     }
 
+    /**
+     * runs the controllers every fixed_dt seconds of real time instead of
+     * once a frame, at most max_substeps times a frame. see `FixedTimestep`
+     */
+    pub fn set_fixed_timestep(&mut self, fixed_dt: f32, max_substeps: u32) {
+        self.world.fixed_step = FixedTimestep::new(fixed_dt, max_substeps);
+    }
+
+    /**
+     * adds the renderer to the `RenderGraph::WORLD` pass, after any renderers
+     * already registered. use `register_pass` to draw somewhere else
+     */
     pub fn register_renderer(&mut self, renderer: impl Renderer + 'static) {
-        self.renderers.push(Box::new(renderer));
+        let mut i = self.render_graph.len();
+        while self.render_graph.has_pass(&format!("renderer_{}", i)) {
+            i += 1;
+        }
+
+        self.render_graph
+            .add_pass(&format!("renderer_{}", i), RenderGraph::WORLD, renderer)
+            .expect("renderer name is unique");
+    }
+
+    /**
+     * adds a renderer to the render graph under its own name, see `RenderGraph`
+     */
+    pub fn register_pass(
+        &mut self,
+        name: &str,
+        order: i32,
+        renderer: impl Renderer + 'static,
+    ) -> QPResult<()> {
+        self.render_graph.add_pass(name, order, renderer)
+    }
+
+    /**
+     * draws everything at width x height and scales it up to the window by a
+     * whole number, see `PixelPerfectCamera`
+     */
+    pub fn set_pixel_perfect(&mut self, width: i32, height: i32) {
+        self.world.viewport.set_pixel_perfect(Some((width, height)));
+        self.pixel_perfect = Some(PixelPerfectCamera::new(width, height));
     }
 
+    /**
+     * goes back to drawing straight to the window, undoing `set_pixel_perfect`
+     */
+    pub fn clear_pixel_perfect(&mut self) {
+        self.world.viewport.set_pixel_perfect(None);
+        self.pixel_perfect = None;
+    }
+
+    /**
+     * clear_color is the starting color, unless `world.clear_config` was
+     * already changed before the app runs. controllers can change what gets
+     * cleared afterwards through `world.clear_config`
+     */
     pub fn run(&mut self, clear_color: (f32, f32, f32, f32)) -> QPResult<()> {
-        self.register_renderer(TextRenderer::new()?);
+        if !self.render_graph.has_pass("text") {
+            self.register_pass("text", RenderGraph::UI, TextRenderer::new()?)?;
+        }
+        apply_clear_color(&mut self.world.clear_config, clear_color);
 
         'running: loop {
             self.world.flush();
             self.world.new_frame(&mut self.winapi)?;
+            self.winapi.apply(&mut self.world.window)?;
 
-            opengl::buffer::clear_buffers(clear_color);
+            self.world.viewport.clear(&self.world.clear_config);
 
             // update controllers
             #[cfg(feature = "qp_profiling")]
             self.profiler.begin();
 
-            for controller in self.controllers.iter_mut() {
-                match controller.update(&mut self.world) {
-                    FrameResult::Quit => break 'running,
-                    FrameResult::Restart => {
-                        self.world.reset();
-                    }
-                    FrameResult::None => (),
-                }
+            self.world.frame_step.handle_events(&self.world.events);
+            if update_frame(&mut self.controllers, &mut self.world) == FrameResult::Quit {
+                break 'running;
             }
 
             #[cfg(feature = "qp_profiling")]
@@ -84,15 +183,15 @@ impl App {
             }
 
             // call renderers
-            let mut draw_calls = 0;
-
             #[cfg(feature = "qp_profiling")]
             self.profiler.begin();
 
-            for renderer in self.renderers.iter_mut() {
-                if let Some(m_draw_calls) = renderer.draw(&mut self.world) {
-                    draw_calls += m_draw_calls;
-                }
+            if let Some(camera) = &mut self.pixel_perfect {
+                camera.begin(&self.world.clear_config)?;
+            }
+            let draw_calls = self.render_graph.draw(&mut self.world);
+            if let Some(camera) = &self.pixel_perfect {
+                camera.end(&self.world.viewport);
             }
 
             if let Some(window) = &self.winapi.window {
@@ -107,12 +206,374 @@ impl App {
             }
 
             self.world.debug_info.draw_calls = draw_calls;
+            self.world.debug_info.render_info = self.render_graph.render_info();
         }
 
         Ok(())
     }
 }
 
+/**
+ * updates the controllers, then applies the deletions they marked. this is
+ * the only point in a frame where entities are deleted, so every controller
+ * sees the same entities and the renderers only see the ones that survived
+ *
+ * with a fixed timestep this happens as many times as the frame has fixed
+ * updates, see `FixedTimestep`
+ */
+fn update_frame(controllers: &mut [RegisteredController], world: &mut World) -> FrameResult {
+    // frame stepping picks the delta and when to run while paused
+    let paused = world.frame_step.paused;
+    let steps = match paused {
+        true => 1,
+        false => world.fixed_step.advance(world.delta),
+    };
+
+    if steps == 0 {
+        // the events wait for the next update, but closing can't
+        if world.events.iter().any(|event| matches!(event, Event::Quit { .. })) {
+            return FrameResult::Quit;
+        }
+        world.carry_events();
+
+        return FrameResult::None;
+    }
+
+    let frame_events = world.take_carried_events();
+    let mut frame_input = None;
+    let mut result = FrameResult::None;
+    for step in 0..steps {
+        // only the first update of a frame sees its events and key presses
+        if step == 1 {
+            frame_input = Some((
+                std::mem::take(&mut world.events),
+                world.input.take_frame_edges(),
+            ));
+        }
+        if let (Some(fixed_dt), false) = (world.fixed_step.fixed_dt, paused) {
+            world.delta = fixed_dt;
+        }
+
+        result = update_controllers(controllers, world);
+        world.registry.update_deletions();
+
+        if result == FrameResult::Quit {
+            break;
+        }
+    }
+
+    // the renderers see the whole frame's input
+    if let Some((events, edges)) = frame_input {
+        world.events = events;
+        world.input.restore_frame_edges(edges);
+    }
+    world.restore_consumed_events();
+
+    // the carried events were already drawn in the frames they came from
+    if let Some(events) = frame_events {
+        world.events = events;
+    }
+
+    result
+}
+
+/**
+ * runs every controller once, unless frame stepping is paused and no step
+ * was requested
+ */
+fn update_controllers(controllers: &mut [RegisteredController], world: &mut World) -> FrameResult {
+    if !world.frame_step.should_update() {
+        // the controllers that would normally handle closing the window are
+        // skipped while paused
+        if world.events.iter().any(|event| matches!(event, Event::Quit { .. })) {
+            return FrameResult::Quit;
+        }
+
+        return FrameResult::None;
+    }
+
+    if world.frame_step.paused {
+        world.delta = world.frame_step.step_delta;
+    }
+
+    for registered in controllers.iter_mut() {
+        if !registered.handle.is_enabled() {
+            continue;
+        }
+
+        match registered.controller.update(world) {
+            FrameResult::Quit => return FrameResult::Quit,
+            FrameResult::Restart => {
+                world.reset();
+            }
+            FrameResult::None => (),
+        }
+    }
+
+    FrameResult::None
+}
+
+struct RegisteredController {
+    name: Option<String>,
+    handle: ControllerHandle,
+    controller: Box<dyn Controller>,
+}
+
+impl RegisteredController {
+    fn new(controller: impl Controller + 'static) -> Self {
+        Self {
+            name: None,
+            handle: ControllerHandle::default(),
+            controller: Box::new(controller),
+        }
+    }
+}
+
+// a config set up before `App::run`, even one that keeps the last frame, wins
+// over the color passed to run
+fn apply_clear_color(config: &mut ClearConfig, clear_color: ClearColor) {
+    if *config == ClearConfig::default() {
+        config.color = Some(clear_color);
+    }
+}
+
+fn register_named(
+    controllers: &mut Vec<RegisteredController>,
+    name: &str,
+    controller: impl Controller + 'static,
+) -> QPResult<ControllerHandle> {
+    if controllers.iter().any(|registered| registered.name.as_deref() == Some(name)) {
+        return Err(QPError::Generic(format!(
+            "controller {} already exists",
+            name
+        )));
+    }
+
+    let registered = RegisteredController {
+        name: Some(name.to_string()),
+        ..RegisteredController::new(controller)
+    };
+    let handle = registered.handle.clone();
+    controllers.push(registered);
+
+    Ok(handle)
+}
+
+fn replace_named(
+    controllers: &mut [RegisteredController],
+    name: &str,
+    controller: impl Controller + 'static,
+) -> QPResult<()> {
+    let Some(registered) = controllers
+        .iter_mut()
+        .find(|registered| registered.name.as_deref() == Some(name))
+    else {
+        return Err(QPError::Generic(format!(
+            "controller {} doesn't exist",
+            name
+        )));
+    };
+
+    registered.controller = Box::new(controller);
+
+    Ok(())
+}
+
+/**
+ * switches a registered controller on and off. disabled controllers are
+ * skipped until they are enabled again. clones share the same switch
+ */
+#[derive(Debug, Clone)]
+pub struct ControllerHandle {
+    enabled: Rc<Cell<bool>>,
+}
+
+impl Default for ControllerHandle {
+    fn default() -> Self {
+        Self {
+            enabled: Rc::new(Cell::new(true)),
+        }
+    }
+}
+
+impl ControllerHandle {
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.set(enabled);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.get()
+    }
+}
+
+/**
+ * debug stepping through the update loop. while paused the controllers only
+ * run when a step is requested, the renderers keep drawing every frame
+ */
+#[derive(Debug)]
+pub struct FrameStep {
+    pub paused: bool,
+
+    /**
+     * keys that pause/resume and step. set in debug builds only
+     */
+    pub toggle_key: Option<Keycode>,
+    pub step_key: Option<Keycode>,
+
+    /**
+     * the delta controllers see on a stepped frame
+     */
+    pub step_delta: f32,
+
+    steps: u32,
+}
+
+impl Default for FrameStep {
+    fn default() -> Self {
+        #[cfg(debug_assertions)]
+        let (toggle_key, step_key) = (Some(Keycode::F9), Some(Keycode::F10));
+
+        #[cfg(not(debug_assertions))]
+        let (toggle_key, step_key) = (None, None);
+
+        Self {
+            paused: false,
+            toggle_key,
+            step_key,
+            step_delta: 1.0 / 60.0,
+            steps: 0,
+        }
+    }
+}
+
+impl FrameStep {
+    pub fn request_step(&mut self) {
+        self.steps += 1;
+    }
+
+    pub fn handle_events(&mut self, events: &[Event]) {
+        for event in events.iter() {
+            let Event::KeyDown {
+                keycode: Some(keycode),
+                repeat: false,
+                ..
+            } = event
+            else {
+                continue;
+            };
+
+            if Some(*keycode) == self.toggle_key {
+                self.paused = !self.paused;
+                self.steps = 0;
+            } else if Some(*keycode) == self.step_key && self.paused {
+                self.request_step();
+            }
+        }
+    }
+
+    /**
+     * whether the controllers run this frame. uses up a step when paused
+     */
+    pub fn should_update(&mut self) -> bool {
+        if !self.paused {
+            return true;
+        }
+
+        if self.steps == 0 {
+            return false;
+        }
+
+        self.steps -= 1;
+
+        true
+    }
+}
+
+/**
+ * runs the controllers at a fixed rate instead of once a frame, so movement
+ * and collisions behave the same at 60 and 144 fps. the real time of each
+ * frame is added up and the controllers run once for every fixed_dt of it,
+ * with `world.delta` set to fixed_dt. what's left over is `alpha`
+ *
+ * a frame that runs no update passes its events on to the next frame. off,
+ * once a frame with the real delta, unless fixed_dt is set
+ */
+#[derive(Debug)]
+pub struct FixedTimestep {
+    pub fixed_dt: Option<f32>,
+
+    /**
+     * the most updates a frame runs. when the game can't keep up, the time
+     * it's behind by is dropped instead of running ever more updates to
+     * catch up with it
+     */
+    pub max_substeps: u32,
+
+    accumulator: f32,
+    skipped: bool,
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self {
+            fixed_dt: None,
+            max_substeps: 5,
+            accumulator: 0.0,
+            skipped: false,
+        }
+    }
+}
+
+impl FixedTimestep {
+    pub fn new(fixed_dt: f32, max_substeps: u32) -> Self {
+        Self {
+            fixed_dt: Some(fixed_dt),
+            max_substeps: max_substeps.max(1),
+            ..Self::default()
+        }
+    }
+
+    /**
+     * adds a frame's real delta and returns how many updates to run for it
+     */
+    pub fn advance(&mut self, delta: f32) -> u32 {
+        let Some(fixed_dt) = self.fixed_dt.filter(|fixed_dt| *fixed_dt > 0.0) else {
+            self.skipped = false;
+
+            return 1;
+        };
+
+        self.accumulator += delta;
+        let steps = ((self.accumulator / fixed_dt) as u32).min(self.max_substeps);
+        self.accumulator -= steps as f32 * fixed_dt;
+        if self.accumulator >= fixed_dt {
+            self.accumulator %= fixed_dt;
+        }
+
+        self.skipped = steps == 0;
+
+        steps
+    }
+
+    /**
+     * how far the frame is between the last update and the next one, 0.0 to
+     * 1.0. renderers can blend the last two states by it for smooth motion
+     */
+    pub fn alpha(&self) -> f32 {
+        match self.fixed_dt.filter(|fixed_dt| *fixed_dt > 0.0) {
+            Some(fixed_dt) => self.accumulator / fixed_dt,
+            None => 1.0,
+        }
+    }
+
+    /**
+     * the last frame ran no update, so its input is still waiting
+     */
+    pub fn skipped(&self) -> bool {
+        self.skipped
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum FrameResult {
     Quit,
@@ -122,8 +583,493 @@ pub enum FrameResult {
 
 pub trait Renderer {
     fn draw(&mut self, world: &mut World) -> Option<u32>;
+
+    /**
+     * what the last `draw` sent to the gpu, for renderers that keep count
+     */
+    fn render_info(&self) -> Option<qp_gfx::RenderInfo> {
+        None
+    }
 }
 
 pub trait Controller {
     fn update(&mut self, world: &mut World) -> FrameResult;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{qp_ecs::components::CTag, EntityBuilder};
+    use crate::test_utils::registry;
+    use ::sdl2::keyboard::{Mod, Scancode};
+    use std::cell::RefCell;
+
+    struct Counter(Rc<Cell<u32>>);
+
+    impl Controller for Counter {
+        fn update(&mut self, world: &mut World) -> FrameResult {
+            self.0.set(self.0.get() + 1);
+            assert!(!world.frame_step.paused || world.delta == world.frame_step.step_delta);
+
+            FrameResult::None
+        }
+    }
+
+    struct Logger(&'static str, Rc<RefCell<Vec<&'static str>>>);
+
+    impl Controller for Logger {
+        fn update(&mut self, _world: &mut World) -> FrameResult {
+            self.1.borrow_mut().push(self.0);
+
+            FrameResult::None
+        }
+    }
+
+    struct DeltaLog(Rc<RefCell<Vec<(f32, usize)>>>);
+
+    impl Controller for DeltaLog {
+        fn update(&mut self, world: &mut World) -> FrameResult {
+            self.0.borrow_mut().push((world.delta, world.events.len()));
+
+            FrameResult::None
+        }
+    }
+
+    // takes the key presses, like a menu being typed into
+    struct Menu;
+
+    impl Controller for Menu {
+        fn update(&mut self, world: &mut World) -> FrameResult {
+            world.consume_events(|event| matches!(event, Event::KeyDown { .. }));
+
+            FrameResult::None
+        }
+    }
+
+    // polls W from the input, like the ship does
+    struct InputLog(Rc<RefCell<Vec<(bool, bool, bool)>>>);
+
+    impl Controller for InputLog {
+        fn update(&mut self, world: &mut World) -> FrameResult {
+            let input = &world.input;
+            self.0.borrow_mut().push((
+                input.was_pressed(Keycode::W),
+                input.is_key_down(Keycode::W) || input.is_scancode_down(Scancode::W),
+                input.was_released(Keycode::W),
+            ));
+
+            FrameResult::None
+        }
+    }
+
+    fn key_down(keycode: Keycode) -> Event {
+        Event::KeyDown {
+            timestamp: 0,
+            window_id: 0,
+            keycode: Some(keycode),
+            scancode: None,
+            keymod: Mod::NOMOD,
+            repeat: false,
+        }
+    }
+
+    fn frame(controllers: &mut [RegisteredController], world: &mut World, events: Vec<Event>) {
+        world.events = events;
+        world.frame_step.handle_events(&world.events);
+        update_controllers(controllers, world);
+    }
+
+    #[test]
+    fn frame_step_runs_once_per_step() {
+        let mut world = World::new(Viewport::headless(800, 600, None), 0).unwrap();
+        world.frame_step.toggle_key = Some(Keycode::F9);
+        world.frame_step.step_key = Some(Keycode::F10);
+
+        let runs = Rc::new(Cell::new(0));
+        let mut controllers = vec![RegisteredController::new(Counter(runs.clone()))];
+
+        frame(&mut controllers, &mut world, vec![]);
+        assert_eq!(runs.get(), 1);
+
+        // pausing stops updates
+        frame(&mut controllers, &mut world, vec![key_down(Keycode::F9)]);
+        frame(&mut controllers, &mut world, vec![]);
+        assert_eq!(runs.get(), 1);
+
+        frame(&mut controllers, &mut world, vec![key_down(Keycode::F10)]);
+        assert_eq!(runs.get(), 2);
+        frame(&mut controllers, &mut world, vec![]);
+        assert_eq!(runs.get(), 2);
+
+        // two step requests in one frame run two frames
+        frame(&mut controllers, &mut world, vec![key_down(Keycode::F10), key_down(Keycode::F10)]);
+        frame(&mut controllers, &mut world, vec![]);
+        frame(&mut controllers, &mut world, vec![]);
+        assert_eq!(runs.get(), 4);
+
+        // resuming runs every frame again
+        frame(&mut controllers, &mut world, vec![key_down(Keycode::F9)]);
+        frame(&mut controllers, &mut world, vec![]);
+        assert_eq!(runs.get(), 6);
+    }
+
+    #[test]
+    fn fixed_timestep_substeps() {
+        let fixed_dt = 1.0 / 64.0;
+        let mut fixed = FixedTimestep::new(fixed_dt, 5);
+
+        assert_eq!(fixed.advance(fixed_dt / 2.0), 0);
+        assert!(fixed.skipped());
+        assert_eq!(fixed.alpha(), 0.5);
+
+        assert_eq!(fixed.advance(fixed_dt / 2.0), 1);
+        assert!(!fixed.skipped());
+        assert_eq!(fixed.alpha(), 0.0);
+
+        assert_eq!(fixed.advance(fixed_dt * 3.0), 3);
+        assert_eq!(fixed.advance(fixed_dt * 4.5), 4);
+        assert_eq!(fixed.alpha(), 0.5);
+
+        // a long stall runs max_substeps and drops the rest
+        assert_eq!(fixed.advance(1.0), 5);
+        assert_eq!(fixed.alpha(), 0.5);
+        assert_eq!(fixed.advance(fixed_dt / 2.0), 1);
+
+        // off by default, once a frame whatever the delta
+        let mut variable = FixedTimestep::default();
+        assert_eq!(variable.advance(0.001), 1);
+        assert_eq!(variable.advance(1.0), 1);
+        assert_eq!(variable.alpha(), 1.0);
+    }
+
+    #[test]
+    fn fixed_timestep_runs_controllers_per_substep() {
+        let mut world = World::new(Viewport::headless(800, 600, None), 0).unwrap();
+        world.fixed_step = FixedTimestep::new(1.0 / 64.0, 5);
+
+        let log = Rc::new(RefCell::new(vec![]));
+        let mut controllers = vec![RegisteredController::new(DeltaLog(log.clone()))];
+
+        world.delta = 3.0 / 64.0;
+        world.events = vec![key_down(Keycode::A), key_down(Keycode::B)];
+        update_frame(&mut controllers, &mut world);
+
+        // the events go to the first update, and are back for the renderers
+        let fixed = 1.0 / 64.0;
+        assert_eq!(*log.borrow(), vec![(fixed, 2), (fixed, 0), (fixed, 0)]);
+        assert_eq!(world.events.len(), 2);
+
+        log.borrow_mut().clear();
+        world.delta = 1.0 / 128.0;
+        update_frame(&mut controllers, &mut world);
+        assert!(log.borrow().is_empty());
+        assert!(world.fixed_step.skipped());
+    }
+
+    #[test]
+    fn fixed_timestep_carries_skipped_events_to_controllers_only() {
+        let mut world = World::new(Viewport::headless(800, 600, None), 0).unwrap();
+        world.fixed_step = FixedTimestep::new(1.0 / 64.0, 5);
+
+        let log = Rc::new(RefCell::new(vec![]));
+        let mut controllers = vec![RegisteredController::new(DeltaLog(log.clone()))];
+
+        // too short a frame to update, its events are still drawn
+        world.delta = 1.0 / 128.0;
+        world.events = vec![key_down(Keycode::A)];
+        update_frame(&mut controllers, &mut world);
+        assert!(log.borrow().is_empty());
+        assert_eq!(world.events, vec![key_down(Keycode::A)]);
+
+        // the next update gets both frames' events, the renderers only the new one
+        world.events = vec![key_down(Keycode::B)];
+        update_frame(&mut controllers, &mut world);
+        assert_eq!(*log.borrow(), vec![(1.0 / 64.0, 2)]);
+        assert_eq!(world.events, vec![key_down(Keycode::B)]);
+
+        // and they're only carried once
+        log.borrow_mut().clear();
+        world.delta = 1.0 / 64.0;
+        world.events = vec![];
+        update_frame(&mut controllers, &mut world);
+        assert_eq!(*log.borrow(), vec![(1.0 / 64.0, 0)]);
+    }
+
+    #[test]
+    fn consumed_events_skip_later_controllers() {
+        let mut world = World::new(Viewport::headless(800, 600, None), 0).unwrap();
+
+        let log = Rc::new(RefCell::new(vec![]));
+        let mut controllers = vec![
+            RegisteredController::new(DeltaLog(log.clone())),
+            RegisteredController::new(Menu),
+            RegisteredController::new(DeltaLog(log.clone())),
+        ];
+
+        world.delta = 0.5;
+        let mouse = Event::MouseMotion {
+            timestamp: 0,
+            window_id: 0,
+            which: 0,
+            mousestate: ::sdl2::mouse::MouseState::from_sdl_state(0),
+            x: 10,
+            y: 20,
+            xrel: 0,
+            yrel: 0,
+        };
+        world.events = vec![key_down(Keycode::W), mouse.clone(), key_down(Keycode::A)];
+        let events = world.events.clone();
+        update_frame(&mut controllers, &mut world);
+
+        // the controller before the menu sees everything, the one after only the mouse
+        assert_eq!(*log.borrow(), vec![(0.5, 3), (0.5, 1)]);
+
+        // and the renderers get the frame back in order
+        assert_eq!(world.events, events);
+
+        // nothing carries over to the next frame
+        log.borrow_mut().clear();
+        world.events = vec![mouse];
+        update_frame(&mut controllers, &mut world);
+        assert_eq!(*log.borrow(), vec![(0.5, 1), (0.5, 1)]);
+        assert_eq!(world.events.len(), 1);
+    }
+
+    #[test]
+    fn consumed_events_mask_input() {
+        let mut world = World::new(Viewport::headless(800, 600, None), 0).unwrap();
+
+        let (before, after) = (Rc::new(RefCell::new(vec![])), Rc::new(RefCell::new(vec![])));
+        let mut controllers = vec![
+            RegisteredController::new(InputLog(before.clone())),
+            RegisteredController::new(Menu),
+            RegisteredController::new(InputLog(after.clone())),
+        ];
+        let w = |down: bool| {
+            let (keycode, scancode) = (Some(Keycode::W), Some(Scancode::W));
+            match down {
+                true => Event::KeyDown {
+                    timestamp: 0,
+                    window_id: 0,
+                    keycode,
+                    scancode,
+                    keymod: Mod::NOMOD,
+                    repeat: false,
+                },
+                false => Event::KeyUp {
+                    timestamp: 0,
+                    window_id: 0,
+                    keycode,
+                    scancode,
+                    keymod: Mod::NOMOD,
+                    repeat: false,
+                },
+            }
+        };
+        let mut frame = |controllers: &mut [RegisteredController], events: Vec<Event>| {
+            world.input.update(&events);
+            world.events = events;
+            update_frame(controllers, &mut world);
+        };
+
+        // the menu takes the press, so the controller after it doesn't see W
+        frame(&mut controllers, vec![w(true)]);
+        assert_eq!(*before.borrow(), vec![(true, true, false)]);
+        assert_eq!(*after.borrow(), vec![(false, false, false)]);
+
+        // and W stays hidden while it's held, up to its release
+        frame(&mut controllers, vec![]);
+        frame(&mut controllers, vec![w(false)]);
+        assert_eq!(after.borrow()[1..], [(false, false, false), (false, false, false)]);
+        assert_eq!(before.borrow()[1..], [(false, false, false), (false, false, false)]);
+
+        // with the menu closed the next press goes through
+        controllers.remove(1);
+        after.borrow_mut().clear();
+        frame(&mut controllers, vec![w(true)]);
+        frame(&mut controllers, vec![w(false)]);
+        assert_eq!(*after.borrow(), vec![(true, true, false), (false, false, true)]);
+    }
+
+    #[test]
+    fn frame_step_quits_while_paused() {
+        let mut world = World::new(Viewport::headless(800, 600, None), 0).unwrap();
+        world.frame_step.paused = true;
+        world.events = vec![Event::Quit { timestamp: 0 }];
+
+        assert_eq!(update_controllers(&mut [], &mut world), FrameResult::Quit);
+    }
+
+    #[test]
+    fn disabled_controllers_are_skipped() {
+        let mut world = World::new(Viewport::headless(800, 600, None), 0).unwrap();
+
+        let (hud, game) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+        let mut controllers = vec![
+            RegisteredController::new(Counter(hud.clone())),
+            RegisteredController::new(Counter(game.clone())),
+        ];
+        let handle = controllers[0].handle.clone();
+
+        handle.set_enabled(false);
+        frame(&mut controllers, &mut world, vec![]);
+        assert_eq!((hud.get(), game.get()), (0, 1));
+
+        handle.set_enabled(true);
+        frame(&mut controllers, &mut world, vec![]);
+        assert_eq!((hud.get(), game.get()), (1, 2));
+    }
+
+    fn tagged(world: &World) -> usize {
+        world.registry.entity_manager.query_all::<CTag>().len()
+    }
+
+    // deletes every tagged entity, which is still whole afterwards
+    struct Despawner;
+
+    impl Controller for Despawner {
+        fn update(&mut self, world: &mut World) -> FrameResult {
+            let em = &mut world.registry.entity_manager;
+            for entity in em.query_all::<CTag>() {
+                em.set_to_delete(entity);
+
+                assert!(em.is_pending_delete(&entity));
+                assert!(em.get::<CTag>(&entity).is_some());
+            }
+
+            FrameResult::None
+        }
+    }
+
+    struct Watcher(Rc<Cell<usize>>);
+
+    impl Controller for Watcher {
+        fn update(&mut self, world: &mut World) -> FrameResult {
+            self.0.set(tagged(world));
+
+            FrameResult::None
+        }
+    }
+
+    impl Renderer for Watcher {
+        fn draw(&mut self, world: &mut World) -> Option<u32> {
+            self.0.set(tagged(world));
+
+            Some(0)
+        }
+    }
+
+    #[test]
+    fn deletions_apply_between_update_and_render() {
+        let mut world = World::new(Viewport::headless(800, 600, None), 0).unwrap();
+        let bullet = EntityBuilder::create(&mut world.registry.entity_manager)
+            .with(CTag {
+                tag: "bullet".into(),
+            })
+            .build();
+
+        let (updated, drawn) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+        let mut controllers = vec![
+            RegisteredController::new(Despawner),
+            RegisteredController::new(Watcher(updated.clone())),
+        ];
+        let mut graph = RenderGraph::new();
+        graph
+            .add_pass("watcher", RenderGraph::WORLD, Watcher(drawn.clone()))
+            .unwrap();
+
+        // controllers after the one that deleted it still see all of it
+        world.flush();
+        update_frame(&mut controllers, &mut world);
+        assert_eq!(updated.get(), 1);
+
+        // but it is gone before anything is drawn
+        assert!(world.registry.entity_manager.entity_id(&bullet).is_none());
+        assert!(!world.registry.entity_manager.is_pending_delete(&bullet));
+        graph.draw(&mut world);
+        assert_eq!(drawn.get(), 0);
+
+        // and stays gone the next frame
+        world.flush();
+        update_frame(&mut controllers, &mut world);
+        assert_eq!(updated.get(), 0);
+    }
+
+    #[test]
+    fn deletions_wait_for_the_frame() {
+        let mut registry = registry();
+
+        let bullet = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTag {
+                tag: "bullet".into(),
+            })
+            .build();
+        registry.entity_manager.set_to_delete(bullet);
+
+        // marked entities keep their components until deletions are applied
+        assert_eq!(
+            registry.entity_manager.get::<CTag>(&bullet).unwrap().tag,
+            "bullet"
+        );
+        assert_eq!(registry.entity_manager.query_all::<CTag>(), vec![bullet]);
+
+        registry.update_deletions();
+        assert!(registry.entity_manager.get::<CTag>(&bullet).is_none());
+        assert!(registry.entity_manager.query_all::<CTag>().is_empty());
+    }
+
+    #[test]
+    fn replaced_controller_runs_in_place() {
+        let mut world = World::new(Viewport::headless(800, 600, None), 0).unwrap();
+
+        let log = Rc::new(RefCell::new(vec![]));
+        let logger = |name| Logger(name, log.clone());
+
+        let mut controllers = vec![];
+        register_named(&mut controllers, "input", logger("input")).unwrap();
+        let handle = register_named(&mut controllers, "ship", logger("ship v1")).unwrap();
+        register_named(&mut controllers, "hud", logger("hud")).unwrap();
+        assert!(register_named(&mut controllers, "ship", logger("ship")).is_err());
+
+        frame(&mut controllers, &mut world, vec![]);
+        replace_named(&mut controllers, "ship", logger("ship v2")).unwrap();
+        frame(&mut controllers, &mut world, vec![]);
+        assert_eq!(
+            *log.borrow(),
+            vec!["input", "ship v1", "hud", "input", "ship v2", "hud"]
+        );
+
+        // the old handle switches the new controller
+        handle.set_enabled(false);
+        log.borrow_mut().clear();
+        frame(&mut controllers, &mut world, vec![]);
+        assert_eq!(*log.borrow(), vec!["input", "hud"]);
+
+        assert!(replace_named(&mut controllers, "boss", logger("boss")).is_err());
+    }
+
+    #[test]
+    fn run_keeps_clear_config_set_up_before_it() {
+        let color = (0.1, 0.2, 0.3, 1.0);
+
+        let mut config = ClearConfig::default();
+        apply_clear_color(&mut config, color);
+        assert_eq!(config.color, Some(color));
+        assert!(config.clear_depth);
+
+        // trails set up by a controller's constructor
+        let mut trails = ClearConfig {
+            color: None,
+            ..ClearConfig::default()
+        };
+        apply_clear_color(&mut trails, color);
+        assert_eq!(trails.color, None);
+
+        let mut red = ClearConfig {
+            color: Some((1.0, 0.0, 0.0, 1.0)),
+            ..ClearConfig::default()
+        };
+        apply_clear_color(&mut red, color);
+        assert_eq!(red.color, Some((1.0, 0.0, 0.0, 1.0)));
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{
+    prelude::{qp_core::to_abs_path, qp_ecs::Component, QPError},
+    QPResult,
+};
+use rodio::Decoder;
+use std::{fs, io::Cursor, sync::Arc};
+
+/**
+* a sound clip (wav, ogg, flac or mp3) kept in memory, so it can be played
+* over and over without going back to the disk. see `AudioBackend::play_asset`
+*/
+#[derive(Debug, Component, PartialEq, Clone)]
+pub struct RAudio {
+    bytes: Arc<[u8]>,
+}
+
+impl RAudio {
+    /**
+     * path is relative to the executable. a missing file or one that can't
+     * be decoded is an error here, not when it's played
+     */
+    pub fn from_file(path: &str) -> QPResult<Self> {
+        Self::from_bytes(fs::read(to_abs_path(path)?)?)
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> QPResult<Self> {
+        let clip = Self {
+            bytes: bytes.into(),
+        };
+        clip.decoder()?;
+
+        Ok(clip)
+    }
+
+    /**
+     * a fresh decoder over the clip, to hand to a sink
+     */
+    pub fn decoder(&self) -> QPResult<Decoder<Cursor<Arc<[u8]>>>> {
+        Decoder::new(Cursor::new(self.bytes.clone()))
+            .map_err(|e| QPError::AudioError(e.to_string()))
+    }
+}
@@ -1,13 +1,22 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    prelude::qp_ecs::{
-        components::{CTransform, CTransform2D},
-        Component,
+    prelude::{
+        qp_ecs::{
+            components::{CTransform, CTransform2D},
+            Component,
+        },
+        qp_physics::{frame_targets, Aabb, CameraFraming},
     },
     QPResult,
 };
 
+/**
+ * the smallest zoom a camera will take. anything at or below zero would flip
+ * or collapse the projection
+ */
+pub const MIN_ZOOM: f32 = 0.001;
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
 pub struct OrthographicCameraParams {
     pub left: f32,
@@ -31,6 +40,20 @@ impl Default for OrthographicCameraParams {
     }
 }
 
+/**
+ * how a 2D camera projects the world. perspective is framed so that z = 0
+ * covers the same area as the orthographic params, which keeps sprites in
+ * place when switching
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum CameraProjection {
+    #[default]
+    Orthographic,
+    Perspective {
+        fov: f32,
+    },
+}
+
 #[derive(Debug, Component, Serialize, Deserialize, PartialEq)]
 pub struct RCamera2D {
     pub projection: glm::Mat4,
@@ -39,6 +62,9 @@ pub struct RCamera2D {
     pub params: OrthographicCameraParams,
     pub zoom: f32,
     pub transform: CTransform2D,
+
+    #[serde(default)]
+    pub projection_type: CameraProjection,
 }
 
 impl Default for RCamera2D {
@@ -52,6 +78,7 @@ impl Default for RCamera2D {
             params,
             zoom: 1.0,
             transform,
+            projection_type: CameraProjection::Orthographic,
         };
 
         camera.projection = camera.calc_projection_matrix();
@@ -69,6 +96,7 @@ impl RCamera2D {
             params,
             zoom,
             transform,
+            projection_type: CameraProjection::Orthographic,
         };
 
         camera.projection = camera.calc_projection_matrix();
@@ -77,25 +105,96 @@ impl RCamera2D {
         camera
     }
 
+    /**
+     * zoom is clamped to at least MIN_ZOOM
+     */
     pub fn set_zoom(&mut self, zoom: f32) {
-        self.zoom = zoom;
+        self.zoom = zoom.max(MIN_ZOOM);
 
         self.projection = self.calc_projection_matrix();
     }
 
-    pub fn calc_projection_matrix(&self) -> glm::Mat4 {
+    /**
+     * switches between orthographic and perspective at runtime. the view
+     * matrix is left alone
+     */
+    pub fn set_projection(&mut self, projection_type: CameraProjection) {
+        self.projection_type = projection_type;
+
+        self.projection = self.calc_projection_matrix();
+    }
+
+    /**
+     * the params shrunk (or grown) around their center by zoom
+     */
+    pub fn zoomed_params(&self) -> OrthographicCameraParams {
         let params = self.params;
+        let half_width = (params.right - params.left) / (2.0 * self.zoom);
+        let half_height = (params.top - params.bottom) / (2.0 * self.zoom);
+        let center = self.frame_center();
+
+        OrthographicCameraParams {
+            left: center.x - half_width,
+            right: center.x + half_width,
+            bottom: center.y - half_height,
+            top: center.y + half_height,
+            ..params
+        }
+    }
+
+    /**
+     * the middle of the frame relative to the camera's translation
+     */
+    pub fn frame_center(&self) -> glm::Vec2 {
+        glm::vec2(
+            (self.params.left + self.params.right) / 2.0,
+            (self.params.bottom + self.params.top) / 2.0,
+        )
+    }
 
-        glm::ortho(
-            params.left,
-            params.right,
-            params.bottom,
-            params.top,
-            params.near,
-            params.far,
+    /**
+     * the part of the world the camera shows, zoom included
+     */
+    pub fn visible_bounds(&self) -> Aabb {
+        let params = self.zoomed_params();
+        let translate = self.transform.translate;
+
+        Aabb::new(
+            glm::vec2(params.left, params.bottom) + translate,
+            glm::vec2(params.right, params.top) + translate,
         )
     }
 
+    pub fn calc_projection_matrix(&self) -> glm::Mat4 {
+        let params = self.zoomed_params();
+
+        match self.projection_type {
+            CameraProjection::Orthographic => glm::ortho(
+                params.left,
+                params.right,
+                params.bottom,
+                params.top,
+                params.near,
+                params.far,
+            ),
+            CameraProjection::Perspective { fov } => {
+                let width = params.right - params.left;
+                let height = params.top - params.bottom;
+
+                // back off far enough that the z = 0 plane fills the frame
+                let distance = (height / 2.0) / (fov / 2.0).tan();
+                let center = glm::vec3(
+                    params.left + width / 2.0,
+                    params.bottom + height / 2.0,
+                    distance,
+                );
+
+                glm::perspective(width / height, fov, distance * 0.1, distance * 2.0)
+                    * glm::translation(&-center)
+            }
+        }
+    }
+
     pub fn calc_view_matrix(&self) -> glm::Mat4 {
         let position = glm::vec3(self.transform.translate.x, self.transform.translate.y, 0.0);
 
@@ -116,6 +215,57 @@ impl RCamera2D {
         self.view = self.calc_view_matrix();
     }
 
+    /**
+     * moves and zooms towards showing every transform, with at least padding
+     * world units between them and the edge of the frame. smoothing is how
+     * far to go on this call, 1.0 snaps straight there
+     *
+     * one transform, or several all in the same place, are centered on at
+     * the current zoom
+     */
+    pub fn frame_entities(&mut self, transforms: &[CTransform2D], padding: f32, smoothing: f32) {
+        let targets = transforms
+            .iter()
+            .map(|transform| (transform.translate, 0.0))
+            .collect::<Vec<_>>();
+        let Some((first, _)) = targets.first() else {
+            return;
+        };
+
+        let framing = CameraFraming {
+            padding,
+            min_zoom: MIN_ZOOM,
+            max_zoom: f32::MAX,
+            lerp_factor: smoothing,
+        };
+        let size = glm::vec2(
+            self.params.right - self.params.left,
+            self.params.top - self.params.bottom,
+        );
+        let Some((center, zoom)) = frame_targets(&targets, size, &framing) else {
+            return;
+        };
+
+        let spread = targets.iter().any(|(pos, _)| pos != first);
+        let zoom = match spread {
+            true => zoom,
+            false => self.zoom,
+        };
+
+        self.move_to_frame(center, zoom, smoothing);
+    }
+
+    /**
+     * lerps towards having center in the middle of the frame at zoom
+     */
+    pub fn move_to_frame(&mut self, center: glm::Vec2, zoom: f32, lerp_factor: f32) {
+        let translate = center - self.frame_center();
+        self.transform.translate = glm::lerp(&self.transform.translate, &translate, lerp_factor);
+        self.set_zoom(self.zoom + (zoom - self.zoom) * lerp_factor);
+
+        self.view = self.calc_view_matrix();
+    }
+
     // pub fn params(&self) -> OrthographicCameraParams {
     //     let zoom_x = (self.params.right - self.params.left) / self.zoom;
     //     let zoom_y = (self.params.top - self.params.bottom) / self.zoom;
@@ -225,3 +375,109 @@ impl RCamera3D {
         self.params.up = glm::normalize(&glm::cross(&self.params.right, &self.params.front));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::transform_at;
+
+    #[test]
+    fn camera_2d_switch_projection() {
+        let mut camera = RCamera2D::new(
+            OrthographicCameraParams::default(),
+            1.0,
+            CTransform2D {
+                translate: glm::vec2(30.0, 40.0),
+                ..CTransform2D::default()
+            },
+        );
+        let ortho = camera.projection;
+        let view = camera.view;
+
+        camera.set_projection(CameraProjection::Perspective {
+            fov: glm::quarter_pi(),
+        });
+        assert_ne!(camera.projection, ortho);
+        assert_eq!(camera.view, view);
+
+        // the corners of the ortho frame stay put at z = 0
+        for corner in [glm::vec4(0.0, 0.0, 0.0, 1.0), glm::vec4(800.0, 600.0, 0.0, 1.0)] {
+            let expected = ortho * corner;
+            let clip = camera.projection * corner;
+            let ndc = clip.xy() / clip.w;
+
+            assert!((ndc - expected.xy()).magnitude() < 1e-4);
+        }
+
+        camera.set_projection(CameraProjection::Orthographic);
+        assert_eq!(camera.projection, ortho);
+        assert_eq!(camera.view, view);
+    }
+
+    #[test]
+    fn camera_2d_frame_entities() {
+        let mut camera = RCamera2D::default();
+
+        let spread = [
+            transform_at(-500.0, 20.0),
+            transform_at(1200.0, -300.0),
+            transform_at(64.0, 900.0),
+            transform_at(0.0, 0.0),
+        ];
+        let padding = 25.0;
+        camera.frame_entities(&spread, padding, 1.0);
+
+        let bounds = camera.visible_bounds();
+        for transform in spread.iter() {
+            let pos = transform.translate;
+            assert!(bounds.contains(&(pos - glm::vec2(padding, padding))));
+            assert!(bounds.contains(&(pos + glm::vec2(padding, padding))));
+        }
+
+        // the widest spread just fits
+        let width = bounds.max.x - bounds.min.x;
+        assert!((width - (1700.0 + padding * 2.0)).abs() < 1e-2);
+
+        // smoothing only goes part of the way
+        let mut smoothed = RCamera2D::default();
+        smoothed.frame_entities(&spread, padding, 0.5);
+        assert_eq!(smoothed.zoom, (1.0 + camera.zoom) / 2.0);
+
+        // a single point is centered without zooming, as are stacked ones
+        for points in [
+            vec![transform_at(40.0, 60.0)],
+            vec![transform_at(40.0, 60.0); 3],
+        ] {
+            let mut camera = RCamera2D::default();
+            camera.frame_entities(&points, 0.0, 1.0);
+
+            assert_eq!(camera.zoom, 1.0);
+            let middle = camera.transform.translate + camera.frame_center();
+            assert_eq!(middle, glm::vec2(40.0, 60.0));
+        }
+
+        // nothing to frame leaves the camera alone
+        let mut camera = RCamera2D::default();
+        camera.frame_entities(&[], 10.0, 1.0);
+        assert_eq!(camera, RCamera2D::default());
+    }
+
+    #[test]
+    fn camera_2d_zoom_stays_positive() {
+        for zoom in [0.0, -2.0, f32::MIN_POSITIVE] {
+            let mut camera = RCamera2D::default();
+            camera.set_zoom(zoom);
+
+            assert_eq!(camera.zoom, MIN_ZOOM);
+            assert!(camera.projection.iter().all(|v| v.is_finite()));
+
+            let bounds = camera.visible_bounds();
+            assert!(bounds.min.x < bounds.max.x);
+            assert!(bounds.min.y < bounds.max.y);
+        }
+
+        let mut camera = RCamera2D::default();
+        camera.set_zoom(2.5);
+        assert_eq!(camera.zoom, 2.5);
+    }
+}
@@ -1,6 +1,8 @@
 use crate::core::prelude::to_abs_path;
 use crate::platform::opengl::textures::{ParameterName, ParameterValue};
 use crate::prelude::qp_ecs::Component;
+use crate::prelude::qp_gfx::{measure_glyphs, GlyphMetrics, QPText, QPTextStyle};
+use crate::asset_manager::AssetManager;
 use crate::platform::opengl::{
     pixel_store,
     textures::{
@@ -10,6 +12,7 @@ use crate::platform::opengl::{
     }
 };
 use crate::QPResult;
+use std::collections::HashMap;
 use ft::{
     face::LoadFlag,
     Face,
@@ -17,28 +20,60 @@ use ft::{
 
 use super::RTexture;
 
-const CHARACTER_COUNT: usize = 128;
+const CHARACTER_COUNT: u32 = 128;
+
+/**
+* drawn in place of a character that no font in the chain has. the first one
+* the chain has is used
+*/
+pub const REPLACEMENT_CHARACTERS: [char; 2] = ['\u{FFFD}', '?'];
 
 #[derive(Debug, Component, PartialEq)]
 pub struct RFont {
     // pub texture: Texture,
-    pub characters: Vec<Character>
+    pub characters: HashMap<char, Character>,
+
+    /**
+    * asset ids of the fonts to try, in order, for characters this font
+    * doesn't have. their own fallbacks are tried after them
+    */
+    pub fallbacks: Vec<u64>,
 }
 
 impl RFont {
+    /**
+    * loads the ascii characters
+    */
     pub fn new(font: &str) -> QPResult<RFont> {
+        Self::with_characters(font, "")
+    }
+
+    /**
+    * loads the ascii characters plus every character in extra, for fonts
+    * used as a fallback for other scripts or symbols
+    */
+    pub fn with_characters(font: &str, extra: &str) -> QPResult<RFont> {
         let font = to_abs_path(&format!("assets/fonts/{font}.ttf"))?;
         let library = ft::Library::init()?;
         let face = library.new_face(font, 0)?;
 
         pixel_store::set_unpack_alignment(1);
 
-        let mut characters = Vec::<Character>::with_capacity(CHARACTER_COUNT);
+        let mut characters = HashMap::<char, Character>::new();
+        let chars = (0..CHARACTER_COUNT)
+            .filter_map(char::from_u32)
+            .chain(extra.chars())
+            .chain(REPLACEMENT_CHARACTERS);
+
+        for c in chars {
+            // freetype would otherwise give back the font's "missing" glyph
+            if characters.contains_key(&c) || face.get_char_index(c as usize).is_none() {
+                continue
+            }
 
-        for c in 0..CHARACTER_COUNT {
             face.set_char_size(40 * 64, 0, 96, 0)?;
 
-            if let Err(_e) = face.load_char(c, LoadFlag::RENDER) {
+            if let Err(_e) = face.load_char(c as usize, LoadFlag::RENDER) {
                 #[cfg(debug_assertions)]
                 println!("{}", _e);
     
@@ -66,16 +101,67 @@ impl RFont {
                 advance_x: face.glyph().advance().x,
                 advance_y: face.glyph().advance().y,
             };
-    
-            if char::from_u32(c as u32).is_some() {
-                characters.push(m_char);
-            }
+
+            characters.insert(c, m_char);
         }
 
         Ok(Self {
-            characters
+            characters,
+            fallbacks: vec![],
         })
     }
+
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.characters.contains_key(&c)
+    }
+
+    /**
+    * finds the glyph to draw for c, starting with the font with asset id
+    * font and walking its fallbacks. characters no font has are drawn as one
+    * of the `REPLACEMENT_CHARACTERS` instead of being skipped
+    */
+    pub fn find_glyph(
+        asset_manager: &AssetManager,
+        font: u64,
+        c: char
+    ) -> Option<&Character> {
+        let fonts = |id| asset_manager.get::<RFont>(id);
+        let (font, c) = resolve_glyph(font, c, fonts)?;
+
+        fonts(font)?.characters.get(&c)
+    }
+
+    /**
+    * the width and height of text drawn in style with this font's glyphs,
+    * laid out the way the text renderer does it: split into lines at '\n',
+    * wrapped at the style's max_width and every line one line height tall.
+    * characters the font doesn't have are measured as the replacement it
+    * would draw. fallbacks aren't looked at, `measure_glyphs` with
+    * `glyph_metrics` measures through the whole chain
+    */
+    pub fn measure(&self, text: &str, style: &QPTextStyle) -> glm::Vec2 {
+        measure_str(text, style, |c| self.metrics(style.font, c))
+    }
+
+    // the metrics of c, or of the replacement drawn for it, as font's
+    fn metrics(&self, font: u64, c: char) -> Option<GlyphMetrics> {
+        let fonts = |id| (id == font).then_some(self);
+        let (_, glyph) = resolve_glyph(font, c, fonts)?;
+
+        self.characters
+            .get(&glyph)
+            .map(|ch| ch.metrics(font, glyph))
+    }
+}
+
+impl GlyphSet for RFont {
+    fn has_glyph(&self, c: char) -> bool {
+        self.has_glyph(c)
+    }
+
+    fn fallbacks(&self) -> &[u64] {
+        &self.fallbacks
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -87,8 +173,84 @@ pub struct Character {
     pub advance_y: i32,
 }
 
+impl Character {
+    /**
+    * what the text layout needs to place this character, drawn as glyph
+    * from the font with asset id font
+    */
+    pub fn metrics(&self, font: u64, glyph: char) -> GlyphMetrics {
+        GlyphMetrics {
+            bearing: self.bearing,
+            size: self.size,
+            advance: (self.advance_x >> 6) as f32,
+            font,
+            glyph,
+            texture: self.texture.texture.id,
+        }
+    }
+}
+
+pub(crate) trait GlyphSet {
+    fn has_glyph(&self, c: char) -> bool;
+    fn fallbacks(&self) -> &[u64];
+}
+
+/**
+* the font id and character to draw for c, see `RFont::find_glyph`
+*/
+pub(crate) fn resolve_glyph<'a, F: GlyphSet + 'a>(
+    font: u64,
+    c: char,
+    fonts: impl Fn(u64) -> Option<&'a F>
+) -> Option<(u64, char)> {
+    // the whole chain, depth first, each font once so cycles are fine
+    let mut chain = vec![];
+    let mut stack = vec![font];
+    while let Some(id) = stack.pop() {
+        if chain.contains(&id) {
+            continue
+        }
+
+        let Some(set) = fonts(id) else {
+            continue
+        };
+
+        chain.push(id);
+        stack.extend(set.fallbacks().iter().rev());
+    }
+
+    // control characters take no room rather than showing a replacement
+    let replacements = match c.is_control() {
+        true => &[][..],
+        false => &REPLACEMENT_CHARACTERS[..],
+    };
+
+    std::iter::once(c)
+        .chain(replacements.iter().copied())
+        .find_map(|c| {
+            chain
+                .iter()
+                .find(|id| fonts(**id).is_some_and(|set| set.has_glyph(c)))
+                .map(|id| (*id, c))
+        })
+}
+
 // helpers
 
+fn measure_str(
+    text: &str,
+    style: &QPTextStyle,
+    metrics: impl Fn(char) -> Option<GlyphMetrics>
+) -> glm::Vec2 {
+    let text = QPText {
+        text: text.into(),
+        style: style.clone(),
+        ..QPText::default()
+    };
+
+    measure_glyphs(&text, |_, c| metrics(c))
+}
+
 fn texture_from_font(
     face: &Face,
     width: i32,
@@ -118,4 +280,82 @@ fn texture_from_font(
         );
 
     texture
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestFont {
+        glyphs: &'static str,
+        fallbacks: Vec<u64>,
+    }
+
+    impl GlyphSet for TestFont {
+        fn has_glyph(&self, c: char) -> bool {
+            self.glyphs.contains(c)
+        }
+
+        fn fallbacks(&self) -> &[u64] {
+            &self.fallbacks
+        }
+    }
+
+    #[test]
+    fn font_missing_glyph_uses_fallback() {
+        let fonts = HashMap::from([
+            (1, TestFont { glyphs: "abc?", fallbacks: vec![2, 3] }),
+            (2, TestFont { glyphs: "é", fallbacks: vec![1] }),
+            (3, TestFont { glyphs: "\u{FFFD}字", fallbacks: vec![] }),
+        ]);
+        let resolve = |font, c| resolve_glyph(font, c, |id| fonts.get(&id));
+
+        assert_eq!(resolve(1, 'a'), Some((1, 'a')));
+        assert_eq!(resolve(1, 'é'), Some((2, 'é')));
+        assert_eq!(resolve(1, '字'), Some((3, '字')));
+
+        // nothing has it, so the first replacement anywhere in the chain
+        assert_eq!(resolve(1, 'ß'), Some((3, '\u{FFFD}')));
+        assert_eq!(resolve(2, 'ß'), Some((3, '\u{FFFD}')));
+
+        // the cycle between 1 and 2 ends, and 2 reaches 1's glyphs
+        assert_eq!(resolve(2, 'b'), Some((1, 'b')));
+
+        assert_eq!(resolve(1, '\n'), None);
+        assert_eq!(resolve(4, 'a'), None);
+    }
+
+    #[test]
+    fn font_measure_scales_with_style() {
+        // every glyph is 10px wide and 20px tall
+        let metrics = |glyph| Some(GlyphMetrics {
+            bearing: glm::vec2(0.0, 20.0),
+            size: glm::vec2(10.0, 20.0),
+            advance: 10.0,
+            font: 0,
+            glyph,
+            texture: 0,
+        });
+        let measure = |text, scale| {
+            let style = QPTextStyle {
+                scale,
+                ..QPTextStyle::default()
+            };
+
+            measure_str(text, &style, metrics)
+        };
+
+        let size = measure("Game Over", 1.0);
+        assert_eq!(size.x, 90.0);
+        assert_eq!(measure("Game Over", 0.5), size * 0.5);
+        assert_eq!(measure("Game Over", 2.0), size * 2.0);
+
+        // the widest line wins, and every line adds a line height
+        let lines = measure("Press Enter\nto\nstart again", 1.0);
+        assert_eq!(lines.x, 110.0);
+        assert_eq!(lines.y, size.y * 3.0);
+
+        assert_eq!(measure("", 2.0), glm::vec2(0.0, 0.0));
+    }
+}
@@ -1,11 +1,13 @@
+pub mod audio;
 pub mod camera;
 pub mod font;
 pub mod shader;
 pub mod texture;
 pub mod tilemap;
 
+pub use audio::RAudio;
 pub use camera::RCamera2D;
-pub use font::RFont;
+pub use font::{Character, RFont, REPLACEMENT_CHARACTERS};
 pub use shader::RShader;
 pub use texture::RTexture;
 pub use texture::RTextureAtlas;
@@ -1,5 +1,9 @@
-use crate::{prelude::qp_ecs::Component, QPResult};
+use crate::{
+    prelude::{qp_core::to_abs_path, qp_ecs::Component, QPError},
+    QPResult,
+};
 use serde::{Deserialize, Serialize};
+use std::fs;
 
 #[derive(Debug, Component, PartialEq, Clone, Serialize, Deserialize)]
 pub struct RTileMap {
@@ -47,6 +51,108 @@ impl RTileMap {
     pub fn is_valid(&self, tile: &glm::Vec2) -> bool {
         tile.x < self.columns as f32 && tile.x >= 0.0 && tile.y < self.rows as f32 && tile.y >= 0.0
     }
+
+    /**
+     * saves the whole map in binary. path is relative to the executable
+     */
+    pub fn save(&self, path: &str) -> QPResult<()> {
+        let bytes = bincode::serialize(self).map_err(|e| QPError::Generic(e.to_string()))?;
+
+        fs::write(to_abs_path(path)?, bytes)?;
+
+        Ok(())
+    }
+
+    /**
+     * loads a map written by `save`. path is relative to the executable
+     */
+    pub fn load(path: &str) -> QPResult<Self> {
+        let bytes = fs::read(to_abs_path(path)?)?;
+        let map: Self =
+            bincode::deserialize(&bytes).map_err(|e| QPError::Generic(e.to_string()))?;
+
+        if map.data.len() != map.columns * map.rows {
+            return Err(QPError::Generic(format!(
+                "tile map has {} tiles but is {}x{}",
+                map.data.len(),
+                map.columns,
+                map.rows
+            )));
+        }
+
+        Ok(map)
+    }
+
+    /**
+     * one line per row of tile values. the top row (the highest y) comes
+     * first, so the csv looks like the map does on screen
+     */
+    pub fn to_csv(&self) -> String {
+        (0..self.rows)
+            .rev()
+            .map(|y| {
+                (0..self.columns)
+                    .map(|x| self.data[x * self.rows + y].to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /**
+     * the inverse of `to_csv`. the tile size isn't part of the csv. every row
+     * needs the same number of values
+     */
+    pub fn from_csv(csv: &str, tile_size: glm::Vec2) -> QPResult<Self> {
+        let lines = csv
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                line.split(',')
+                    .map(|value| {
+                        value.trim().parse::<u16>().map_err(|e| {
+                            QPError::Generic(format!("bad tile value {}: {}", value, e))
+                        })
+                    })
+                    .collect::<QPResult<Vec<_>>>()
+            })
+            .collect::<QPResult<Vec<_>>>()?;
+
+        let rows = lines.len();
+        let columns = lines.first().map_or(0, |line| line.len());
+        if lines.iter().any(|line| line.len() != columns) {
+            return Err(QPError::Generic(
+                "every row of a tile map csv needs the same number of values".into(),
+            ));
+        }
+
+        let mut data = Vec::with_capacity(columns * rows);
+        for x in 0..columns {
+            for line in lines.iter().rev() {
+                data.push(line[x]);
+            }
+        }
+
+        Self::new(columns, rows, data, tile_size)
+    }
+
+    /**
+     * path is relative to the executable
+     */
+    pub fn save_csv(&self, path: &str) -> QPResult<()> {
+        fs::write(to_abs_path(path)?, self.to_csv())?;
+
+        Ok(())
+    }
+
+    /**
+     * path is relative to the executable
+     */
+    pub fn load_csv(path: &str, tile_size: glm::Vec2) -> QPResult<Self> {
+        Self::from_csv(&fs::read_to_string(to_abs_path(path)?)?, tile_size)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -54,3 +160,44 @@ pub enum ValidTile<T> {
     Invalid,
     Valid(T),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3 columns, 2 rows, stored a column at a time from the bottom
+    fn map() -> RTileMap {
+        RTileMap::new(3, 2, vec![1, 4, 2, 5, 3, 600], glm::vec2(32.0, 16.0)).unwrap()
+    }
+
+    #[test]
+    fn tile_map_save_load_round_trip() {
+        let path = std::env::temp_dir().join("quipi_tile_map.bin");
+        let path = path.to_string_lossy();
+
+        let map = map();
+        map.save(&path).unwrap();
+        let loaded = RTileMap::load(&path).unwrap();
+
+        assert_eq!(loaded, map);
+        assert_eq!(
+            loaded.get_tile_value(glm::vec2(2.0, 1.0)),
+            ValidTile::Valid(600)
+        );
+    }
+
+    #[test]
+    fn tile_map_csv_round_trip() {
+        let map = map();
+        let csv = map.to_csv();
+        assert_eq!(csv, "4,5,600\n1,2,3");
+
+        let path = std::env::temp_dir().join("quipi_tile_map.csv");
+        let path = path.to_string_lossy();
+        map.save_csv(&path).unwrap();
+        assert_eq!(RTileMap::load_csv(&path, map.tile_size).unwrap(), map);
+
+        assert!(RTileMap::from_csv("1,2\n3", map.tile_size).is_err());
+        assert!(RTileMap::from_csv("1,x", map.tile_size).is_err());
+    }
+}
@@ -1,5 +1,8 @@
 pub mod assets;
 mod loaders;
+mod queue;
+
+pub use queue::{LoadProgress, LoadingState};
 
 use std::{
     cell::RefCell,
@@ -19,6 +22,7 @@ use crate::{
 pub struct AssetManager {
     asset_store: EntityManager,
     asset_map: HashMap<u64, VersionedIndex>,
+    queue: queue::AssetQueue,
 
     strings: Weak<RefCell<StringInterner>>,
 }
@@ -28,11 +32,13 @@ impl AssetManager {
         let mut manager = Self {
             asset_store: EntityManager::new()?,
             asset_map: HashMap::new(),
+            queue: queue::AssetQueue::default(),
             strings,
         };
 
         manager
             .asset_store
+            .register_component::<assets::RAudio>()
             .register_component::<assets::RFont>()
             .register_component::<assets::RShader>()
             .register_component::<assets::RCamera2D>()
@@ -67,6 +73,41 @@ impl AssetManager {
         Ok(id)
     }
 
+    /**
+     * queue an asset to be loaded later by `load_queued`. the loader runs on
+     * the main thread, so it can create gl resources
+     */
+    pub fn queue_asset<A: Component + std::fmt::Debug + PartialEq + 'static>(
+        &mut self,
+        name: &str,
+        loader: impl FnOnce() -> QPResult<A> + 'static,
+    ) {
+        let name = name.to_string();
+
+        self.queue
+            .push(Box::new(move |manager| manager.load_asset(&name, loader()?)));
+    }
+
+    /**
+     * load up to `count` queued assets. a loader that fails still counts
+     * towards the progress, its error is returned
+     */
+    pub fn load_queued(&mut self, count: usize) -> QPResult<LoadingState> {
+        for _ in 0..count {
+            let Some(loader) = self.queue.pop() else {
+                break;
+            };
+
+            loader(self)?;
+        }
+
+        Ok(self.load_progress().into())
+    }
+
+    pub fn load_progress(&self) -> LoadProgress {
+        self.queue.progress()
+    }
+
     pub fn unload_asset<A: Component + std::fmt::Debug + PartialEq + 'static>(&mut self, id: u64) {
         if let Some(index) = self.asset_map.get(&id) {
             self.asset_store.remove::<A>(index);
@@ -132,3 +173,45 @@ impl AssetManager {
         Some(string_interner)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::qp_assets::RTileMap;
+
+    fn tilemap() -> QPResult<RTileMap> {
+        RTileMap::new(2, 2, vec![0, 1, 2, 3], glm::vec2(16.0, 16.0))
+    }
+
+    #[test]
+    fn asset_manager_queued_progress() {
+        let strings = Rc::new(RefCell::new(StringInterner::new()));
+        let mut manager = AssetManager::init(Rc::downgrade(&strings)).unwrap();
+
+        assert_eq!(manager.load_progress().fraction(), 1.0);
+
+        for name in ["map_a", "map_b", "map_c", "map_d"] {
+            manager.queue_asset(name, tilemap);
+        }
+
+        assert_eq!(manager.load_progress(), LoadProgress { loaded: 0, total: 4 });
+        assert_eq!(manager.load_progress().fraction(), 0.0);
+        assert!(manager.get_asset_id("map_a").is_none());
+
+        let state = manager.load_queued(1).unwrap();
+        assert_eq!(state, LoadingState::Loading(LoadProgress { loaded: 1, total: 4 }));
+        assert_eq!(manager.load_progress().fraction(), 0.25);
+        assert!(manager.get_asset_id("map_a").is_some());
+        assert!(manager.get_asset_id("map_b").is_none());
+
+        manager.load_queued(2).unwrap();
+        assert_eq!(manager.load_progress().fraction(), 0.75);
+
+        let state = manager.load_queued(10).unwrap();
+        assert_eq!(state, LoadingState::Done);
+        assert_eq!(manager.load_progress().fraction(), 1.0);
+
+        let id = manager.get_asset_id("map_d").unwrap();
+        assert_eq!(manager.get::<RTileMap>(id), Some(&tilemap().unwrap()));
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::VecDeque;
+
+use crate::QPResult;
+
+use super::AssetManager;
+
+type AssetLoader = Box<dyn FnOnce(&mut AssetManager) -> QPResult<u64>>;
+
+/**
+* assets waiting to be loaded a few at a time, so the app can keep
+* rendering a loading screen while a big manifest comes in
+*/
+#[derive(Default)]
+pub struct AssetQueue {
+    pending: VecDeque<AssetLoader>,
+    loaded: usize,
+    total: usize,
+}
+
+impl AssetQueue {
+    pub fn push(&mut self, loader: AssetLoader) {
+        self.pending.push_back(loader);
+        self.total += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<AssetLoader> {
+        let loader = self.pending.pop_front()?;
+        self.loaded += 1;
+
+        Some(loader)
+    }
+
+    pub fn progress(&self) -> LoadProgress {
+        LoadProgress {
+            loaded: self.loaded,
+            total: self.total,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LoadProgress {
+    pub loaded: usize,
+    pub total: usize,
+}
+
+impl LoadProgress {
+    /**
+     * 0.0 to 1.0, an empty queue counts as fully loaded
+     */
+    pub fn fraction(&self) -> f32 {
+        match self.total {
+            0 => 1.0,
+            total => self.loaded as f32 / total as f32,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.loaded >= self.total
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoadingState {
+    Loading(LoadProgress),
+    Done,
+}
+
+impl From<LoadProgress> for LoadingState {
+    fn from(progress: LoadProgress) -> Self {
+        match progress.is_done() {
+            true => LoadingState::Done,
+            false => LoadingState::Loading(progress),
+        }
+    }
+}
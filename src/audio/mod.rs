@@ -1,15 +1,79 @@
+use std::collections::HashMap;
 use std::fs::File;
 use std::thread;
 use std::io::BufReader;
-use rodio::{Decoder, OutputStream, Sink};
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source, SpatialSink};
 
-use crate::{core::prelude::to_abs_path, QPResult};
+use crate::{
+    asset_manager::AssetManager,
+    core::prelude::to_abs_path,
+    prelude::{
+        qp_assets::RAudio,
+        qp_ecs::components::{CAudioListener, CSoundEmitter, CTransform2D},
+        GlobalRegistry, QPError,
+    },
+    QPResult,
+};
 
-pub struct QPAudio {}
+/**
+ * the calls the sound systems need from whatever is actually making noise
+ */
+pub trait AudioBackend {
+    /**
+     * starts playing a clip and returns the channel it plays on
+     */
+    fn play(&mut self, clip: &str, looping: bool, volume: f32) -> QPResult<u64>;
+
+    /**
+     * plays a clip that's already in memory once, and returns the channel it
+     * plays on
+     */
+    fn play_clip(&mut self, clip: &RAudio, volume: f32) -> QPResult<u64>;
+
+    /**
+     * plays the RAudio loaded in the asset manager as asset. an asset that
+     * isn't loaded, eg. because its file failed to load, is an error
+     */
+    fn play_asset(&mut self, assets: &AssetManager, asset: u64, volume: f32) -> QPResult<u64> {
+        let Some(clip) = assets.get::<RAudio>(asset) else {
+            return Err(QPError::AudioError(format!(
+                "no audio clip is loaded as asset {}",
+                asset
+            )));
+        };
+
+        self.play_clip(clip, volume)
+    }
+
+    fn stop(&mut self, channel: u64);
+
+    /**
+     * stops every channel, eg. when the scene is reset. see
+     * `s_stop_all_sounds` to also reset the sound emitters
+     */
+    fn stop_all(&mut self);
+
+    fn set_volume(&mut self, channel: u64, volume: f32);
+
+    /**
+     * -1.0 is hard left, 1.0 is hard right
+     */
+    fn set_pan(&mut self, channel: u64, pan: f32);
+}
+
+pub struct QPAudio {
+    stream: Option<(OutputStream, OutputStreamHandle)>,
+    channels: HashMap<u64, SpatialSink>,
+    next_channel: u64,
+}
 
 impl QPAudio {
     pub fn new() -> QPResult<Self> {
-        Ok(Self {})
+        Ok(Self {
+            stream: None,
+            channels: HashMap::new(),
+            next_channel: 0,
+        })
     }
 
     pub fn play(&self) {
@@ -27,4 +91,388 @@ impl QPAudio {
             sink.sleep_until_end();
         });
     }
-}
\ No newline at end of file
+
+    // the output device is only opened once something actually plays
+    fn handle(&mut self) -> QPResult<&OutputStreamHandle> {
+        if self.stream.is_none() {
+            let stream = OutputStream::try_default()
+                .map_err(|e| QPError::AudioError(e.to_string()))?;
+
+            self.stream = Some(stream);
+        }
+
+        match &self.stream {
+            Some((_, handle)) => Ok(handle),
+            None => unreachable!(),
+        }
+    }
+
+    fn sink(&mut self) -> QPResult<SpatialSink> {
+        SpatialSink::try_new(self.handle()?, [0.0; 3], [-1.0, 0.0, 0.0], [1.0, 0.0, 0.0])
+            .map_err(|e| QPError::AudioError(e.to_string()))
+    }
+
+    fn start(&mut self, sink: SpatialSink, volume: f32) -> u64 {
+        sink.set_volume(volume);
+
+        let channel = self.next_channel;
+        self.next_channel += 1;
+        self.channels.insert(channel, sink);
+
+        channel
+    }
+}
+
+impl AudioBackend for QPAudio {
+    fn play(&mut self, clip: &str, looping: bool, volume: f32) -> QPResult<u64> {
+        let sink = self.sink()?;
+
+        let file = BufReader::new(File::open(to_abs_path(clip)?)?);
+        let source = Decoder::new(file).map_err(|e| QPError::AudioError(e.to_string()))?;
+
+        match looping {
+            true => sink.append(source.repeat_infinite()),
+            false => sink.append(source),
+        }
+
+        Ok(self.start(sink, volume))
+    }
+
+    fn play_clip(&mut self, clip: &RAudio, volume: f32) -> QPResult<u64> {
+        let sink = self.sink()?;
+        sink.append(clip.decoder()?);
+
+        Ok(self.start(sink, volume))
+    }
+
+    fn stop(&mut self, channel: u64) {
+        if let Some(sink) = self.channels.remove(&channel) {
+            sink.stop();
+        }
+    }
+
+    fn stop_all(&mut self) {
+        for (_, sink) in self.channels.drain() {
+            sink.stop();
+        }
+    }
+
+    fn set_volume(&mut self, channel: u64, volume: f32) {
+        if let Some(sink) = self.channels.get(&channel) {
+            sink.set_volume(volume);
+        }
+    }
+
+    fn set_pan(&mut self, channel: u64, pan: f32) {
+        if let Some(sink) = self.channels.get(&channel) {
+            sink.set_emitter_position([pan, 0.0, 0.0]);
+        }
+    }
+}
+
+/**
+ * starts and stops emitter channels to match their `playing` flag, and pans
+ * each playing emitter by its horizontal offset from the CAudioListener.
+ * emitters further than `range` away are panned hard to one side. without a
+ * listener everything plays centered
+ *
+ * an emitter whose clip can't be played is logged once and marked `failed`,
+ * the others carry on
+ */
+pub fn s_update_sound_emitters(
+    registry: &mut GlobalRegistry,
+    audio: &mut impl AudioBackend,
+    range: f32,
+) {
+    let listener = find_listener(registry);
+
+    for entity in registry.entity_manager.query_all::<CSoundEmitter>() {
+        let x = registry
+            .entity_manager
+            .get::<CTransform2D>(&entity)
+            .map(|transform| transform.translate.x);
+
+        let Some(emitter) = registry.entity_manager.get_mut::<CSoundEmitter>(&entity) else {
+            continue;
+        };
+        if emitter.failed {
+            continue;
+        }
+
+        match (emitter.playing, emitter.channel) {
+            (true, None) => match audio.play(&emitter.clip, emitter.looping, emitter.volume) {
+                Ok(channel) => emitter.channel = Some(channel),
+                Err(e) => {
+                    println!(
+                        "[audio] can't play {}, switching its emitter off: {}",
+                        emitter.clip, e
+                    );
+                    emitter.failed = true;
+                }
+            },
+            (false, Some(channel)) => {
+                audio.stop(channel);
+                emitter.channel = None;
+            }
+            _ => (),
+        }
+
+        if let Some(channel) = emitter.channel {
+            audio.set_volume(channel, emitter.volume);
+
+            let pan = match (x, listener) {
+                (Some(x), Some(listener)) => ((x - listener.x) / range).clamp(-1.0, 1.0),
+                _ => 0.0,
+            };
+            audio.set_pan(channel, pan);
+        }
+    }
+}
+
+/**
+ * stops every channel and forgets the emitters' channels. emitters that are
+ * still `playing` start over on the next `s_update_sound_emitters`
+ */
+pub fn s_stop_all_sounds(registry: &mut GlobalRegistry, audio: &mut impl AudioBackend) {
+    audio.stop_all();
+
+    for entity in registry.entity_manager.query_all::<CSoundEmitter>() {
+        if let Some(emitter) = registry.entity_manager.get_mut::<CSoundEmitter>(&entity) {
+            emitter.channel = None;
+        }
+    }
+}
+
+/**
+ * position of the first CAudioListener by spawn order
+ */
+pub fn find_listener(registry: &GlobalRegistry) -> Option<glm::Vec2> {
+    let listeners = registry
+        .entity_manager
+        .query_all_in_spawn_order::<CAudioListener>();
+
+    #[cfg(debug_assertions)]
+    if listeners.len() > 1 {
+        static WARNED: std::sync::Once = std::sync::Once::new();
+        WARNED.call_once(|| {
+            println!("[audio] more than one CAudioListener, using the first one spawned")
+        });
+    }
+
+    let listener = listeners.first()?;
+
+    registry
+        .entity_manager
+        .get::<CTransform2D>(listener)
+        .map(|transform| transform.translate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::EntityBuilder;
+    use crate::test_utils::registry;
+
+    #[derive(Default)]
+    struct MockAudio {
+        playing: HashMap<u64, f32>,
+        next_channel: u64,
+        attempts: u32,
+    }
+
+    impl AudioBackend for MockAudio {
+        fn play(&mut self, clip: &str, _looping: bool, _volume: f32) -> QPResult<u64> {
+            self.attempts += 1;
+            if clip.starts_with("missing") {
+                return Err(QPError::AudioError(clip.to_string()));
+            }
+
+            let channel = self.next_channel;
+            self.next_channel += 1;
+            self.playing.insert(channel, 0.0);
+
+            Ok(channel)
+        }
+
+        fn play_clip(&mut self, _clip: &RAudio, volume: f32) -> QPResult<u64> {
+            self.play("", false, volume)
+        }
+
+        fn stop(&mut self, channel: u64) {
+            self.playing.remove(&channel);
+        }
+
+        fn stop_all(&mut self) {
+            self.playing.clear();
+        }
+
+        fn set_volume(&mut self, _channel: u64, _volume: f32) {}
+
+        fn set_pan(&mut self, channel: u64, pan: f32) {
+            self.playing.insert(channel, pan);
+        }
+    }
+
+    #[test]
+    fn sound_emitter_toggles_channel() {
+        let mut registry = registry();
+        let mut audio = MockAudio::default();
+
+        EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D::default())
+            .with(CAudioListener)
+            .build();
+
+        let entity = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D {
+                translate: glm::vec2(50.0, 0.0),
+                ..CTransform2D::default()
+            })
+            .with(CSoundEmitter::new("thruster.ogg", true, 1.0))
+            .build();
+
+        s_update_sound_emitters(&mut registry, &mut audio, 100.0);
+        assert!(audio.playing.is_empty());
+
+        registry.entity_manager.get_mut::<CSoundEmitter>(&entity).unwrap().playing = true;
+        s_update_sound_emitters(&mut registry, &mut audio, 100.0);
+
+        let channel = registry.entity_manager.get::<CSoundEmitter>(&entity).unwrap().channel.unwrap();
+        assert_eq!(audio.playing.get(&channel), Some(&0.5));
+
+        // staying on doesn't restart the channel
+        s_update_sound_emitters(&mut registry, &mut audio, 100.0);
+        assert_eq!(audio.playing.len(), 1);
+
+        registry.entity_manager.get_mut::<CSoundEmitter>(&entity).unwrap().playing = false;
+        s_update_sound_emitters(&mut registry, &mut audio, 100.0);
+
+        assert!(audio.playing.is_empty());
+        assert_eq!(registry.entity_manager.get::<CSoundEmitter>(&entity).unwrap().channel, None);
+    }
+
+    #[test]
+    fn sound_emitter_pans_from_listener() {
+        let mut registry = registry();
+        let mut audio = MockAudio::default();
+
+        let ship = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D {
+                translate: glm::vec2(500.0, 0.0),
+                ..CTransform2D::default()
+            })
+            .with(CAudioListener)
+            .build();
+
+        // a later listener is ignored
+        EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D::default())
+            .with(CAudioListener)
+            .build();
+
+        let mut emitter = CSoundEmitter::new("engine.ogg", true, 1.0);
+        emitter.playing = true;
+        let entity = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D {
+                translate: glm::vec2(450.0, 0.0),
+                ..CTransform2D::default()
+            })
+            .with(emitter)
+            .build();
+
+        s_update_sound_emitters(&mut registry, &mut audio, 100.0);
+        let channel = registry.entity_manager.get::<CSoundEmitter>(&entity).unwrap().channel.unwrap();
+        assert_eq!(audio.playing.get(&channel), Some(&-0.5));
+
+        registry.entity_manager.get_mut::<CTransform2D>(&ship).unwrap().translate.x = 300.0;
+        s_update_sound_emitters(&mut registry, &mut audio, 100.0);
+        assert_eq!(audio.playing.get(&channel), Some(&1.0));
+    }
+
+    #[test]
+    fn sound_emitter_failure_skips_only_that_emitter() {
+        let mut registry = registry();
+        let mut audio = MockAudio::default();
+
+        let mut emitters = vec![];
+        for clip in ["missing.ogg", "engine.ogg"] {
+            let mut emitter = CSoundEmitter::new(clip, true, 1.0);
+            emitter.playing = true;
+            emitters.push(
+                EntityBuilder::create(&mut registry.entity_manager)
+                    .with(emitter)
+                    .build(),
+            );
+        }
+
+        s_update_sound_emitters(&mut registry, &mut audio, 100.0);
+        let em = &registry.entity_manager;
+        assert!(em.get::<CSoundEmitter>(&emitters[0]).unwrap().failed);
+        assert!(em.get::<CSoundEmitter>(&emitters[1]).unwrap().channel.is_some());
+        assert_eq!(audio.playing.len(), 1);
+
+        // the broken one isn't tried again every frame
+        s_update_sound_emitters(&mut registry, &mut audio, 100.0);
+        assert_eq!(audio.attempts, 2);
+    }
+
+    // a mono 8 bit wav with a handful of silent samples
+    fn wav() -> Vec<u8> {
+        let samples = [128u8; 8];
+        let mut bytes = vec![];
+        bytes.extend(b"RIFF");
+        bytes.extend((36 + samples.len() as u32).to_le_bytes());
+        bytes.extend(b"WAVEfmt ");
+        bytes.extend(16u32.to_le_bytes());
+        bytes.extend(1u16.to_le_bytes()); // pcm
+        bytes.extend(1u16.to_le_bytes()); // mono
+        bytes.extend(8000u32.to_le_bytes());
+        bytes.extend(8000u32.to_le_bytes());
+        bytes.extend(1u16.to_le_bytes());
+        bytes.extend(8u16.to_le_bytes());
+        bytes.extend(b"data");
+        bytes.extend((samples.len() as u32).to_le_bytes());
+        bytes.extend(samples);
+
+        bytes
+    }
+
+    #[test]
+    fn play_audio_assets() {
+        let mut registry = registry();
+        let mut audio = MockAudio::default();
+
+        assert!(RAudio::from_bytes(b"not a sound".to_vec()).is_err());
+        assert!(RAudio::from_file("assets/audio/missing.wav").is_err());
+
+        let laser = registry
+            .asset_manager
+            .load_asset("laser", RAudio::from_bytes(wav()).unwrap())
+            .unwrap();
+        let channel = audio.play_asset(&registry.asset_manager, laser, 0.8).unwrap();
+        assert!(audio.playing.contains_key(&channel));
+
+        // a clip that never loaded is an error, not a panic
+        assert!(matches!(
+            audio.play_asset(&registry.asset_manager, u64::MAX, 1.0),
+            Err(QPError::AudioError(_))
+        ));
+
+        let mut emitter = CSoundEmitter::new("engine.ogg", true, 1.0);
+        emitter.playing = true;
+        let engine = EntityBuilder::create(&mut registry.entity_manager)
+            .with(emitter)
+            .build();
+        s_update_sound_emitters(&mut registry, &mut audio, 100.0);
+        assert_eq!(audio.playing.len(), 2);
+
+        s_stop_all_sounds(&mut registry, &mut audio);
+        assert!(audio.playing.is_empty());
+        let emitter = registry.entity_manager.get::<CSoundEmitter>(&engine).unwrap();
+        assert_eq!(emitter.channel, None);
+
+        // still switched on, so it starts again
+        s_update_sound_emitters(&mut registry, &mut audio, 100.0);
+        assert_eq!(audio.playing.len(), 1);
+    }
+}
@@ -22,6 +22,25 @@ impl AnyMap {
             .map(|any| any.downcast_mut::<C>()).unwrap_or(None)
     }
 
+    /**
+     * three different items at once. None if any is missing or if they are
+     * not three different types
+     */
+    pub fn get_three_mut<A: 'static, B: 'static, C: 'static>(
+        &mut self,
+    ) -> Option<(&mut A, &mut B, &mut C)> {
+        let (a, b, c) = (TypeId::of::<A>(), TypeId::of::<B>(), TypeId::of::<C>());
+        if a == b || a == c || b == c {
+            return None;
+        }
+
+        let [Some(a), Some(b), Some(c)] = self.0.get_disjoint_mut([&a, &b, &c]) else {
+            return None;
+        };
+
+        Some((a.downcast_mut()?, b.downcast_mut()?, c.downcast_mut()?))
+    }
+
     pub fn all_collections(&mut self) -> Vec<&Box<dyn std::any::Any>> {
         self.0.values().into_iter().collect()
     }
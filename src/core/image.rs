@@ -56,6 +56,13 @@ impl QPImage {
         })
     }
 
+    /**
+     * the pixels as 8 bit rgba, whatever the source format was
+     */
+    pub fn rgba(&self) -> Vec<u8> {
+        self.img.to_rgba8().into_raw()
+    }
+
     pub fn data(&self) -> Vec<u8> {
         self.img.as_bytes().to_vec()
     }
@@ -1,2 +1,3 @@
+pub mod math2d;
 pub mod random;
 pub mod trig;
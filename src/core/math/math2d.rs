@@ -0,0 +1,150 @@
+// 2D vector helpers. angles are in radians, measured counter-clockwise from
+// the positive x-axis
+
+/**
+ * distance between two points
+ */
+pub fn distance(a: &glm::Vec2, b: &glm::Vec2) -> f32 {
+    (b - a).magnitude()
+}
+
+/**
+ * distance between two points, squared. cheaper than `distance` when only
+ * comparing distances
+ */
+pub fn distance_squared(a: &glm::Vec2, b: &glm::Vec2) -> f32 {
+    (b - a).magnitude_squared()
+}
+
+/**
+ * unit vector in the same direction, or zero instead of NaN for a zero vector
+ */
+pub fn normalize_or_zero(vec: &glm::Vec2) -> glm::Vec2 {
+    let magnitude = vec.magnitude();
+
+    match magnitude > 0.0 {
+        true => vec / magnitude,
+        false => glm::vec2(0.0, 0.0),
+    }
+}
+
+/**
+ * bounces vec off a surface with the given normal. the normal doesn't need to
+ * be normalized
+ */
+pub fn reflect(vec: &glm::Vec2, normal: &glm::Vec2) -> glm::Vec2 {
+    let normal = normalize_or_zero(normal);
+
+    vec - normal * 2.0 * vec.dot(&normal)
+}
+
+/**
+ * linear interpolation, t = 0 gives a and t = 1 gives b
+ */
+pub fn lerp(a: &glm::Vec2, b: &glm::Vec2, t: f32) -> glm::Vec2 {
+    a + (b - a) * t
+}
+
+/**
+ * signed angle to turn a onto b, in -pi..=pi. positive is counter-clockwise.
+ * zero if either vector is zero
+ */
+pub fn angle_between(a: &glm::Vec2, b: &glm::Vec2) -> f32 {
+    if a.magnitude() == 0.0 || b.magnitude() == 0.0 {
+        return 0.0;
+    }
+
+    a.perp(b).atan2(a.dot(b))
+}
+
+/**
+ * unit vector pointing at angle
+ */
+pub fn from_angle(angle: f32) -> glm::Vec2 {
+    let (sin, cos) = angle.sin_cos();
+
+    glm::vec2(cos, sin)
+}
+
+/**
+ * angle the vector points at, in -pi..=pi. the inverse of `from_angle`
+ */
+pub fn to_angle(vec: &glm::Vec2) -> f32 {
+    vec.y.atan2(vec.x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: glm::Vec2, b: glm::Vec2) -> bool {
+        (a - b).magnitude() < 1e-5
+    }
+
+    #[test]
+    fn math2d_distance() {
+        let (a, b) = (glm::vec2(1.0, 1.0), glm::vec2(4.0, 5.0));
+
+        assert_eq!(distance(&a, &b), 5.0);
+        assert_eq!(distance_squared(&a, &b), 25.0);
+        assert_eq!(distance(&a, &a), 0.0);
+    }
+
+    #[test]
+    fn math2d_normalize_or_zero() {
+        assert!(close(
+            normalize_or_zero(&glm::vec2(3.0, 4.0)),
+            glm::vec2(0.6, 0.8)
+        ));
+
+        let zero = normalize_or_zero(&glm::vec2(0.0, 0.0));
+        assert_eq!(zero, glm::vec2(0.0, 0.0));
+        assert!(!zero.x.is_nan() && !zero.y.is_nan());
+    }
+
+    #[test]
+    fn math2d_reflect() {
+        // off the floor, with a normal that isn't unit length
+        let bounced = reflect(&glm::vec2(1.0, -1.0), &glm::vec2(0.0, 3.0));
+        assert!(close(bounced, glm::vec2(1.0, 1.0)));
+
+        let bounced = reflect(&glm::vec2(2.0, 0.0), &glm::vec2(-1.0, 0.0));
+        assert!(close(bounced, glm::vec2(-2.0, 0.0)));
+    }
+
+    #[test]
+    fn math2d_lerp() {
+        let (a, b) = (glm::vec2(0.0, 10.0), glm::vec2(10.0, 20.0));
+
+        assert_eq!(lerp(&a, &b, 0.0), a);
+        assert_eq!(lerp(&a, &b, 1.0), b);
+        assert_eq!(lerp(&a, &b, 0.25), glm::vec2(2.5, 12.5));
+    }
+
+    #[test]
+    fn math2d_angle_between() {
+        let right = glm::vec2(2.0, 0.0);
+
+        assert!((angle_between(&right, &glm::vec2(0.0, 1.0)) - glm::half_pi::<f32>()).abs() < 1e-5);
+        assert!(
+            (angle_between(&right, &glm::vec2(0.0, -3.0)) + glm::half_pi::<f32>()).abs() < 1e-5
+        );
+        assert!(
+            (angle_between(&right, &glm::vec2(-1.0, 0.0)).abs() - glm::pi::<f32>()).abs() < 1e-5
+        );
+        assert_eq!(angle_between(&right, &glm::vec2(0.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn math2d_from_and_to_angle() {
+        assert!(close(from_angle(0.0), glm::vec2(1.0, 0.0)));
+        assert!(close(from_angle(glm::half_pi()), glm::vec2(0.0, 1.0)));
+
+        assert_eq!(to_angle(&glm::vec2(1.0, 0.0)), 0.0);
+        assert!((to_angle(&glm::vec2(0.0, 5.0)) - glm::half_pi::<f32>()).abs() < 1e-5);
+
+        for angle in [-3.0, -1.0, 0.5, 2.0, 3.1] {
+            assert!((to_angle(&from_angle(angle)) - angle).abs() < 1e-5);
+        }
+    }
+}
@@ -1,14 +1,107 @@
 use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/**
+* somewhere to get random numbers from. systems that need randomness should
+* take one of these rather than a `Random`, so tests can hand them a
+* `ScriptedRandom` and know exactly what comes out
+*/
+pub trait RandomSource {
+    /**
+     * 0.0 up to, but not including, 1.0
+     */
+    fn random(&mut self) -> f32;
+
+    /**
+     * start up to, but not including, end
+     */
+    fn range(&mut self, start: i32, end: i32) -> i32 {
+        if end <= start {
+            return start;
+        }
+
+        let offset = (self.random() * (end - start) as f32) as i32;
+        start + offset.min(end - start - 1)
+    }
+
+    fn binary(&mut self, bias: f32) -> bool {
+        self.random() < bias
+    }
+
+    /**
+     * a whole numbered point in the rect around center
+     */
+    fn point_in_rect(&mut self, center: glm::Vec2, size: glm::Vec2) -> glm::Vec2 {
+        let half = size / 2.0;
+        let x = self.range((center.x - half.x) as i32, (center.x + half.x) as i32);
+        let y = self.range((center.y - half.y) as i32, (center.y + half.y) as i32);
+
+        glm::vec2(x as f32, y as f32)
+    }
+}
 
 pub struct Random {
     rng: rand_chacha::ChaCha8Rng,
+
+    // how many times this generator has been forked
+    forks: u64,
+}
+
+/**
+* exactly where a Random is in its sequence, see `Random::state`. can be
+* saved with a scene to replay a run from that point
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RandomState {
+    pub seed: [u8; 32],
+    pub stream: u64,
+    pub word_pos: u128,
+    pub forks: u64,
 }
 
 impl Random {
     pub fn from_seed(seed: u64) -> Self {
         let rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
 
-        Self { rng }
+        Self { rng, forks: 0 }
+    }
+
+    /**
+     * picks up exactly where the generator the state came from was
+     */
+    pub fn from_state(state: RandomState) -> Self {
+        let mut rng = rand_chacha::ChaCha8Rng::from_seed(state.seed);
+        rng.set_stream(state.stream);
+        rng.set_word_pos(state.word_pos);
+
+        Self {
+            rng,
+            forks: state.forks,
+        }
+    }
+
+    pub fn state(&self) -> RandomState {
+        RandomState {
+            seed: self.rng.get_seed(),
+            stream: self.rng.get_stream(),
+            word_pos: self.rng.get_word_pos(),
+            forks: self.forks,
+        }
+    }
+
+    /**
+     * a new generator for a subsystem, eg. the asteroid spawner, so it can
+     * use as many numbers as it likes without shifting everyone else's. the
+     * parent's sequence is untouched, and forking the same parent in the
+     * same order always gives the same children
+     */
+    pub fn fork(&mut self) -> Self {
+        self.forks += 1;
+
+        let mut rng = rand_chacha::ChaCha8Rng::from_seed(self.rng.get_seed());
+        rng.set_stream(mix(self.rng.get_stream() ^ mix(self.forks)));
+
+        Self { rng, forks: 0 }
     }
 
     pub fn random(&mut self) -> f32 {
@@ -23,3 +116,158 @@ impl Random {
         self.random() < bias
     }
 }
+
+impl RandomSource for Random {
+    fn random(&mut self) -> f32 {
+        Random::random(self)
+    }
+
+    fn range(&mut self, start: i32, end: i32) -> i32 {
+        Random::range(self, start, end)
+    }
+}
+
+/**
+* hands out a fixed list of numbers, starting over once it runs out. for
+* testing code that uses a RandomSource
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptedRandom {
+    values: Vec<f32>,
+    next: usize,
+}
+
+impl ScriptedRandom {
+    /**
+     * values should be 0.0 up to, but not including, 1.0
+     */
+    pub fn new(values: Vec<f32>) -> Self {
+        Self { values, next: 0 }
+    }
+
+    /**
+     * how many numbers have been handed out
+     */
+    pub fn taken(&self) -> usize {
+        self.next
+    }
+}
+
+impl RandomSource for ScriptedRandom {
+    fn random(&mut self) -> f32 {
+        if self.values.is_empty() {
+            return 0.0;
+        }
+
+        let value = self.values[self.next % self.values.len()];
+        self.next += 1;
+
+        value
+    }
+}
+
+// splitmix64, spreads nearby numbers far apart
+fn mix(x: u64) -> u64 {
+    let x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    let x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn take(rand: &mut Random, count: usize) -> Vec<f32> {
+        (0..count).map(|_| rand.random()).collect()
+    }
+
+    fn correlation(a: &[f32], b: &[f32]) -> f32 {
+        let mean = |v: &[f32]| v.iter().sum::<f32>() / v.len() as f32;
+        let (mean_a, mean_b) = (mean(a), mean(b));
+
+        let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+        for (a, b) in a.iter().zip(b) {
+            cov += (a - mean_a) * (b - mean_b);
+            var_a += (a - mean_a).powi(2);
+            var_b += (b - mean_b).powi(2);
+        }
+
+        cov / (var_a * var_b).sqrt()
+    }
+
+    #[test]
+    fn random_state_restores_sequence() {
+        let mut rand = Random::from_seed(1234);
+        take(&mut rand, 37);
+
+        let state = rand.state();
+        let expected = take(&mut rand, 100);
+
+        // through a scene file and back
+        let yaml = serde_yaml::to_string(&state).unwrap();
+        let loaded: RandomState = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(loaded, state);
+
+        let mut restored = Random::from_state(loaded);
+        assert_eq!(take(&mut restored, 100), expected);
+        assert_eq!(restored.state(), rand.state());
+    }
+
+    #[test]
+    fn random_fork_is_independent() {
+        let mut rand = Random::from_seed(1234);
+        let mut untouched = Random::from_seed(1234);
+
+        let mut a = rand.fork();
+        let mut b = rand.fork();
+
+        // forking doesn't move the parent along
+        let parent = take(&mut rand, 1000);
+        assert_eq!(parent, take(&mut untouched, 1000));
+
+        let (a, b) = (take(&mut a, 1000), take(&mut b, 1000));
+        assert_ne!(a, b);
+        for (x, y) in [(&parent, &a), (&parent, &b), (&a, &b)] {
+            assert!(correlation(x, y).abs() < 0.1);
+        }
+
+        // the same forks in the same order give the same streams
+        let mut again = Random::from_seed(1234);
+        assert_eq!(take(&mut again.fork(), 1000), a);
+    }
+
+    #[test]
+    fn scripted_random_drives_systems() {
+        // scatters stars around the ship, some of them bright
+        fn scatter(rand: &mut dyn RandomSource, ship: glm::Vec2) -> Vec<(glm::Vec2, bool)> {
+            (0..3)
+                .map(|_| {
+                    let pos = rand.point_in_rect(ship, glm::vec2(800.0, 600.0));
+                    (pos, rand.binary(0.3))
+                })
+                .collect()
+        }
+
+        let mut rand = ScriptedRandom::new(vec![0.0, 0.5, 0.25, 0.999, 0.75, 0.6]);
+        let stars = scatter(&mut rand, glm::vec2(100.0, 0.0));
+
+        assert_eq!(
+            stars,
+            vec![
+                (glm::vec2(-300.0, 0.0), true),
+                (glm::vec2(499.0, 150.0), false),
+                (glm::vec2(-300.0, 0.0), true),
+            ]
+        );
+        assert_eq!(rand.taken(), 9);
+
+        // the real generator works anywhere a scripted one does
+        let mut rand = Random::from_seed(1234);
+        for (pos, _) in scatter(&mut rand, glm::vec2(100.0, 0.0)) {
+            assert!((-300.0..500.0).contains(&pos.x));
+            assert!((-300.0..300.0).contains(&pos.y));
+        }
+    }
+}
@@ -45,6 +45,9 @@ pub struct Interval {
     timer: Timer,
     interval: f32,
     last_tick: f32,
+
+    // time since the last whole interval
+    leftover: f32,
 }
 
 impl Interval {
@@ -59,19 +62,116 @@ impl Interval {
             timer,
             last_tick,
             interval,
+            leftover: 0.0,
         }
     }
 
+    /**
+     * true if at least one interval has passed since the last check, going by
+     * the wall clock. any extra intervals are dropped
+     */
     pub fn check(&mut self) -> bool {
-        let delta = self.timer.elapsed() - self.last_tick;
+        let now = self.timer.elapsed();
+        let delta = now - self.last_tick;
+        self.last_tick = now;
 
-        if delta >= self.interval {
-            self.last_tick += delta - (delta % self.interval);
+        self.ticks(delta) > 0
+    }
 
-            return true;
+    /**
+     * how many whole intervals fit in the time so far, with `delta` seconds
+     * added. the remainder carries over to the next call, so a long frame
+     * catches up instead of losing ticks
+     *
+     * drive an interval with either this or `check`, not both
+     */
+    pub fn ticks(&mut self, delta: f32) -> u32 {
+        if self.interval <= 0.0 {
+            return 0;
         }
 
-        false
+        self.leftover += delta;
+        let ticks = (self.leftover / self.interval).floor();
+        self.leftover -= ticks * self.interval;
+
+        ticks as u32
+    }
+
+    /**
+     * time since the last whole interval
+     */
+    pub fn leftover(&self) -> f32 {
+        self.leftover
+    }
+}
+
+/**
+* counts down from a number of seconds to 0. the time left only moves by the
+* frame deltas given to `advance`, and not at all while paused
+*/
+#[derive(Debug)]
+pub struct Countdown {
+    countdown: f32,
+    time_left: f32,
+    paused: bool,
+}
+
+impl Countdown {
+    /*
+     * countdown is in seconds
+     */
+    pub fn new(countdown: f32) -> Self {
+        Self {
+            countdown,
+            time_left: countdown,
+            paused: false,
+        }
+    }
+
+    /**
+     * takes delta seconds off, usually `world.delta` once a frame. the
+     * seconds left, 0.0 once it's run out
+     */
+    pub fn advance(&mut self, delta: f32) -> f32 {
+        if !self.paused {
+            self.time_left = (self.time_left - delta).max(0.0);
+        }
+
+        self.time_left
+    }
+
+    pub fn time_left(&self) -> f32 {
+        self.time_left
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.time_left <= 0.0
+    }
+
+    /**
+     * 1.0 when it starts, 0.0 once it's run out. as of the last `advance`
+     */
+    pub fn fraction_remaining(&self) -> f32 {
+        if self.countdown <= 0.0 {
+            return 0.0;
+        }
+
+        self.time_left / self.countdown
+    }
+
+    /**
+     * stops the clock until `resume`
+     */
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
     }
 }
 
@@ -85,3 +185,52 @@ pub fn now_secs() -> QPResult<u64> {
         .duration_since(SystemTime::UNIX_EPOCH)?
         .as_secs())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_ticks_catch_up() {
+        let mut interval = Interval::new(0.125);
+
+        // a hitch long enough for 5 and a half intervals
+        assert_eq!(interval.ticks(0.6875), 5);
+        assert_eq!(interval.leftover(), 0.0625);
+
+        // the leftover counts towards the next one
+        assert_eq!(interval.ticks(0.0625), 1);
+        assert_eq!(interval.leftover(), 0.0);
+        assert_eq!(interval.ticks(0.1), 0);
+    }
+
+    #[test]
+    fn countdown_paused_doesnt_advance() {
+        let mut countdown = Countdown::new(10.0);
+        assert_eq!(countdown.advance(0.5), 9.5);
+
+        countdown.pause();
+        assert!(countdown.is_paused());
+        assert_eq!(countdown.advance(0.25), 9.5);
+        assert_eq!(countdown.advance(3.0), 9.5);
+
+        countdown.resume();
+        assert!(!countdown.is_paused());
+        assert_eq!(countdown.advance(0.25), 9.25);
+    }
+
+    #[test]
+    fn countdown_fraction_remaining() {
+        let mut countdown = Countdown::new(2.0);
+        assert_eq!(countdown.fraction_remaining(), 1.0);
+        assert!(!countdown.is_done());
+
+        countdown.advance(0.5);
+        assert_eq!(countdown.fraction_remaining(), 0.75);
+
+        // a long frame doesn't go below 0
+        assert_eq!(countdown.advance(5.0), 0.0);
+        assert_eq!(countdown.fraction_remaining(), 0.0);
+        assert!(countdown.is_done());
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use super::{
+    entity_manager::EntityBuilder, entity_manager::EntityManager, indexed_array::VersionedIndex,
+};
+
+type ArchetypeFactory = Box<dyn for<'a> Fn(EntityBuilder<'a>) -> EntityBuilder<'a>>;
+
+/**
+* named recipes for new entities with default components, eg. so an editor
+* can place an "asteroid" without knowing what an asteroid is made of
+*
+* ```no_run
+* # use quipi::prelude::{qp_ecs::components::{CHealth, CTransform2D}, World};
+* # fn setup(world: &mut World) {
+* world.archetypes.register("asteroid", |entity| {
+*     entity
+*         .with(CTransform2D::default())
+*         .with(CHealth::new(3.0))
+* });
+*
+* let asteroid = world.spawn_default("asteroid");
+* # }
+* ```
+*/
+#[derive(Default)]
+pub struct Archetypes {
+    factories: HashMap<String, ArchetypeFactory>,
+}
+
+impl Archetypes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * registering a name again replaces its factory
+     */
+    pub fn register(
+        &mut self,
+        name: &str,
+        factory: impl for<'a> Fn(EntityBuilder<'a>) -> EntityBuilder<'a> + 'static,
+    ) {
+        self.factories.insert(name.to_string(), Box::new(factory));
+    }
+
+    pub fn unregister(&mut self, name: &str) -> bool {
+        self.factories.remove(name).is_some()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    /**
+     * every registered name, sorted, eg. for an editor's palette
+     */
+    pub fn names(&self) -> Vec<&str> {
+        let mut names = self
+            .factories
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+
+        names
+    }
+
+    /**
+     * creates an entity from the named factory. None if the name isn't
+     * registered
+     */
+    pub fn spawn(&self, name: &str, entity_manager: &mut EntityManager) -> Option<VersionedIndex> {
+        let factory = self.factories.get(name)?;
+
+        Some(factory(EntityBuilder::create(entity_manager)).build())
+    }
+}
+
+impl std::fmt::Debug for Archetypes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Archetypes")
+            .field("names", &self.names())
+            .finish()
+    }
+}
@@ -0,0 +1,32 @@
+use super::{
+    entity_manager::EntityManager,
+    indexed_array::VersionedIndex,
+    prelude::components::{CSprite, CTag, CTransform2D, CVelocity2D},
+};
+
+/**
+* a group of components that are always added together
+*/
+pub trait Bundle {
+    fn insert(self, entity_manager: &mut EntityManager, entity: &VersionedIndex);
+}
+
+/**
+* everything a moving 2D sprite needs
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sprite2DBundle {
+    pub tag: CTag,
+    pub transform: CTransform2D,
+    pub sprite: CSprite,
+    pub velocity: CVelocity2D,
+}
+
+impl Bundle for Sprite2DBundle {
+    fn insert(self, entity_manager: &mut EntityManager, entity: &VersionedIndex) {
+        entity_manager.add(entity, self.tag);
+        entity_manager.add(entity, self.transform);
+        entity_manager.add(entity, self.sprite);
+        entity_manager.add(entity, self.velocity);
+    }
+}
@@ -0,0 +1,14 @@
+use super::super::prelude::Component;
+use crate::prelude::VersionedIndex;
+
+/**
+* keeps a screen space element (eg. a health bar) over a world entity. the
+* element's CTransform2D is set to where target appears through the camera,
+* plus offset in pixels
+*/
+#[derive(Debug, Component, Clone, Copy, PartialEq)]
+pub struct CAnchorToEntity {
+    pub target: VersionedIndex,
+    pub camera: u64,
+    pub offset: glm::Vec2,
+}
@@ -0,0 +1,54 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+/**
+* flips through cells of the sprite's texture atlas, see `s_animate_sprites`
+*
+* a looping animation starts over after the last frame. one that doesn't
+* stays on the last frame and sets finished
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct CSpriteAnimation {
+    /**
+     * atlas cells, in the same units as TextureAtlas::active_texture
+     */
+    pub frames: Vec<glm::Vec2>,
+    pub fps: f32,
+    pub looping: bool,
+
+    #[serde(default)]
+    pub frame: usize,
+
+    #[serde(default)]
+    pub elapsed: f32,
+
+    #[serde(default)]
+    pub finished: bool,
+}
+
+impl CSpriteAnimation {
+    pub fn new(frames: Vec<glm::Vec2>, fps: f32, looping: bool) -> Self {
+        Self {
+            frames,
+            fps,
+            looping,
+            ..Self::default()
+        }
+    }
+
+    /**
+     * the atlas cell to show now
+     */
+    pub fn current(&self) -> Option<glm::Vec2> {
+        self.frames.get(self.frame).copied()
+    }
+
+    /**
+     * back to the first frame
+     */
+    pub fn restart(&mut self) {
+        self.frame = 0;
+        self.elapsed = 0.0;
+        self.finished = false;
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use super::super::prelude::Component;
+use crate::core::prelude::trig::rotate2d;
+use serde::{Deserialize, Serialize};
+
+use super::components::CTransform2D;
+
+/**
+* named sockets on an entity, eg. a muzzle or a thruster
+*
+* offsets are in the entity's local space, so they turn and scale with it
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct CAttachPoints(pub HashMap<String, glm::Vec2>);
+
+impl CAttachPoints {
+    pub fn new(points: &[(&str, glm::Vec2)]) -> Self {
+        Self(
+            points
+                .iter()
+                .map(|(name, offset)| (name.to_string(), *offset))
+                .collect(),
+        )
+    }
+
+    /**
+     * the named point in world space for an entity placed with transform
+     */
+    pub fn resolve(&self, name: &str, transform: &CTransform2D) -> Option<glm::Vec2> {
+        let offset = self.0.get(name)?.component_mul(&transform.scale);
+
+        Some(transform.translate + rotate2d(&offset, transform.rotate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attach_point_follows_rotation_and_scale() {
+        let points = CAttachPoints::new(&[("muzzle", glm::vec2(0.0, 28.0))]);
+        let transform = CTransform2D {
+            translate: glm::vec2(100.0, 50.0),
+            rotate: glm::half_pi(),
+            scale: glm::vec2(2.0, 2.0),
+        };
+
+        // a quarter turn points "up" along -x
+        let muzzle = points.resolve("muzzle", &transform).unwrap();
+        assert!((muzzle - glm::vec2(44.0, 50.0)).magnitude() < 1e-4);
+
+        let unrotated = CTransform2D {
+            rotate: 0.0,
+            ..transform
+        };
+        assert_eq!(points.resolve("muzzle", &unrotated), Some(glm::vec2(100.0, 106.0)));
+        assert_eq!(points.resolve("thruster", &transform), None);
+    }
+}
@@ -0,0 +1,19 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+/**
+* an entity the camera keeps in frame, see `s_frame_camera_targets`
+*
+* the camera centers on the weighted average of every target, so a heavier
+* target (eg. the player) pulls the center towards itself
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct CCameraTarget {
+    pub weight: f32,
+}
+
+impl Default for CCameraTarget {
+    fn default() -> Self {
+        Self { weight: 1.0 }
+    }
+}
@@ -1,9 +1,9 @@
 use super::super::prelude::{
     Component,
-    VersionedIndex
+    EntityId
 };
 
 #[derive(Component, Debug, PartialEq)]
 pub struct CChildren {
-    pub list: Vec<VersionedIndex>
+    pub list: Vec<EntityId>
 }
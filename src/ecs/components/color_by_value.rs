@@ -0,0 +1,95 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+/**
+* drives the sprite color from a value, eg. remaining health or lifetime
+*
+* gradient is a list of (stop, color) sorted by stop. values between two
+* stops blend linearly and values outside the stops clamp to the ends. two
+* stops with the same value switch color there without blending
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct CColorByValue {
+    pub value: f32,
+    pub gradient: Vec<(f32, glm::Vec4)>,
+}
+
+impl CColorByValue {
+    pub fn sample(&self) -> Option<glm::Vec4> {
+        let (first, last) = (self.gradient.first()?, self.gradient.last()?);
+
+        if self.value <= first.0 {
+            return Some(first.1);
+        }
+
+        for pair in self.gradient.windows(2) {
+            let ((from, from_color), (to, to_color)) = (pair[0], pair[1]);
+
+            if self.value <= to {
+                // two stops sharing a value make a hard edge, there's nothing to blend
+                if to <= from {
+                    return Some(to_color);
+                }
+
+                let t = (self.value - from) / (to - from);
+
+                return Some(glm::lerp(&from_color, &to_color, t));
+            }
+        }
+
+        Some(last.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_by_value_interpolates_between_stops() {
+        let red = glm::vec4(1.0, 0.0, 0.0, 1.0);
+        let yellow = glm::vec4(1.0, 1.0, 0.0, 1.0);
+        let green = glm::vec4(0.0, 1.0, 0.0, 0.0);
+
+        let mut cmp = CColorByValue {
+            value: 0.75,
+            gradient: vec![(0.0, red), (0.5, yellow), (1.0, green)],
+        };
+        assert_eq!(cmp.sample(), Some(glm::vec4(0.5, 1.0, 0.0, 0.5)));
+
+        cmp.value = 0.5;
+        assert_eq!(cmp.sample(), Some(yellow));
+
+        cmp.value = -1.0;
+        assert_eq!(cmp.sample(), Some(red));
+
+        cmp.value = 2.0;
+        assert_eq!(cmp.sample(), Some(green));
+
+        cmp.gradient.clear();
+        assert_eq!(cmp.sample(), None);
+    }
+
+    #[test]
+    fn color_by_value_duplicate_stops_make_a_hard_edge() {
+        let red = glm::vec4(1.0, 0.0, 0.0, 1.0);
+        let green = glm::vec4(0.0, 1.0, 0.0, 1.0);
+
+        let mut cmp = CColorByValue {
+            value: 0.5,
+            gradient: vec![(0.0, red), (0.5, red), (0.5, green), (1.0, green)],
+        };
+        assert_eq!(cmp.sample(), Some(red));
+
+        cmp.value = 0.75;
+        assert_eq!(cmp.sample(), Some(green));
+
+        // only duplicates, every value lands on one side of the edge
+        cmp.gradient = vec![(0.5, red), (0.5, green)];
+        cmp.value = 0.5;
+        assert_eq!(cmp.sample(), Some(red));
+
+        cmp.value = 0.75;
+        assert_eq!(cmp.sample(), Some(green));
+    }
+}
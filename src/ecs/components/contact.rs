@@ -0,0 +1,34 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+/**
+* the layer used for entities without a CCollisionLayer
+*/
+pub const DEFAULT_COLLISION_LAYER: u32 = 1;
+
+/**
+* which collision layers (bits) an entity is on. without one an entity is
+* on `DEFAULT_COLLISION_LAYER`
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct CCollisionLayer(pub u32);
+
+impl Default for CCollisionLayer {
+    fn default() -> Self {
+        Self(DEFAULT_COLLISION_LAYER)
+    }
+}
+
+/**
+* damages whatever it touches, see `s_damage_on_contact`
+*
+* only entities on a layer in layer_mask are hit, eg. so enemy bullets
+* don't hurt enemies. with destroy_self the entity (eg. a bullet) is deleted
+* on its first hit
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct CDamageOnContact {
+    pub amount: f32,
+    pub destroy_self: bool,
+    pub layer_mask: u32,
+}
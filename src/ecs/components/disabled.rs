@@ -0,0 +1,10 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+/**
+* switches an entity off without deleting it, eg. while it waits in an
+* `EntityPool` or to respawn. tag queries, collision, contact damage and
+* movement skip it
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct CDisabled;
@@ -0,0 +1,29 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+/**
+* blinks the sprite between color and its own color for duration seconds
+*
+* the sprite's original color is captured the first time the flash is ticked
+* and restored when it runs out
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct CFlash {
+    pub duration: f32,
+    pub remaining: f32,
+    pub color: glm::Vec4,
+
+    #[serde(default)]
+    pub original: Option<glm::Vec4>,
+}
+
+impl CFlash {
+    pub fn new(duration: f32, color: glm::Vec4) -> Self {
+        Self {
+            duration,
+            remaining: duration,
+            color,
+            original: None,
+        }
+    }
+}
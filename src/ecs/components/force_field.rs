@@ -0,0 +1,28 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+/**
+* pushes (positive strength) or pulls (negative strength) bodies within radius
+*
+* falloff shapes how the force fades towards the edge: 0.0 is constant,
+* 1.0 is linear, 2.0 is quadratic and so on
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct CForceField {
+    pub radius: f32,
+    pub strength: f32,
+    pub falloff: f32,
+}
+
+impl CForceField {
+    /**
+     * how much of the full strength reaches a body at this distance
+     */
+    pub fn scale(&self, distance: f32) -> f32 {
+        if distance >= self.radius {
+            return 0.0;
+        }
+
+        (1.0 - distance / self.radius).powf(self.falloff)
+    }
+}
@@ -0,0 +1,52 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct CHealth {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl CHealth {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+}
+
+/**
+* while present, damage is ignored
+*
+* the remaining seconds are ticked down by s_tick_invulnerable and the
+* component is removed once they run out
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct CInvulnerable {
+    pub remaining: f32,
+}
+
+/**
+* brings a dead entity back after delay seconds, at spawn_point and with full
+* health, instead of it staying dead. see s_respawn
+*
+* remaining counts down while the entity is dead
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct CRespawn {
+    pub spawn_point: glm::Vec2,
+    pub delay: f32,
+    pub remaining: f32,
+}
+
+impl CRespawn {
+    pub fn new(spawn_point: glm::Vec2, delay: f32) -> Self {
+        Self {
+            spawn_point,
+            delay,
+            remaining: delay,
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use super::super::prelude::Component;
+use super::transform::CTransform2D;
+use serde::{Deserialize, Serialize};
+
+/**
+* the CTransform2D composed with every parent's, cached by
+* `s_propagate_transforms`. opt-in: only entities with this take part
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct CWorldTransform2D(pub CTransform2D);
+
+/**
+* the local transform changed and the world transform of this entity and
+* everything under it needs recomputing
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct CTransformDirty;
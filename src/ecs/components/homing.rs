@@ -0,0 +1,14 @@
+use super::super::prelude::Component;
+use crate::prelude::VersionedIndex;
+
+/**
+* steers the entity's velocity towards target, turning at most turn_rate
+* radians a second and flying at speed. keeps its last heading if the target
+* is deleted. add CFaceVelocity to have it point the way it flies
+*/
+#[derive(Debug, Component, Clone, Copy, PartialEq)]
+pub struct CHoming {
+    pub target: VersionedIndex,
+    pub turn_rate: f32,
+    pub speed: f32,
+}
@@ -0,0 +1,35 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+use crate::schemas::sprite::SchemaSprite;
+
+/**
+* deletes the entity once its time is up, spawning the prefab at its last
+* world position, eg. a flare that bursts after a delay. see s_time_to_live
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct CTimeToLive {
+    /**
+     * seconds left
+     */
+    pub remaining: f32,
+
+    /**
+     * built at the entity's position and rotation when it expires
+     */
+    pub prefab: Option<SchemaSprite>,
+}
+
+impl CTimeToLive {
+    pub fn new(seconds: f32) -> Self {
+        Self {
+            remaining: seconds,
+            prefab: None,
+        }
+    }
+
+    pub fn with_prefab(mut self, prefab: SchemaSprite) -> Self {
+        self.prefab = Some(prefab);
+        self
+    }
+}
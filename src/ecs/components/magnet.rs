@@ -0,0 +1,13 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+/**
+* pulls the entity towards the nearest entity tagged target_tag once it comes
+* within radius, eg. pickups drifting towards the player
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct CMagnet {
+    pub target_tag: String,
+    pub radius: f32,
+    pub strength: f32,
+}
@@ -1,13 +1,35 @@
+mod anchor;
+mod animation;
+mod attach_points;
+mod camera_target;
 mod children;
+mod contact;
+mod disabled;
 mod distance;
 mod euler_angles;
 mod identifiers;
+mod flash;
+mod force_field;
 mod gizmo;
+mod health;
+mod hierarchy;
+mod homing;
+mod lifetime;
+mod magnet;
 mod mesh;
+mod motion_blur;
 mod mvp;
+mod parallax;
+mod patrol;
 mod scene;
+mod shape;
+mod shard;
+mod sleep;
+mod sound_emitter;
+mod static_body;
 mod states;
 mod circle;
+mod color_by_value;
 mod quad;
 mod sprite;
 mod target;
@@ -17,51 +39,124 @@ mod velocity;
 pub mod components {
     use super::*;
 
+    pub use anchor::CAnchorToEntity;
+    pub use animation::CSpriteAnimation;
+    pub use attach_points::CAttachPoints;
+    pub use camera_target::CCameraTarget;
     pub use quad::CQuad;
     pub use circle::CCircle;
+    pub use color_by_value::CColorByValue;
+    pub use contact::CCollisionLayer;
+    pub use contact::CDamageOnContact;
+    pub use contact::DEFAULT_COLLISION_LAYER;
+    pub use disabled::CDisabled;
     pub use distance::CDistance;
     pub use euler_angles::CEulerAngles;
+    pub use flash::CFlash;
+    pub use force_field::CForceField;
     pub use gizmo::CGizmo;
+    pub use health::CHealth;
+    pub use health::CInvulnerable;
+    pub use health::CRespawn;
+    pub use hierarchy::CTransformDirty;
+    pub use hierarchy::CWorldTransform2D;
     pub use transform::CTransform;
+    pub use transform::CModelMatrix2D;
     pub use transform::CTransform2D;
+    pub use sprite::CScreenSprite;
     pub use sprite::CSprite;
     pub use velocity::CVelocity;
     pub use velocity::CVelocity2D;
+    pub use velocity::CFaceVelocity;
+    pub use velocity::CVelocityClamp2D;
     pub use children::CChildren;
+    pub use identifiers::CName;
     pub use identifiers::CTag;
+    pub use lifetime::CTimeToLive;
     pub use mvp::CModelMatrix;
     pub use mvp::CProjectionMatrix;
     pub use mvp::CViewMatrix;
     pub use mvp::CMVPMatrix;
+    pub use homing::CHoming;
+    pub use magnet::CMagnet;
     pub use mesh::CMeshData;
+    pub use motion_blur::CMotionBlur;
+    pub use parallax::CDrawLayer;
+    pub use parallax::CLayerOffset;
+    pub use parallax::CParallaxDepth;
+    pub use patrol::CPatrol;
     pub use scene::CScene;
+    pub use shape::{shape_coverage, shape_distance, CShape};
+    pub use shard::CShard;
+    pub use sleep::CRestTime;
+    pub use sleep::CSleeping;
+    pub use sound_emitter::CAudioListener;
+    pub use sound_emitter::CSoundEmitter;
+    pub use static_body::CStatic;
     pub use states::CMouseBtnState;
     pub use target::CTarget;
 
     use crate::prelude::GlobalRegistry;
 
     pub fn register_components(registry: &mut GlobalRegistry) {
-        registry.entity_manager
-            .register_component::<CChildren>()
-            .register_component::<CDistance>()
-            .register_component::<CEulerAngles>()
-            .register_component::<CGizmo>()
-            .register_component::<CMeshData>()
-            .register_component::<CModelMatrix>()
-            .register_component::<CProjectionMatrix>()
-            .register_component::<CViewMatrix>()
-            .register_component::<CMVPMatrix>()
-            .register_component::<CMouseBtnState>()
-            .register_component::<CScene>()
-            .register_component::<CTag>()
-            .register_component::<CCircle>()
-            .register_component::<CTransform>()
-            .register_component::<CTransform2D>()
-            .register_component::<CQuad>()
-            .register_component::<CSprite>()
-            .register_component::<CTarget>()
-            .register_component::<CVelocity>()
-            .register_component::<CVelocity2D>()
-            .register_component::<()>(); // empty component
+        crate::register_components!(
+            registry,
+            CAnchorToEntity,
+            CAttachPoints,
+            CAudioListener,
+            CCameraTarget,
+            CChildren,
+            CCollisionLayer,
+            CColorByValue,
+            CDamageOnContact,
+            CDisabled,
+            CDistance,
+            CEulerAngles,
+            CFaceVelocity,
+            CVelocityClamp2D,
+            CFlash,
+            CForceField,
+            CGizmo,
+            CHealth,
+            CHoming,
+            CInvulnerable,
+            CRespawn,
+            CMagnet,
+            CMeshData,
+            CModelMatrix,
+            CMotionBlur,
+            CProjectionMatrix,
+            CViewMatrix,
+            CMVPMatrix,
+            CName,
+            CParallaxDepth,
+            CDrawLayer,
+            CLayerOffset,
+            CPatrol,
+            CMouseBtnState,
+            CRestTime,
+            CScene,
+            CShape,
+            CShard,
+            CSleeping,
+            CSoundEmitter,
+            CStatic,
+            CTag,
+            CCircle,
+            CTransform,
+            CTransform2D,
+            CModelMatrix2D,
+            CTransformDirty,
+            CWorldTransform2D,
+            CQuad,
+            CScreenSprite,
+            CSprite,
+            CSpriteAnimation,
+            CTarget,
+            CTimeToLive,
+            CVelocity,
+            CVelocity2D,
+            (), // empty component
+        );
     }
 }
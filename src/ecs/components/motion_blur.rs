@@ -0,0 +1,96 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+use super::components::{CTransform2D, CVelocity2D};
+
+/**
+* stretches the sprite along its CVelocity2D so fast things (eg. bullets)
+* look blurred. the sprite is 1 + speed * scale times longer in the direction
+* it's moving, around its center
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct CMotionBlur {
+    pub scale: f32,
+}
+
+impl CMotionBlur {
+    /**
+     * the sprite's model matrix with the stretch applied in world space, so
+     * it follows the velocity whatever the sprite's rotation
+     */
+    pub fn model(&self, transform: &CTransform2D, velocity: &CVelocity2D) -> glm::Mat4 {
+        let velocity = glm::vec2(velocity.x, velocity.y);
+        let speed = velocity.magnitude();
+        if speed <= f32::EPSILON {
+            return transform.to_matrix();
+        }
+
+        // I + (k - 1) * d * d^T stretches by k along d only
+        let direction = glm::vec3(velocity.x / speed, velocity.y / speed, 0.0);
+        let stretch = glm::Mat3::identity()
+            + direction * direction.transpose() * (speed * self.scale.max(0.0));
+
+        let translate = glm::vec3(transform.translate.x, transform.translate.y, 0.0);
+        let centered = CTransform2D {
+            translate: glm::vec2(0.0, 0.0),
+            ..*transform
+        };
+
+        glm::translation(&translate) * glm::mat3_to_mat4(&stretch) * centered.to_matrix()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::qp_ecs::components::CQuad;
+
+    // the quad's extent along axis after the model matrix
+    fn extent(model: &glm::Mat4, axis: glm::Vec2) -> f32 {
+        let quad = CQuad {
+            width: 10.0,
+            height: 4.0,
+            center_x: 0.0,
+            center_y: 0.0,
+        };
+        let projected = quad
+            .positions()
+            .iter()
+            .map(|pos| (model * pos).xy().dot(&axis))
+            .collect::<Vec<_>>();
+
+        projected.iter().cloned().fold(f32::MIN, f32::max)
+            - projected.iter().cloned().fold(f32::MAX, f32::min)
+    }
+
+    #[test]
+    fn motion_blur_stretches_along_velocity() {
+        let blur = CMotionBlur { scale: 0.01 };
+        let transform = CTransform2D {
+            translate: glm::vec2(50.0, 20.0),
+            rotate: 0.7,
+            ..CTransform2D::default()
+        };
+        let plain = transform.to_matrix();
+
+        let stopped = blur.model(&transform, &CVelocity2D { x: 0.0, y: 0.0 });
+        assert_eq!(stopped, plain);
+
+        // 300 px/s diagonally makes it 4 times longer that way
+        let diagonal = 300.0 / 2.0_f32.sqrt();
+        let velocity = CVelocity2D {
+            x: diagonal,
+            y: diagonal,
+        };
+        let moving = blur.model(&transform, &velocity);
+        let along = glm::vec2(1.0, 1.0).normalize();
+        let across = glm::vec2(-1.0, 1.0).normalize();
+
+        assert!((extent(&moving, along) - 4.0 * extent(&plain, along)).abs() < 1e-3);
+        assert!((extent(&moving, across) - extent(&plain, across)).abs() < 1e-3);
+
+        // still centered on the entity
+        let center = moving * glm::vec4(0.0, 0.0, 0.0, 1.0);
+        assert!((center.xy() - transform.translate).magnitude() < 1e-4);
+    }
+}
@@ -0,0 +1,124 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+/**
+* per-entity parallax
+*
+* a depth of 0.0 moves with the world, 1.0 stays fixed to the screen and
+* anything in between trails the camera proportionally
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct CParallaxDepth(pub f32);
+
+impl CParallaxDepth {
+    /**
+     * returns the world position to render at given the camera translation
+     */
+    pub fn apply(&self, translate: &glm::Vec2, camera: &glm::Vec2) -> glm::Vec2 {
+        translate + camera * self.0
+    }
+}
+
+/**
+* where a sprite sits in the scene's depth, for the SpriteRenderer
+*
+* layer is the draw order, lower layers are drawn first and end up behind.
+* parallax is how much of the camera's movement the sprite shows on screen:
+* 1.0 moves with the world, 0.3 is a far off background that shifts 30% as
+* much, and above 1.0 is a foreground that rushes past. this covers what
+* CParallaxDepth does, which is the same as a parallax of 1.0 - depth
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct CLayerOffset {
+    pub layer: i32,
+    pub parallax: f32,
+}
+
+impl Default for CLayerOffset {
+    fn default() -> Self {
+        Self {
+            layer: 0,
+            parallax: 1.0,
+        }
+    }
+}
+
+/**
+* the draw order of a sprite, without any parallax. lower layers are drawn
+* first and end up behind, and sprites on the same layer keep the order they
+* were created in. takes the place of CLayerOffset's layer if it has both
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CDrawLayer(pub i32);
+
+impl CLayerOffset {
+    /**
+     * returns the world position to render at given the camera translation
+     */
+    pub fn apply(&self, translate: &glm::Vec2, camera: &glm::Vec2) -> glm::Vec2 {
+        translate + camera * (1.0 - self.parallax)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // screen position is the world position relative to the camera
+    fn screen_pos(depth: &CParallaxDepth, translate: &glm::Vec2, camera: &glm::Vec2) -> glm::Vec2 {
+        depth.apply(translate, camera) - camera
+    }
+
+    #[test]
+    fn parallax_depth_zero_moves_with_world() {
+        let depth = CParallaxDepth(0.0);
+        let translate = glm::vec2(100.0, 50.0);
+
+        let before = screen_pos(&depth, &translate, &glm::vec2(0.0, 0.0));
+        let after = screen_pos(&depth, &translate, &glm::vec2(30.0, -20.0));
+
+        assert_eq!(after - before, glm::vec2(-30.0, 20.0));
+    }
+
+    #[test]
+    fn parallax_depth_one_stays_on_screen() {
+        let depth = CParallaxDepth(1.0);
+        let translate = glm::vec2(100.0, 50.0);
+
+        let before = screen_pos(&depth, &translate, &glm::vec2(0.0, 0.0));
+        let after = screen_pos(&depth, &translate, &glm::vec2(30.0, -20.0));
+
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn layer_offset_shifts_by_parallax() {
+        let translate = glm::vec2(100.0, 50.0);
+        let camera_delta = glm::vec2(30.0, -20.0);
+        let shift = |parallax| {
+            let offset = CLayerOffset {
+                parallax,
+                ..CLayerOffset::default()
+            };
+            let before = offset.apply(&translate, &glm::vec2(0.0, 0.0));
+            let after = offset.apply(&translate, &camera_delta) - camera_delta;
+
+            after - before
+        };
+
+        assert!((shift(0.3) + camera_delta * 0.3).magnitude() < 1e-5);
+        assert_eq!(shift(1.0), -camera_delta);
+
+        // the same as the depth it replaces
+        let depth = CParallaxDepth(0.7);
+        let offset = CLayerOffset {
+            parallax: 0.3,
+            ..CLayerOffset::default()
+        };
+        assert!(
+            (depth.apply(&translate, &camera_delta) - offset.apply(&translate, &camera_delta))
+                .magnitude()
+                < 1e-5
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+/**
+* walks an entity through a list of waypoints at a fixed speed
+*
+* with `looping` it goes back to the first waypoint after the last one,
+* otherwise it stops there
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct CPatrol {
+    pub waypoints: Vec<glm::Vec2>,
+    pub speed: f32,
+    pub looping: bool,
+
+    #[serde(default)]
+    pub current: usize,
+}
+
+impl CPatrol {
+    pub fn new(waypoints: Vec<glm::Vec2>, speed: f32, looping: bool) -> Self {
+        Self {
+            waypoints,
+            speed,
+            looping,
+            current: 0,
+        }
+    }
+
+    pub fn target(&self) -> Option<&glm::Vec2> {
+        self.waypoints.get(self.current)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.current >= self.waypoints.len()
+    }
+}
@@ -11,6 +11,18 @@ pub struct CQuad {
 }
 
 impl CQuad {
+    /**
+     * a quad `height` tall in the same proportions as `size`, eg. an atlas
+     * cell's, so the texture isn't stretched
+     */
+    pub fn fit_height(size: glm::Vec2, height: f32) -> Self {
+        Self {
+            width: height * size.x / size.y,
+            height,
+            ..Self::default()
+        }
+    }
+
     pub fn indices() -> [i32; 6] { [0, 1, 3, 1, 2, 3] }
     pub fn positions(&self) -> [glm::Vec4; 4] {
         let pos1 = glm::vec4(self.center_x + (self.width / 2.0), self.center_y + (self.height / 2.0), 0.0, 1.0);
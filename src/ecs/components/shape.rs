@@ -0,0 +1,161 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+/**
+* cuts a CSprite's quad into an anti-aliased shape in the sprite shader, so
+* circles and rounded panels don't need a texture
+*
+* sizes are in the quad's units, before the transform's scale. softness is
+* how wide the faded edge is; 0.0 still smooths the edge over about a pixel
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum CShape {
+    /**
+     * centered in the quad, so a quad 2 * radius wide fits it exactly
+     */
+    Circle { radius: f32, softness: f32 },
+
+    /**
+     * fills the quad. the corner radius is capped at half the shorter side
+     */
+    RoundedRect { corner_radius: f32, softness: f32 },
+}
+
+impl CShape {
+    /**
+     * the vertex shape params for a quad with the given half size: half
+     * width, half height, corner radius and softness
+     */
+    pub fn params(&self, half_size: glm::Vec2) -> glm::Vec4 {
+        match *self {
+            Self::Circle { radius, softness } => glm::vec4(radius, radius, radius, softness),
+            Self::RoundedRect {
+                corner_radius,
+                softness,
+            } => {
+                let corner_radius = corner_radius.clamp(0.0, half_size.x.min(half_size.y));
+
+                glm::vec4(half_size.x, half_size.y, corner_radius, softness)
+            }
+        }
+    }
+}
+
+/**
+* signed distance from local to the edge of the shape, negative inside.
+* mirrors the sprite shader
+*/
+pub fn shape_distance(local: glm::Vec2, params: glm::Vec4) -> f32 {
+    let half = params.xy();
+    let radius = params.z;
+
+    let q = glm::abs(&local) - half + glm::vec2(radius, radius);
+    let outside = glm::max(&q, 0.0).magnitude();
+    let inside = q.x.max(q.y).min(0.0);
+
+    outside + inside - radius
+}
+
+/**
+* how much of the pixel at local the shape covers, 0.0 to 1.0. mirrors the
+* sprite shader, with aa standing in for the shader's pixel width
+*/
+pub fn shape_coverage(local: glm::Vec2, params: glm::Vec4, aa: f32) -> f32 {
+    let edge = params.w.max(aa);
+    if edge <= 0.0 {
+        return match shape_distance(local, params) <= 0.0 {
+            true => 1.0,
+            false => 0.0,
+        };
+    }
+
+    let t = ((shape_distance(local, params) + edge) / (2.0 * edge)).clamp(0.0, 1.0);
+
+    1.0 - t * t * (3.0 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{
+        qp_ecs::components::{CQuad, CSprite},
+        qp_gfx::Mesh,
+    };
+
+    fn sprite(width: f32, height: f32) -> CSprite {
+        let quad = CQuad {
+            width,
+            height,
+            center_x: 0.0,
+            center_y: 0.0,
+        };
+
+        CSprite::new(&quad, None, None)
+    }
+
+    #[test]
+    fn shape_params_reach_the_vertices() {
+        let sprite = sprite(40.0, 20.0);
+        let shape = CShape::RoundedRect {
+            corner_radius: 5.0,
+            softness: 1.5,
+        };
+
+        let vertices = sprite.shaped_vertices(&shape);
+        let plain = sprite.vertices();
+        let corners = [(20.0, 10.0), (20.0, -10.0), (-20.0, -10.0), (-20.0, 10.0)];
+        for ((vertex, plain), (x, y)) in vertices.iter().zip(plain.iter()).zip(corners) {
+            assert_eq!(vertex.shape, glm::vec4(20.0, 10.0, 5.0, 1.5));
+            assert_eq!(vertex.local, glm::vec2(x, y));
+
+            // everything else is the plain sprite
+            assert_eq!(vertex.position, plain.position);
+            assert_eq!(vertex.tex_coords, plain.tex_coords);
+        }
+        assert_eq!(plain[0].shape, glm::Vec4::zeros());
+
+        let circle = CShape::Circle {
+            radius: 8.0,
+            softness: 0.0,
+        };
+        assert_eq!(
+            sprite.shaped_vertices(&circle)[0].shape,
+            glm::vec4(8.0, 8.0, 8.0, 0.0)
+        );
+
+        // too round for the quad
+        let pill = CShape::RoundedRect {
+            corner_radius: 50.0,
+            softness: 0.0,
+        };
+        assert_eq!(pill.params(glm::vec2(20.0, 10.0)).z, 10.0);
+    }
+
+    #[test]
+    fn shape_square_corners_is_plain_rect() {
+        let params = CShape::RoundedRect {
+            corner_radius: 0.0,
+            softness: 0.0,
+        }
+        .params(glm::vec2(20.0, 10.0));
+
+        for x in -25..=25 {
+            for y in -15..=15 {
+                let local = glm::vec2(x as f32, y as f32);
+                let in_rect = local.x.abs() <= 20.0 && local.y.abs() <= 10.0;
+
+                assert_eq!(shape_coverage(local, params, 0.0), in_rect as i32 as f32);
+            }
+        }
+
+        // rounded corners cut the corner off but keep the edges
+        let rounded = glm::vec4(20.0, 10.0, 5.0, 0.0);
+        assert_eq!(shape_coverage(glm::vec2(19.5, 9.5), rounded, 0.0), 0.0);
+        assert_eq!(shape_coverage(glm::vec2(19.5, 0.0), rounded, 0.0), 1.0);
+
+        // and a circle is round
+        let circle = glm::vec4(8.0, 8.0, 8.0, 0.0);
+        assert!((shape_distance(glm::vec2(3.0, 4.0), circle) + 3.0).abs() < 1e-5);
+        assert_eq!(shape_coverage(glm::vec2(6.0, 6.0), circle, 0.0), 0.0);
+    }
+}
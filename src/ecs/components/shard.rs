@@ -0,0 +1,22 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+/**
+* a piece of a shattered sprite that spins and fades out over its lifetime,
+* see `s_shatter`
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct CShard {
+    pub lifetime: f32,
+    pub remaining: f32,
+
+    /**
+     * radians per second
+     */
+    pub spin: f32,
+
+    /**
+     * the sprite's alpha when it shattered
+     */
+    pub alpha: f32,
+}
@@ -0,0 +1,14 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+/**
+* opts a body into sleeping and tracks how long it has been at rest
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct CRestTime(pub f32);
+
+/**
+* marks a resting body that movement systems can skip until it is woken
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct CSleeping;
@@ -0,0 +1,41 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+/**
+* a sound attached to an entity
+*
+* flip `playing` to start or stop it. the channel is owned by the audio
+* backend and is only set while the sound is playing. `failed` is set when
+* the clip couldn't be played, the emitter stays silent until it's cleared
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CSoundEmitter {
+    pub clip: String,
+    pub looping: bool,
+    pub playing: bool,
+    pub volume: f32,
+
+    #[serde(skip)]
+    pub channel: Option<u64>,
+    #[serde(skip)]
+    pub failed: bool,
+}
+
+impl CSoundEmitter {
+    pub fn new(clip: &str, looping: bool, volume: f32) -> Self {
+        Self {
+            clip: clip.to_string(),
+            looping,
+            playing: false,
+            volume,
+            channel: None,
+            failed: false,
+        }
+    }
+}
+
+/**
+* the entity spatial audio is heard from. there should only be one
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct CAudioListener;
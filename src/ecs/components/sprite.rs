@@ -6,7 +6,7 @@ use crate::{
     schemas::sprite::TextureAtlas,
 };
 
-use super::components::CQuad;
+use super::components::{CQuad, CShape};
 
 #[derive(Debug, Component, Serialize, Deserialize, Clone, PartialEq, Default)]
 pub struct CSprite {
@@ -14,6 +14,14 @@ pub struct CSprite {
     pub color: glm::Vec4,
     pub texture_atlas: Option<TextureAtlas>,
 
+    /**
+     * draw with this shader instead of the renderer's, eg. for a dissolve
+     * effect. the vertices are already through the camera matrices, so any
+     * shader that takes the sprite vertex layout works
+     */
+    #[serde(default)]
+    pub shader: Option<u64>,
+
     mvp: glm::Mat4,
     positions: [glm::Vec4; 4],
 }
@@ -31,14 +39,64 @@ impl CSprite {
                 _ => glm::vec4(1.0, 1.0, 1.0, 1.0),
             },
             texture_atlas,
+            shader: None,
             mvp: glm::Mat4::identity(),
             positions: quad.positions(),
         }
     }
 
+    /**
+     * only draws one of the two triangles the quad is made of, either side of
+     * the bottom right to top left diagonal. upper keeps the top right one.
+     * the texture stays where it was on the quad
+     */
+    pub fn into_triangle(mut self, upper: bool) -> Self {
+        // the other triangle is flattened onto the shared bottom right corner
+        match upper {
+            true => self.positions[2] = self.positions[1],
+            false => self.positions[0] = self.positions[1],
+        }
+
+        self
+    }
+
+    pub fn with_shader(mut self, shader: u64) -> Self {
+        self.shader = Some(shader);
+        self
+    }
+
     pub fn apply_matrices(&mut self, model: glm::Mat4, view: glm::Mat4, projection: glm::Mat4) {
         self.mvp = projection * view * model;
     }
+
+    /**
+     * the center and size of the quad the sprite was made with, before any
+     * matrices are applied
+     */
+    pub fn bounds(&self) -> (glm::Vec2, glm::Vec2) {
+        let center = (self.positions[0].xy() + self.positions[2].xy()) / 2.0;
+        let size = (self.positions[0].xy() - self.positions[2].xy()).abs();
+
+        (center, size)
+    }
+
+    /**
+     * the mesh's vertices, cut into the shape by the sprite shader
+     */
+    pub fn shaped_vertices(&self, shape: &CShape) -> Vec<Vertex> {
+        let (center, size) = self.bounds();
+        let params = shape.params(size / 2.0);
+
+        self.vertices()
+            .into_iter()
+            .zip(self.positions.iter())
+            .map(|(vertex, pos)| Vertex {
+                local: pos.xy() - center,
+                shape: params,
+                ..vertex
+            })
+            .collect()
+    }
 }
 
 impl Mesh for CSprite {
@@ -72,25 +130,38 @@ impl Mesh for CSprite {
                 color: self.color,
                 tex_coords: glm::vec2((1.0 / x_dim) + x_offset, (1.0 / y_dim) + y_offset),
                 tex_index: 0.0,
+                ..Vertex::default()
             },
             Vertex {
                 position: pos2.xyz(),
                 color: self.color,
                 tex_coords: glm::vec2((1.0 / x_dim) + x_offset, (0.0 / y_dim) + y_offset),
                 tex_index: 0.0,
+                ..Vertex::default()
             },
             Vertex {
                 position: pos3.xyz(),
                 color: self.color,
                 tex_coords: glm::vec2((0.0 / x_dim) + x_offset, (0.0 / y_dim) + y_offset),
                 tex_index: 0.0,
+                ..Vertex::default()
             },
             Vertex {
                 position: pos4.xyz(),
                 color: self.color,
                 tex_coords: glm::vec2((0.0 / x_dim) + x_offset, (1.0 / y_dim) + y_offset),
                 tex_index: 0.0,
+                ..Vertex::default()
             },
         ]
     }
 }
+
+/**
+* marks a CSprite as part of the HUD
+*
+* its CTransform2D is in window pixels, with (0, 0) at the bottom left, and it
+* is drawn by the ScreenSpriteRenderer instead of through the world camera
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct CScreenSprite;
@@ -0,0 +1,9 @@
+use super::super::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+/**
+* marks an entity that never moves, eg. tiles and placed props. static
+* entities go in the StaticBvh instead of anything rebuilt every frame
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct CStatic;
@@ -78,15 +78,27 @@ impl CTransform2D {
      * 3. scale
      */
     pub fn to_matrix(&self) -> glm::Mat4 {
-        let translate = glm::vec3(self.translate.x, self.translate.y, 0.0);
-        let rotate = glm::vec3(0.0, 0.0, 1.0);
-        let scale = glm::vec3(self.scale.x, self.scale.y, 0.0);
+        self.model_matrix()
+    }
 
-        let matrix = glm::Mat4::identity();
-        let matrix = glm::translate(&matrix, &translate);
-        let matrix = glm::rotate(&matrix, self.rotate, &rotate);
+    /**
+     * the same matrix as `to_matrix`, written out instead of built from
+     * glm::rotate, so it's one sin_cos and no axis normalising. see
+     * CModelMatrix2D to skip even that for things that don't move
+     *
+     * z is scaled by 0, flattening everything onto the xy plane
+     */
+    #[rustfmt::skip]
+    pub fn model_matrix(&self) -> glm::Mat4 {
+        let (sin, cos) = self.rotate.sin_cos();
+        let (sx, sy) = (self.scale.x, self.scale.y);
 
-        glm::scale(&matrix, &scale)
+        glm::mat4(
+            cos * sx, -sin * sy, 0.0, self.translate.x,
+            sin * sx, cos * sy, 0.0, self.translate.y,
+            0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        )
     }
 
     /*
@@ -97,3 +109,106 @@ impl CTransform2D {
         rotate2d(&glm::vec2(0.0, 1.0), self.rotate).normalize()
     }
 }
+
+/**
+* caches the model matrix of an entity's CTransform2D. the matrix is only
+* recomputed when the transform it was made from changes, so the fields of
+* CTransform2D can stay public and be written to as usual. opt-in, worth it
+* for lots of sprites that mostly stand still
+*/
+#[derive(Debug, Component, Clone, Copy, PartialEq)]
+pub struct CModelMatrix2D {
+    source: Option<CTransform2D>,
+    matrix: glm::Mat4,
+}
+
+impl Default for CModelMatrix2D {
+    fn default() -> Self {
+        Self {
+            source: None,
+            matrix: glm::Mat4::identity(),
+        }
+    }
+}
+
+impl CModelMatrix2D {
+    /**
+     * the model matrix of transform, from the cache if transform hasn't
+     * changed since the last call
+     */
+    pub fn model_matrix(&mut self, transform: &CTransform2D) -> glm::Mat4 {
+        if self.source.as_ref() != Some(transform) {
+            self.matrix = transform.model_matrix();
+            self.source = Some(*transform);
+        }
+
+        self.matrix
+    }
+
+    /**
+     * forces the next `model_matrix` to recompute
+     */
+    pub fn invalidate(&mut self) {
+        self.source = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform() -> CTransform2D {
+        CTransform2D {
+            translate: glm::vec2(5.0, -4.0),
+            rotate: std::f32::consts::FRAC_PI_6,
+            scale: glm::vec2(2.0, 3.0),
+        }
+    }
+
+    #[test]
+    fn model_matrix_is_translate_rotate_scale() {
+        let transform = transform();
+        let (sin, cos) = (0.5_f32, 3.0_f32.sqrt() / 2.0);
+        #[rustfmt::skip]
+        let expected = glm::mat4(
+            cos * 2.0, -sin * 3.0, 0.0, 5.0,
+            sin * 2.0, cos * 3.0, 0.0, -4.0,
+            0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        );
+
+        assert!((transform.model_matrix() - expected).abs().max() < 1e-5);
+        assert_eq!(transform.to_matrix(), transform.model_matrix());
+
+        // z is still flattened, as it was when built with glm
+        let point = transform.to_matrix() * glm::vec4(1.0, 1.0, 7.0, 1.0);
+        assert_eq!(point.z, 0.0);
+
+        // a corner of a unit quad ends up scaled, then rotated, then moved
+        let corner = transform.model_matrix() * glm::vec4(1.0, 1.0, 0.0, 1.0);
+        let by_hand = glm::vec2(2.0 * cos - 3.0 * sin + 5.0, 2.0 * sin + 3.0 * cos - 4.0);
+        assert!(glm::distance(&corner.xy(), &by_hand) < 1e-5);
+    }
+
+    #[test]
+    fn model_matrix_cache_follows_changes() {
+        let mut transform = transform();
+        let mut cache = CModelMatrix2D::default();
+
+        assert_eq!(cache.model_matrix(&transform), transform.model_matrix());
+        assert_eq!(cache.source, Some(transform));
+
+        for change in [
+            |t: &mut CTransform2D| t.translate.x += 1.0,
+            |t: &mut CTransform2D| t.rotate = 1.2,
+            |t: &mut CTransform2D| t.scale.y = -1.0,
+        ] {
+            change(&mut transform);
+            assert_eq!(cache.model_matrix(&transform), transform.model_matrix());
+        }
+
+        cache.invalidate();
+        assert_eq!(cache.source, None);
+        assert_eq!(cache.model_matrix(&transform), transform.model_matrix());
+    }
+}
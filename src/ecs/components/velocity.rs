@@ -10,6 +10,22 @@ pub struct CVelocity2D {
 #[derive(Debug, Component, Serialize, Deserialize, Default, Clone, PartialEq)]
 pub struct CSpeed(f32);
 
+/**
+* turns the entity to face the way its CVelocity2D is moving. see
+* `s_face_velocity`
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct CFaceVelocity;
+
+/**
+* caps how fast the entity's CVelocity2D can go, whichever way it's going.
+* see `s_clamp_velocity`
+*/
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct CVelocityClamp2D {
+    pub max_speed: f32,
+}
+
 /**
 * 3D velocity vector
 */
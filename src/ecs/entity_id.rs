@@ -0,0 +1,19 @@
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/**
+* a stable handle for an entity, handed out in spawn order and never reused
+*
+* unlike a VersionedIndex it says nothing about where the entity is stored,
+* so it's what saves and cross-entity references (eg. CChildren) should hold.
+* `EntityManager::resolve` turns it back into a VersionedIndex for access
+*/
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Serialize, Deserialize)]
+pub struct EntityId(pub u64);
+
+impl fmt::Display for EntityId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
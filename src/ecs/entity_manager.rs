@@ -1,11 +1,75 @@
+use std::{any::TypeId, collections::HashMap};
+
 use super::{
+    hooks::{ComponentHooks, HookEvent},
     indexed_array::{IndexedArray, VersionedIndex, VersionedIndexAllocator},
-    prelude::Component,
+    prelude::{
+        components::{CDisabled, CName, CTag},
+        Bundle, Component, EntityId,
+    },
 };
 use crate::{prelude::qp_core::AnyMap, QPResult};
 
 type EntityMap<C> = IndexedArray<C>;
 
+// three component maps borrowed at once, for joins
+type ComponentMapsMut<'a, A, B, C> = (
+    &'a mut EntityMap<A>,
+    &'a mut EntityMap<B>,
+    &'a mut EntityMap<C>,
+    &'a VersionedIndexAllocator,
+);
+
+// what the manager needs to do to a component's map without knowing its type,
+// one per registered component
+#[derive(Debug, Clone, Copy)]
+struct ComponentVTable {
+    type_id: TypeId,
+
+    // the type name without the module path
+    name: &'static str,
+
+    // empties the map without unregistering it
+    clear: fn(&mut AnyMap),
+
+    // whether an entity has the component
+    has: fn(&AnyMap, &VersionedIndex) -> bool,
+
+    // reserves or reports room in the map for entity indices up to a count
+    reserve: fn(&mut AnyMap, usize),
+    capacity: fn(&AnyMap) -> usize,
+
+    // how many of the component are stored
+    len: fn(&AnyMap) -> usize,
+}
+
+impl ComponentVTable {
+    fn of<C: Component + PartialEq + 'static>() -> Self {
+        Self {
+            type_id: TypeId::of::<C>(),
+            name: short_type_name::<C>(),
+            clear: clear_map::<C>,
+            has: has_component::<C>,
+            reserve: reserve_map::<C>,
+            capacity: map_capacity::<C>,
+            len: map_len::<C>,
+        }
+    }
+}
+
+/**
+* the size of one component's map, see `EntityManager::component_stats`. len
+* counts the components stored, including ones left behind by deleted
+* entities until their slot is reused. capacity is how many entity indices
+* fit before the map grows
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentStoreStats {
+    pub name: &'static str,
+    pub len: usize,
+    pub capacity: usize,
+}
+
 #[derive(Debug)]
 pub struct EntityManager {
     entity_allocator: VersionedIndexAllocator,
@@ -13,6 +77,16 @@ pub struct EntityManager {
 
     entities: Vec<VersionedIndex>,
     to_delete: Vec<VersionedIndex>,
+
+    // stable ids, both ways. ids are never reused, not even after a clear
+    next_id: u64,
+    ids: HashMap<EntityId, VersionedIndex>,
+    id_of: HashMap<VersionedIndex, EntityId>,
+
+    // the registered components, in registration order
+    components: Vec<ComponentVTable>,
+
+    hooks: ComponentHooks,
 }
 
 impl EntityManager {
@@ -22,36 +96,136 @@ impl EntityManager {
             component_maps: AnyMap::new(),
             entities: Vec::<VersionedIndex>::new(),
             to_delete: Vec::<VersionedIndex>::new(),
+            next_id: 0,
+            ids: HashMap::new(),
+            id_of: HashMap::new(),
+            components: vec![],
+            hooks: ComponentHooks::default(),
         };
 
         Ok(entity_manager)
     }
 
+    /**
+     * registering a component that is already registered does nothing, the
+     * map and anything stored in it are kept
+     */
     pub fn register_component<C: Component + PartialEq + 'static>(&mut self) -> &mut Self {
+        if self.is_registered::<C>() {
+            return self;
+        }
+
         self.component_maps
             .insert::<EntityMap<C>>(EntityMap::<C>::default());
+        self.components.push(ComponentVTable::of::<C>());
 
         self
     }
 
+    /**
+     * the type names (without the module path) of every registered component,
+     * in the order they were registered
+     */
+    pub fn registered_component_names(&self) -> Vec<&'static str> {
+        self.components.iter().map(|component| component.name).collect()
+    }
+
+    /**
+     * calls hook with the entity the next time the manager is flushed, once
+     * for every time a C is added to an entity that didn't have one
+     */
+    pub fn on_add<C: Component + PartialEq + 'static>(
+        &mut self,
+        hook: impl FnMut(&VersionedIndex) + 'static,
+    ) -> &mut Self {
+        self.hooks
+            .register(HookEvent::Add, TypeId::of::<C>(), Box::new(hook));
+
+        self
+    }
+
+    /**
+     * calls hook with the entity the next time the manager is flushed, once
+     * for every time a C is removed, including when its entity is deleted
+     */
+    pub fn on_remove<C: Component + PartialEq + 'static>(
+        &mut self,
+        hook: impl FnMut(&VersionedIndex) + 'static,
+    ) -> &mut Self {
+        self.hooks
+            .register(HookEvent::Remove, TypeId::of::<C>(), Box::new(hook));
+
+        self
+    }
+
+    pub fn is_registered<C: Component + PartialEq + 'static>(&self) -> bool {
+        self.component_maps.get::<EntityMap<C>>().is_some()
+    }
+
     pub fn create(&mut self) -> VersionedIndex {
         let entity = self.entity_allocator.allocate();
+        let id = EntityId(self.next_id);
+        self.next_id += 1;
 
         self.entities.push(entity);
+        self.ids.insert(id, entity);
+        self.id_of.insert(entity, id);
 
         entity
     }
 
+    /**
+     * the stable id the entity was given when it was spawned
+     */
+    pub fn entity_id(&self, entity: &VersionedIndex) -> Option<EntityId> {
+        if !self.entity_allocator.validate(entity) {
+            return None;
+        }
+
+        self.id_of.get(entity).copied()
+    }
+
+    /**
+     * where the entity with this id currently lives, if it's still alive
+     */
+    pub fn resolve(&self, id: EntityId) -> Option<VersionedIndex> {
+        self.ids
+            .get(&id)
+            .copied()
+            .filter(|entity| self.entity_allocator.validate(entity))
+    }
+
+    /**
+     * marks the entity to be deleted at the next flush. until then it and its
+     * components are still there, so everything else that runs this frame
+     * sees the whole entity. the app flushes once a frame, after the
+     * controllers and before the renderers, see `GlobalRegistry::update_deletions`
+     */
     pub fn set_to_delete(&mut self, entity: VersionedIndex) {
         self.to_delete.push(entity);
     }
 
+    /**
+     * true if the entity was marked with `set_to_delete` and hasn't been
+     * flushed yet
+     */
+    pub fn is_pending_delete(&self, entity: &VersionedIndex) -> bool {
+        self.to_delete.contains(entity)
+    }
+
+    /**
+     * true if the entity is switched off with a CDisabled. never true if
+     * CDisabled isn't registered
+     */
+    pub fn is_disabled(&self, entity: &VersionedIndex) -> bool {
+        self.is_registered::<CDisabled>() && self.get::<CDisabled>(entity).is_some()
+    }
+
     pub fn flush(&mut self) {
-        for entity in self.to_delete.iter_mut() {
-            self.entity_allocator.deallocate(*entity);
-        }
+        let to_delete = std::mem::take(&mut self.to_delete);
+        self.deallocate_many(&to_delete);
 
-        self.to_delete.clear();
+        self.hooks.fire();
     }
 
     pub fn add<C: Component + std::fmt::Debug + PartialEq + 'static>(
@@ -60,15 +234,13 @@ impl EntityManager {
         component: C,
     ) {
         match self.component_maps.get_mut::<EntityMap<C>>() {
-            None => {
-                #[cfg(debug_assertions)]
-                println!(
-                    "component {:?} has not been registered",
-                    std::any::type_name::<C>()
-                );
-            }
+            None => unregistered::<C>(),
             Some(cmp_map) => {
-                cmp_map.set(&entity, component);
+                if cmp_map.get(entity).is_none() {
+                    self.hooks.queue(HookEvent::Add, TypeId::of::<C>(), *entity);
+                }
+
+                cmp_map.set(entity, component);
             }
         }
     }
@@ -78,15 +250,13 @@ impl EntityManager {
         entity: &VersionedIndex,
     ) {
         match self.component_maps.get_mut::<EntityMap<C>>() {
-            None => {
-                #[cfg(debug_assertions)]
-                println!(
-                    "component {:?} has not been registered",
-                    std::any::type_name::<C>()
-                );
-            }
+            None => unregistered::<C>(),
             Some(cmp_map) => {
-                cmp_map.unset(&entity);
+                if cmp_map.get(entity).is_some() {
+                    self.hooks.queue(HookEvent::Remove, TypeId::of::<C>(), *entity);
+                }
+
+                cmp_map.unset(entity);
             }
         }
     }
@@ -98,13 +268,9 @@ impl EntityManager {
 
         match self.component_maps.get::<EntityMap<C>>() {
             None => {
-                #[cfg(debug_assertions)]
-                println!(
-                    "component {:?} has not been registered",
-                    std::any::type_name::<C>()
-                );
+                unregistered::<C>();
 
-                return None;
+                None
             }
             Some(cmp_map) => match cmp_map.get(entity) {
                 None => None,
@@ -123,13 +289,9 @@ impl EntityManager {
 
         match self.component_maps.get_mut::<EntityMap<C>>() {
             None => {
-                #[cfg(debug_assertions)]
-                println!(
-                    "component {:?} has not been registered",
-                    std::any::type_name::<C>()
-                );
+                unregistered::<C>();
 
-                return None;
+                None
             }
             Some(cmp_map) => match cmp_map.get_mut(entity) {
                 None => None,
@@ -146,6 +308,29 @@ impl EntityManager {
         cmp_map.get_entities(&self.entity_allocator)
     }
 
+    pub(crate) fn component_map<C: Component + PartialEq + 'static>(
+        &self,
+    ) -> Option<(&EntityMap<C>, &VersionedIndexAllocator)> {
+        let cmp_map = self.component_maps.get::<EntityMap<C>>()?;
+
+        Some((cmp_map, &self.entity_allocator))
+    }
+
+    pub(crate) fn component_maps_mut<A, B, C>(
+        &mut self,
+    ) -> Option<ComponentMapsMut<'_, A, B, C>>
+    where
+        A: Component + PartialEq + 'static,
+        B: Component + PartialEq + 'static,
+        C: Component + PartialEq + 'static,
+    {
+        let (a, b, c) = self
+            .component_maps
+            .get_three_mut::<EntityMap<A>, EntityMap<B>, EntityMap<C>>()?;
+
+        Some((a, b, c, &self.entity_allocator))
+    }
+
     pub fn query<C: Component + PartialEq + 'static>(&self, filter: C) -> Vec<VersionedIndex> {
         let Some(cmp_map) = self.component_maps.get::<EntityMap<C>>() else {
             return vec![];
@@ -165,16 +350,182 @@ impl EntityManager {
         result
     }
 
-    pub fn reset(&mut self) -> QPResult<()> {
-        for entity in self.entities.iter() {
-            self.entity_allocator.deallocate(*entity);
+    /**
+     * entities with a matching CTag, in spawn order. the order only depends
+     * on the spawn sequence, so replays see the same results every run.
+     * disabled entities are left out
+     */
+    pub fn get_by_tag(&self, tag: &str) -> Vec<VersionedIndex> {
+        let mut entities = self.in_spawn_order::<CTag>(|cmp| cmp.tag == tag);
+        entities.retain(|entity| !self.is_disabled(entity));
+
+        entities
+    }
+
+    /**
+     * entities with a matching CName, in spawn order
+     */
+    pub fn get_by_name(&self, name: &str) -> Vec<VersionedIndex> {
+        self.in_spawn_order::<CName>(|cmp| cmp.name == name)
+    }
+
+    /**
+     * like `query_all`, but ordered by when the entities were created
+     */
+    pub fn query_all_in_spawn_order<C: Component + PartialEq + 'static>(&self) -> Vec<VersionedIndex> {
+        self.in_spawn_order::<C>(|_| true)
+    }
+
+    fn in_spawn_order<C: Component + PartialEq + 'static>(
+        &self,
+        filter: impl Fn(&C) -> bool,
+    ) -> Vec<VersionedIndex> {
+        let Some(cmp_map) = self.component_maps.get::<EntityMap<C>>() else {
+            return vec![];
+        };
+
+        self.entities
+            .iter()
+            .filter(|entity| self.entity_allocator.validate(entity))
+            .filter(|entity| cmp_map.get(entity).is_some_and(&filter))
+            .copied()
+            .collect()
+    }
+
+    /**
+     * removes every entity and component but keeps the registrations. the
+     * slots are freed rather than reset, so the version keeps counting up and
+     * handles from before the clear stay invalid once their slot is reused
+     */
+    pub fn clear(&mut self) {
+        self.entity_allocator.deallocate_many(&self.entities);
+
+        for component in self.components.iter() {
+            (component.clear)(&mut self.component_maps);
+        }
+
+        self.entities.clear();
+        self.to_delete.clear();
+        self.ids.clear();
+        self.id_of.clear();
+        self.hooks.clear_pending();
+    }
+
+    /**
+     * deletes every entity with a matching CTag right away and returns how many
+     */
+    pub fn clear_entities_with_tag(&mut self, tag: &str) -> usize {
+        let entities = self.in_spawn_order::<CTag>(|cmp| cmp.tag == tag);
+        self.deallocate_many(&entities);
+
+        entities.len()
+    }
+
+    // queues on_remove hooks for the entities' components before freeing them
+    fn deallocate_many(&mut self, entities: &[VersionedIndex]) {
+        if entities.is_empty() {
+            return;
+        }
+
+        for entity in entities {
+            // the id goes with the first copy, so an entity marked twice is
+            // only removed once
+            if !self.entity_allocator.validate(entity) {
+                continue;
+            }
+            let Some(id) = self.id_of.remove(entity) else {
+                continue;
+            };
+            self.ids.remove(&id);
+
+            for component in self.components.iter() {
+                if self.hooks.is_hooked(HookEvent::Remove, &component.type_id)
+                    && (component.has)(&self.component_maps, entity)
+                {
+                    self.hooks.queue(HookEvent::Remove, component.type_id, *entity);
+                }
+            }
         }
 
+        self.entity_allocator.deallocate_many(entities);
+
+        // keeps the spawn order lookups from walking every entity ever spawned
+        let allocator = &self.entity_allocator;
+        self.entities.retain(|entity| allocator.validate(entity));
+    }
+
+    pub fn reset(&mut self) -> QPResult<()> {
+        self.entity_allocator.deallocate_many(&self.entities);
+
         self.entities.clear();
+        self.ids.clear();
+        self.id_of.clear();
 
         Ok(())
     }
 
+    /**
+     * makes room for `additional` more entities in the allocator and in
+     * every registered component's map, so a burst of spawns that size
+     * doesn't reallocate part way through a frame
+     */
+    pub fn reserve(&mut self, additional: usize) {
+        self.entity_allocator.reserve(additional);
+        self.entities.reserve(additional);
+        self.ids.reserve(additional);
+        self.id_of.reserve(additional);
+
+        let slots = self.entity_allocator.length() + additional;
+        for component in self.components.iter() {
+            (component.reserve)(&mut self.component_maps, slots);
+        }
+    }
+
+    /**
+     * how many entities there's room for before something has to grow, see
+     * `reserve`
+     */
+    pub fn capacity(&self) -> usize {
+        let components = self
+            .components
+            .iter()
+            .map(|component| (component.capacity)(&self.component_maps))
+            .min()
+            .unwrap_or(usize::MAX);
+
+        [
+            self.entity_allocator.capacity(),
+            components,
+            self.ids.capacity(),
+            self.id_of.capacity(),
+            self.entities.capacity(),
+        ]
+        .into_iter()
+        .min()
+        .unwrap_or_default()
+    }
+
+    /**
+     * how full each registered component's map is, in registration order
+     */
+    pub fn component_stats(&self) -> Vec<ComponentStoreStats> {
+        self.components
+            .iter()
+            .map(|component| ComponentStoreStats {
+                name: component.name,
+                len: (component.len)(&self.component_maps),
+                capacity: (component.capacity)(&self.component_maps),
+            })
+            .collect()
+    }
+
+    /**
+     * how many entities the allocator has room for before it grows
+     */
+    pub fn allocator_capacity(&self) -> usize {
+        self.entity_allocator.capacity()
+    }
+
     pub fn registered_components_len(&self) -> usize {
         self.component_maps.len()
     }
@@ -200,6 +551,58 @@ impl EntityManager {
     }
 }
 
+fn clear_map<C: Component + PartialEq + 'static>(component_maps: &mut AnyMap) {
+    if let Some(cmp_map) = component_maps.get_mut::<EntityMap<C>>() {
+        *cmp_map = EntityMap::<C>::default();
+    }
+}
+
+fn reserve_map<C: Component + PartialEq + 'static>(component_maps: &mut AnyMap, slots: usize) {
+    if let Some(cmp_map) = component_maps.get_mut::<EntityMap<C>>() {
+        cmp_map.reserve(slots.saturating_sub(cmp_map.slots()));
+    }
+}
+
+fn map_capacity<C: Component + PartialEq + 'static>(component_maps: &AnyMap) -> usize {
+    component_maps
+        .get::<EntityMap<C>>()
+        .map(|cmp_map| cmp_map.capacity())
+        .unwrap_or(usize::MAX)
+}
+
+fn map_len<C: Component + PartialEq + 'static>(component_maps: &AnyMap) -> usize {
+    component_maps
+        .get::<EntityMap<C>>()
+        .map_or(0, |cmp_map| cmp_map.count())
+}
+
+fn short_type_name<C: 'static>() -> &'static str {
+    let name = std::any::type_name::<C>();
+
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+fn has_component<C: Component + PartialEq + 'static>(
+    component_maps: &AnyMap,
+    entity: &VersionedIndex,
+) -> bool {
+    component_maps
+        .get::<EntityMap<C>>()
+        .is_some_and(|cmp_map| cmp_map.get(entity).is_some())
+}
+
+/**
+ * using a component that was never registered is a setup bug, so debug builds
+ * fail loudly instead of silently dropping the call
+ */
+fn unregistered<C: 'static>() {
+    #[cfg(debug_assertions)]
+    panic!(
+        "component {} has not been registered",
+        std::any::type_name::<C>()
+    );
+}
+
 pub struct EntityBuilder<'a> {
     entity_manager: &'a mut EntityManager,
     entity: VersionedIndex,
@@ -207,9 +610,7 @@ pub struct EntityBuilder<'a> {
 
 impl<'a> EntityBuilder<'a> {
     pub fn create(entity_manager: &'a mut EntityManager) -> Self {
-        let entity = entity_manager.entity_allocator.allocate();
-
-        entity_manager.entities.push(entity);
+        let entity = entity_manager.create();
 
         Self {
             entity_manager,
@@ -223,7 +624,41 @@ impl<'a> EntityBuilder<'a> {
         self
     }
 
+    pub fn with_bundle(self, bundle: impl Bundle) -> Self {
+        bundle.insert(self.entity_manager, &self.entity);
+
+        self
+    }
+
     pub fn build(self) -> VersionedIndex {
         self.entity
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deleted_entities_leave_the_spawn_order() {
+        let mut entity_manager = EntityManager::new().unwrap();
+        entity_manager.register_component::<CTag>();
+
+        let mut kept = vec![];
+        for i in 0..100 {
+            let entity = entity_manager.create();
+            let tag = if i % 10 == 0 { "ship" } else { "bullet" };
+            entity_manager.add(&entity, CTag { tag: tag.to_string() });
+            match i % 10 {
+                0 => kept.push(entity),
+                _ => entity_manager.set_to_delete(entity),
+            }
+        }
+        entity_manager.flush();
+        assert_eq!(entity_manager.entities, kept);
+
+        entity_manager.clear_entities_with_tag("ship");
+        assert!(entity_manager.entities.is_empty());
+        assert!(entity_manager.get_by_tag("ship").is_empty());
+    }
+}
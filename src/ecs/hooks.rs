@@ -0,0 +1,76 @@
+use std::{any::TypeId, collections::HashMap, fmt};
+
+use super::indexed_array::VersionedIndex;
+
+pub type ComponentHook = Box<dyn FnMut(&VersionedIndex)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum HookEvent {
+    Add,
+    Remove,
+}
+
+/**
+* callbacks registered with `on_add` and `on_remove`, plus the events queued
+* for them until the next flush
+*/
+#[derive(Default)]
+pub(super) struct ComponentHooks {
+    on_add: HashMap<TypeId, Vec<ComponentHook>>,
+    on_remove: HashMap<TypeId, Vec<ComponentHook>>,
+    pending: Vec<(HookEvent, TypeId, VersionedIndex)>,
+}
+
+impl ComponentHooks {
+    pub fn register(&mut self, event: HookEvent, type_id: TypeId, hook: ComponentHook) {
+        let hooks = match event {
+            HookEvent::Add => &mut self.on_add,
+            HookEvent::Remove => &mut self.on_remove,
+        };
+
+        hooks.entry(type_id).or_default().push(hook);
+    }
+
+    pub fn is_hooked(&self, event: HookEvent, type_id: &TypeId) -> bool {
+        match event {
+            HookEvent::Add => self.on_add.contains_key(type_id),
+            HookEvent::Remove => self.on_remove.contains_key(type_id),
+        }
+    }
+
+    pub fn queue(&mut self, event: HookEvent, type_id: TypeId, entity: VersionedIndex) {
+        if self.is_hooked(event, &type_id) {
+            self.pending.push((event, type_id, entity));
+        }
+    }
+
+    /**
+     * fires every queued event in the order it happened
+     */
+    pub fn fire(&mut self) {
+        for (event, type_id, entity) in std::mem::take(&mut self.pending) {
+            let hooks = match event {
+                HookEvent::Add => self.on_add.get_mut(&type_id),
+                HookEvent::Remove => self.on_remove.get_mut(&type_id),
+            };
+
+            for hook in hooks.into_iter().flatten() {
+                hook(&entity);
+            }
+        }
+    }
+
+    pub fn clear_pending(&mut self) {
+        self.pending.clear();
+    }
+}
+
+impl fmt::Debug for ComponentHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ComponentHooks")
+            .field("on_add", &self.on_add.len())
+            .field("on_remove", &self.on_remove.len())
+            .field("pending", &self.pending)
+            .finish()
+    }
+}
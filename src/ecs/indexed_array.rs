@@ -10,7 +10,7 @@ use serde::{Serialize, Deserialize};
 /// - RustConf 2018 - Closing Keynote - Using Rust For Game Development by Catherine West
 /// - https://github.com/fitzgen/generational-arena
 
-#[derive(Debug, Default, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, PartialEq, Eq, Hash, Copy, Clone, Serialize, Deserialize)]
 pub struct VersionedIndex {
     index: usize,
     version: u64
@@ -76,6 +76,29 @@ impl VersionedIndexAllocator {
         }
     }
 
+    /// frees every valid index in one pass, for when lots of entities go at
+    /// once (eg. tearing down a scene). invalid and repeated indices are
+    /// skipped. the version is bumped once for the whole batch, which is
+    /// enough to keep every freed index stale after its slot is reused
+    pub fn deallocate_many(&mut self, indices: &[VersionedIndex]) {
+        let mut freed = 0;
+
+        for index in indices {
+            if !self.validate(index) {
+                continue;
+            }
+
+            self.entries[index.index] = AllocatorEntry::Free { next: self.next };
+            self.next = Some(index.index);
+            freed += 1;
+        }
+
+        if freed > 0 {
+            self.version += 1;
+            self.length -= freed;
+        }
+    }
+
     pub fn is_allocated(&self, index: &VersionedIndex) -> bool {
         match self.entries.get(index.index) {
             None => false,
@@ -86,6 +109,13 @@ impl VersionedIndexAllocator {
         }
     }
 
+    /// makes room for `additional` more entries without reallocating
+    pub fn reserve(&mut self, additional: usize) {
+        self.entries.reserve(additional);
+    }
+
+    pub fn capacity(&self) -> usize { self.entries.capacity() }
+
     pub fn length(&self) -> usize { self.entries.len() }
     pub fn valid_count(&self) -> usize {
         self.entries
@@ -127,9 +157,13 @@ impl VersionedIndexAllocator {
     // }
 
     pub fn validate(&self, index: &VersionedIndex) -> bool {
-        let entity = self.entries[index.index];
+        // entries never shrink, but an index from another allocator (eg. one
+        // read back from a saved scene) can still point past the end
+        let Some(entity) = self.entries.get(index.index) else {
+            return false;
+        };
 
-        if let AllocatorEntry::Occupied { version } = entity {
+        if let AllocatorEntry::Occupied { version } = *entity {
             if version == index.version {
                 return true;
             }
@@ -139,6 +173,10 @@ impl VersionedIndexAllocator {
     }
 }
 
+// setting an index past the end grows the array a few slots further, so the
+// next few sets don't have to
+const SLOT_PADDING: usize = 4;
+
 #[derive(Debug, Default)]
 pub struct Entry<T> {
     value: T,
@@ -146,11 +184,19 @@ pub struct Entry<T> {
 }
 
 #[derive(Debug)]
-pub struct IndexedArray<T>(Vec<Option<Entry<T>>>);
+pub struct IndexedArray<T> {
+    entries: Vec<Option<Entry<T>>>,
+
+    // how many entries are set, kept up to date so counting is free
+    set: usize
+}
 
 impl<T> Default for IndexedArray<T> {
     fn default() -> Self {
-        Self(Vec::<Option<Entry<T>>>::with_capacity(16))
+        Self {
+            entries: Vec::<Option<Entry<T>>>::with_capacity(16),
+            set: 0
+        }
     }
 }
 
@@ -158,15 +204,19 @@ impl<T> IndexedArray<T> {
     pub fn set(&mut self, index: &VersionedIndex, value: T) {
         let i = index.index;
 
-        if i >= self.0.capacity() {
-            self.0.reserve(self.0.capacity() * 2);
+        if i >= self.entries.capacity() {
+            self.entries.reserve(self.entries.capacity() * 2);
+        }
+
+        if i >= self.entries.len() {
+            self.entries.resize_with(i + SLOT_PADDING, || None);
         }
 
-        if i >= self.0.len() {
-            self.0.resize_with(i + 4, || None);
+        if self.entries[i].is_none() {
+            self.set += 1;
         }
 
-        self.0[i] = Some(Entry {
+        self.entries[i] = Some(Entry {
             version: index.version,
             value
         });
@@ -175,15 +225,17 @@ impl<T> IndexedArray<T> {
     pub fn unset(&mut self, index: &VersionedIndex) {
         let i = index.index;
 
-        if i >= self.0.len() {
+        if i >= self.entries.len() {
             return;
         }
 
-        self.0[i] = None;
+        if self.entries[i].take().is_some() {
+            self.set -= 1;
+        }
     }
 
     pub fn get(&self, index: &VersionedIndex) -> Option<&T> {
-        match self.0.get(index.index) {
+        match self.entries.get(index.index) {
             Some(Some(entry)) => {
                 if entry.version == index.version {
                     Some(&entry.value)
@@ -194,7 +246,7 @@ impl<T> IndexedArray<T> {
     }
 
     pub fn get_mut(&mut self, index: &VersionedIndex) -> Option<&mut T> {
-        match self.0.get_mut(index.index) {
+        match self.entries.get_mut(index.index) {
             None => None,
             Some(None) => None,
             Some(Some(entry)) => {
@@ -205,29 +257,164 @@ impl<T> IndexedArray<T> {
         }
     }
 
-    /// TODO: write test
+    /**
+     * makes room for `additional` more indices past the slots in use, so
+     * setting them doesn't reallocate
+     */
+    pub fn reserve(&mut self, additional: usize) {
+        let needed = self.entries.len() + additional + SLOT_PADDING;
+
+        self.entries.reserve(needed.saturating_sub(self.entries.len()));
+    }
+
+    /**
+     * how many indices fit before the array has to grow
+     */
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity().saturating_sub(SLOT_PADDING)
+    }
+
+    /**
+     * how many slots are in use, set or not
+     */
+    pub fn slots(&self) -> usize {
+        self.entries.len()
+    }
+
+    /**
+     * how many entries are set, valid or not
+     */
+    pub fn count(&self) -> usize {
+        self.set
+    }
+
+    /**
+     * hands out mutable entries for indices in ascending order, see
+     * `AscendingMut`
+     */
+    pub fn ascending_mut(&mut self) -> AscendingMut<'_, T> {
+        AscendingMut {
+            rest: &mut self.entries,
+            offset: 0
+        }
+    }
+
     pub fn get_entities(
         &self,
         allocator: &VersionedIndexAllocator
     ) -> Vec<VersionedIndex> {
-        self.0.iter()
-            .enumerate()
-            .filter_map(|(i, wrapped)| match wrapped {
-                Some(entry) => {
-                    let index = VersionedIndex {
-                        index: i,
-                        version: entry.version
-                    };
-
-                    match allocator.validate(&index) {
-                        true => Some(index),
-                        false => None
-                    }
-                },
-                None => None
-            })
+        self.iter(allocator)
+            .map(|(index, _)| index)
             .collect()
     }
+
+    /// iterate over the entries that are still valid in `allocator`.
+    ///
+    /// the allocator is borrowed once for the whole iteration rather than
+    /// once per element.
+    pub fn iter<'a>(
+        &'a self,
+        allocator: &'a VersionedIndexAllocator
+    ) -> Iter<'a, T> {
+        Iter {
+            entries: self.entries.iter().enumerate(),
+            allocator
+        }
+    }
+
+    pub fn iter_mut<'a>(
+        &'a mut self,
+        allocator: &'a VersionedIndexAllocator
+    ) -> IterMut<'a, T> {
+        IterMut {
+            entries: self.entries.iter_mut().enumerate(),
+            allocator
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    entries: std::iter::Enumerate<std::slice::Iter<'a, Option<Entry<T>>>>,
+    allocator: &'a VersionedIndexAllocator
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (VersionedIndex, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (i, wrapped) in self.entries.by_ref() {
+            let Some(entry) = wrapped else { continue };
+
+            let index = VersionedIndex {
+                index: i,
+                version: entry.version
+            };
+
+            if self.allocator.validate(&index) {
+                return Some((index, &entry.value));
+            }
+        }
+
+        None
+    }
+}
+
+pub struct IterMut<'a, T> {
+    entries: std::iter::Enumerate<std::slice::IterMut<'a, Option<Entry<T>>>>,
+    allocator: &'a VersionedIndexAllocator
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = (VersionedIndex, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (i, wrapped) in self.entries.by_ref() {
+            let Some(entry) = wrapped else { continue };
+
+            let index = VersionedIndex {
+                index: i,
+                version: entry.version
+            };
+
+            if self.allocator.validate(&index) {
+                return Some((index, &mut entry.value));
+            }
+        }
+
+        None
+    }
+}
+
+/**
+ * mutable access to many entries of an IndexedArray at once. each `get` must
+ * ask for a higher index than the last, which is what lets the entries it
+ * hands out live as long as the array borrow
+ */
+pub struct AscendingMut<'a, T> {
+    rest: &'a mut [Option<Entry<T>>],
+    offset: usize
+}
+
+impl<'a, T> AscendingMut<'a, T> {
+    /**
+     * None if the entry isn't set, or if index isn't past the last one asked
+     * for
+     */
+    pub fn get(&mut self, index: &VersionedIndex) -> Option<&'a mut T> {
+        let i = index.index.checked_sub(self.offset)?;
+        if i >= self.rest.len() {
+            return None;
+        }
+
+        let (head, tail) = std::mem::take(&mut self.rest).split_at_mut(i + 1);
+        self.rest = tail;
+        self.offset = index.index + 1;
+
+        match head.last_mut() {
+            Some(Some(entry)) if entry.version == index.version => Some(&mut entry.value),
+            _ => None
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -328,4 +515,116 @@ mod tests {
         assert!(!allocator.is_allocated(&VersionedIndex { index: 1, version: 0 }));
         assert!(allocator.is_allocated(&VersionedIndex { index: 1, version: 1 }));
     }
+
+    #[test]
+    fn indexed_array_iter_skips_stale_entries() {
+        let mut allocator = VersionedIndexAllocator::default();
+        let mut entities = EntityMap::<Entity>::default();
+
+        let player_id = allocator.allocate();
+        let npc_id = allocator.allocate();
+        let enemy_id = allocator.allocate();
+
+        entities.set(&player_id, Entity("player".to_string()));
+        entities.set(&npc_id, Entity("npc".to_string()));
+        entities.set(&enemy_id, Entity("enemy".to_string()));
+
+        allocator.deallocate(npc_id);
+
+        let items: Vec<(VersionedIndex, &Entity)> = entities.iter(&allocator).collect();
+
+        assert_eq!(
+            items,
+            vec![
+                (player_id, &Entity("player".to_string())),
+                (enemy_id, &Entity("enemy".to_string())),
+            ]
+        );
+        assert_eq!(entities.get_entities(&allocator), vec![player_id, enemy_id]);
+
+        for (_, entity) in entities.iter_mut(&allocator) {
+            entity.0.push('!');
+        }
+
+        assert_eq!(entities.get(&player_id), Some(&Entity("player!".to_string())));
+        assert_eq!(entities.get(&enemy_id), Some(&Entity("enemy!".to_string())));
+    }
+
+    #[test]
+    fn indexed_array_iter_with_shared_allocator() {
+        let allocator = std::cell::RefCell::new(VersionedIndexAllocator::default());
+        let mut entities = EntityMap::<Entity>::default();
+
+        for name in ["player", "npc", "enemy"] {
+            let index = allocator.borrow_mut().allocate();
+            entities.set(&index, Entity(name.to_string()));
+        }
+
+        // the iterator holds a single borrow, so other readers can still get at the allocator
+        let guard = allocator.borrow();
+        let mut count = 0;
+        for (index, _) in entities.iter(&guard) {
+            assert!(allocator.borrow().is_allocated(&index));
+            count += 1;
+        }
+
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn allocator_deallocate_many() {
+        let mut allocator = VersionedIndexAllocator::default();
+        let indices = (0..100).map(|_| allocator.allocate()).collect::<Vec<_>>();
+        let (doomed, kept) = indices.split_at(60);
+
+        // repeats and already freed indices are skipped
+        let mut batch = doomed.to_vec();
+        batch.push(doomed[0]);
+        allocator.deallocate_many(&batch);
+        allocator.deallocate_many(&doomed[..10]);
+
+        for index in doomed {
+            assert!(!allocator.is_allocated(index));
+        }
+        for index in kept {
+            assert!(allocator.is_allocated(index));
+        }
+        assert_eq!(allocator.valid_count(), 40);
+        assert_eq!(allocator.length, 40);
+
+        // the freed slots are reused, but the old indices stay stale
+        let reused = (0..60).map(|_| allocator.allocate()).collect::<Vec<_>>();
+        assert_eq!(allocator.length(), 100);
+        for index in doomed {
+            assert!(!allocator.is_allocated(index));
+            assert!(reused.iter().any(|new| new.index == index.index));
+        }
+    }
+
+    #[test]
+    fn indexed_array_counts_set_entries() {
+        let mut allocator = VersionedIndexAllocator::default();
+        let mut entities = EntityMap::<Entity>::default();
+
+        let player_id = allocator.allocate();
+        let npc_id = allocator.allocate();
+        assert_eq!(entities.count(), 0);
+
+        entities.set(&player_id, Entity("player".to_string()));
+        entities.set(&npc_id, Entity("npc".to_string()));
+        entities.set(&npc_id, Entity("npc".to_string()));
+        assert_eq!(entities.count(), 2);
+
+        entities.unset(&npc_id);
+        entities.unset(&npc_id);
+        entities.unset(&VersionedIndex { index: 50, version: 0 });
+        assert_eq!(entities.count(), 1);
+
+        // a stale entry still counts until its slot is set again
+        allocator.deallocate(player_id);
+        assert_eq!(entities.count(), 1);
+        let enemy_id = allocator.allocate();
+        entities.set(&enemy_id, Entity("enemy".to_string()));
+        assert_eq!(entities.count(), 1);
+    }
 }
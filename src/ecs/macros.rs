@@ -0,0 +1,31 @@
+/**
+* registers every listed component with the registry's entity manager, in
+* order. the same as chaining `register_component::<C>()` for each of them
+*
+* ```no_run
+* use quipi::prelude::{qp_ecs::components::{CHealth, CTag}, GlobalRegistry, QPError};
+*
+* let mut registry = GlobalRegistry::init()?;
+* quipi::register_components!(registry, CTag, CHealth);
+*
+* assert!(registry.entity_manager.is_registered::<CHealth>());
+* # Ok::<(), QPError>(())
+* ```
+*/
+#[macro_export]
+macro_rules! register_components {
+    ($registry:expr, $($component:ty),+ $(,)?) => {
+        $registry.entity_manager$(.register_component::<$component>())+;
+    };
+}
+
+/**
+* registers every listed asset type with the registry's asset manager, in
+* order. see `register_components!`
+*/
+#[macro_export]
+macro_rules! register_assets {
+    ($registry:expr, $($asset:ty),+ $(,)?) => {
+        $($registry.asset_manager.register_asset::<$asset>();)+
+    };
+}
@@ -1,6 +1,12 @@
+mod archetypes;
+mod bundles;
 mod components;
+mod entity_id;
 mod entity_manager;
+mod hooks;
 mod indexed_array;
+mod macros;
+mod pool;
 mod query;
 mod tests;
 
@@ -15,8 +21,16 @@ pub mod prelude {
     pub use indexed_array::VersionedIndex;
     pub use indexed_array::VersionedIndexAllocator;
 
+    pub use archetypes::Archetypes;
+    pub use bundles::Bundle;
+    pub use bundles::Sprite2DBundle;
     pub use components::components;
+    pub use entity_id::EntityId;
+    pub use entity_manager::ComponentStoreStats;
     pub use entity_manager::EntityBuilder;
     pub use entity_manager::EntityManager;
+    pub use hooks::ComponentHook;
+    pub use pool::EntityPool;
     pub use query::EMQuery;
+    pub use query::EMQueryWithout;
 }
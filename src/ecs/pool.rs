@@ -0,0 +1,93 @@
+use super::{
+    entity_manager::EntityManager,
+    indexed_array::VersionedIndex,
+    prelude::components::{CDisabled, CSprite, CVelocity2D},
+};
+
+/**
+* entities that are done with but kept around to be reused, so spawning lots
+* of short lived things, eg. bullets, doesn't allocate every time
+*
+* released entities are hidden, stop moving and are marked CDisabled so tag
+* queries and collision skip them. whoever acquires one is expected to give
+* it fresh components, see `SchemaSprite::apply_to`
+*/
+#[derive(Debug, Default, Clone)]
+pub struct EntityPool {
+    free: Vec<VersionedIndex>,
+
+    /**
+     * the most entities kept around. anything released past this is deleted
+     */
+    pub max_free: Option<usize>,
+}
+
+impl EntityPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_free(max_free: usize) -> Self {
+        Self {
+            free: Vec::with_capacity(max_free),
+            max_free: Some(max_free),
+        }
+    }
+
+    /**
+     * how many entities are waiting to be reused
+     */
+    pub fn len(&self) -> usize {
+        self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.free.is_empty()
+    }
+
+    /**
+     * a released entity that's still alive, if there is one. it's enabled
+     * again, so hand it back with `release` if it can't be used after all
+     */
+    pub fn acquire(&mut self, entity_manager: &mut EntityManager) -> Option<VersionedIndex> {
+        while let Some(entity) = self.free.pop() {
+            if entity_manager.entity_id(&entity).is_some()
+                && !entity_manager.is_pending_delete(&entity)
+            {
+                entity_manager.remove::<CDisabled>(&entity);
+
+                return Some(entity);
+            }
+        }
+
+        None
+    }
+
+    /**
+     * hands the entity back to the pool. releasing a dead entity, or one
+     * that's already in the pool, does nothing
+     */
+    pub fn release(&mut self, entity_manager: &mut EntityManager, entity: VersionedIndex) {
+        if entity_manager.entity_id(&entity).is_none()
+            || entity_manager.is_pending_delete(&entity)
+            || self.free.contains(&entity)
+        {
+            return;
+        }
+
+        if self.max_free.is_some_and(|max| self.free.len() >= max) {
+            entity_manager.set_to_delete(entity);
+            return;
+        }
+
+        if let Some(sprite) = entity_manager.get_mut::<CSprite>(&entity) {
+            sprite.skip = true;
+        }
+        if entity_manager.get::<CVelocity2D>(&entity).is_some() {
+            entity_manager.remove::<CVelocity2D>(&entity);
+        }
+        entity_manager.add(&entity, CDisabled);
+
+        self.free.push(entity);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::prelude::{
-    qp_ecs::Component,
+    qp_ecs::{Component, IndexedArray, VersionedIndexAllocator},
     GlobalRegistry,
     VersionedIndex
 };
@@ -54,4 +54,148 @@ impl<A, B, C> EMQuery<A, B, C>
             },
         }
     }
-}
\ No newline at end of file
+
+    /**
+     * every entity with all three components, along with them. the
+     * component with the fewest entries drives the search, so a rare
+     * component keeps the query cheap however many entities there are
+     *
+     * ```no_run
+     * # use quipi::prelude::{qp_ecs::{components::{CSprite, CTransform2D, CVelocity2D}, EMQuery}, GlobalRegistry};
+     * # fn update(registry: &GlobalRegistry) {
+     * for (entity, transform, velocity, sprite) in
+     *     EMQuery::<CTransform2D, CVelocity2D, CSprite>::query3(registry)
+     * {
+     *     // ...
+     * }
+     * # }
+     * ```
+     */
+    pub fn query3(
+        registry: &GlobalRegistry
+    ) -> impl Iterator<Item = (VersionedIndex, &A, &B, &C)> {
+        let em = &registry.entity_manager;
+        let (Some((a, allocator)), Some((b, _)), Some((c, _))) = (
+            em.component_map::<A>(),
+            em.component_map::<B>(),
+            em.component_map::<C>()
+        ) else {
+            return Vec::new().into_iter();
+        };
+
+        joined(allocator, a, b, c)
+            .into_iter()
+            .filter_map(|entity| Some((entity, a.get(&entity)?, b.get(&entity)?, c.get(&entity)?)))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /**
+     * `query3` with mutable components. A, B and C must be different
+     * components, nothing matches otherwise
+     */
+    pub fn query3_mut(
+        registry: &mut GlobalRegistry
+    ) -> impl Iterator<Item = (VersionedIndex, &mut A, &mut B, &mut C)> {
+        let Some((a, b, c, allocator)) = registry.entity_manager.component_maps_mut::<A, B, C>() else {
+            return Vec::new().into_iter();
+        };
+
+        let entities = joined(allocator, a, b, c);
+
+        // the entities come out in index order, so each map can hand out
+        // its entries one after another
+        let (mut a, mut b, mut c) = (a.ascending_mut(), b.ascending_mut(), c.ascending_mut());
+        entities
+            .into_iter()
+            .filter_map(|entity| Some((entity, a.get(&entity)?, b.get(&entity)?, c.get(&entity)?)))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /**
+     * same query, leaving out entities that have W. chain more `without`
+     * calls to exclude more components
+     *
+     * ```no_run
+     * # use quipi::prelude::{qp_ecs::{components::{CStatic, CTransform2D}, EMQuery}, GlobalRegistry};
+     * # fn update(registry: &GlobalRegistry) {
+     * let moving = EMQuery::<CTransform2D>::without::<CStatic>().query_all(registry);
+     * # }
+     * ```
+     */
+    pub fn without<W: Component + PartialEq + 'static>() -> EMQueryWithout<A, B, C> {
+        EMQueryWithout {
+            excluded: vec![],
+            _marker: ::std::marker::PhantomData,
+        }.without::<W>()
+    }
+}
+
+/**
+ * the valid entities in all three maps, in index order
+ */
+fn joined<A, B, C>(
+    allocator: &VersionedIndexAllocator,
+    a: &IndexedArray<A>,
+    b: &IndexedArray<B>,
+    c: &IndexedArray<C>
+) -> Vec<VersionedIndex> {
+    let in_all = |entity: &VersionedIndex| {
+        a.get(entity).is_some() && b.get(entity).is_some() && c.get(entity).is_some()
+    };
+
+    let counts = [a.count(), b.count(), c.count()];
+    let smallest = counts.iter().min().copied().unwrap_or_default();
+
+    let driver = match counts.iter().position(|count| *count == smallest) {
+        Some(0) => a.get_entities(allocator),
+        Some(1) => b.get_entities(allocator),
+        _ => c.get_entities(allocator),
+    };
+
+    driver.into_iter().filter(in_all).collect()
+}
+
+type HasComponent = fn(&GlobalRegistry, &VersionedIndex) -> bool;
+
+/**
+ * an EMQuery with components that matching entities must not have. made by
+ * `EMQuery::without`
+ */
+pub struct EMQueryWithout<A, B = (), C = ()>
+    where
+        A: Component + PartialEq + 'static,
+        B: Component + PartialEq + 'static,
+        C: Component + PartialEq + 'static,
+{
+    excluded: Vec<HasComponent>,
+    _marker: ::std::marker::PhantomData<(A, B, C)>
+}
+
+impl<A, B, C> EMQueryWithout<A, B, C>
+    where
+        A: Component + PartialEq + 'static,
+        B: Component + PartialEq + 'static,
+        C: Component + PartialEq + 'static,
+{
+    pub fn without<W: Component + PartialEq + 'static>(mut self) -> Self {
+        self.excluded.push(has::<W>);
+
+        self
+    }
+
+    pub fn query_all(
+        &self,
+        registry: &GlobalRegistry
+    ) -> Vec<VersionedIndex> {
+        EMQuery::<A, B, C>::query_all(registry)
+            .into_iter()
+            .filter(|entity| !self.excluded.iter().any(|has| has(registry, entity)))
+            .collect()
+    }
+}
+
+fn has<W: Component + PartialEq + 'static>(registry: &GlobalRegistry, entity: &VersionedIndex) -> bool {
+    registry.entity_manager.get::<W>(entity).is_some()
+}
@@ -1,6 +1,14 @@
 #[cfg(test)]
 mod ecs_tests {
-    use super::super::prelude::*;
+    use super::super::prelude::{
+        components::{
+            CHealth, CQuad, CSprite, CStatic, CTag, CTransform2D,
+            CVelocity2D,
+        },
+        *,
+    };
+    use crate::prelude::GlobalRegistry;
+    use crate::test_utils::registry;
 
     #[derive(Component, Debug, PartialEq)]
     struct DrawComponent {}
@@ -19,5 +27,454 @@ mod ecs_tests {
         registry.register_component::<TransformComponent>();
         assert_eq!(registry.registered_components_len(), 2);
     }
-}
 
+    #[test]
+    fn ecs_is_registered() {
+        let mut registry = EntityManager::new().unwrap();
+        assert!(!registry.is_registered::<DrawComponent>());
+
+        registry.register_component::<DrawComponent>();
+        assert!(registry.is_registered::<DrawComponent>());
+        assert!(!registry.is_registered::<TransformComponent>());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "TransformComponent has not been registered")]
+    fn ecs_add_unregistered_component() {
+        let mut registry = EntityManager::new().unwrap();
+        registry.register_component::<DrawComponent>();
+
+        let entity = registry.create();
+        registry.add(&entity, TransformComponent {});
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "TransformComponent has not been registered")]
+    fn ecs_get_unregistered_component() {
+        let mut registry = EntityManager::new().unwrap();
+        registry.register_component::<DrawComponent>();
+
+        let entity = registry.create();
+        registry.add(&entity, DrawComponent {});
+
+        registry.get::<TransformComponent>(&entity);
+    }
+
+    fn spawn_tagged() -> Vec<VersionedIndex> {
+        let mut registry = EntityManager::new().unwrap();
+        registry.register_component::<CTag>();
+
+        let tags = ["enemy", "player", "enemy", "star", "enemy", "enemy"];
+        let mut spawned = vec![];
+        for tag in tags {
+            let entity = registry.create();
+            registry.add(&entity, CTag { tag: tag.to_string() });
+            spawned.push(entity);
+        }
+
+        // free a slot so the next enemy reuses an earlier index
+        registry.set_to_delete(spawned[0]);
+        registry.flush();
+
+        let entity = registry.create();
+        registry.add(&entity, CTag { tag: "enemy".to_string() });
+        assert!(entity.to_string().starts_with("0."));
+
+        let enemies = registry.get_by_tag("enemy");
+        assert_eq!(enemies, vec![spawned[2], spawned[4], spawned[5], entity]);
+
+        enemies
+    }
+
+    #[test]
+    fn ecs_get_by_tag_is_deterministic() {
+        assert_eq!(spawn_tagged(), spawn_tagged());
+    }
+
+    #[test]
+    fn ecs_with_bundle() {
+        let mut registry = registry();
+
+        let bundle = Sprite2DBundle {
+            tag: CTag {
+                tag: "ship".to_string(),
+            },
+            transform: CTransform2D {
+                translate: glm::vec2(3.0, 4.0),
+                ..CTransform2D::default()
+            },
+            sprite: CSprite::new(&CQuad::default(), None, None),
+            velocity: CVelocity2D { x: 1.0, y: 2.0 },
+        };
+
+        let entity = EntityBuilder::create(&mut registry.entity_manager)
+            .with_bundle(bundle.clone())
+            .build();
+
+        let em = &registry.entity_manager;
+        assert_eq!(em.get::<CTag>(&entity), Some(&bundle.tag));
+        assert_eq!(em.get::<CTransform2D>(&entity), Some(&bundle.transform));
+        assert_eq!(em.get::<CSprite>(&entity), Some(&bundle.sprite));
+        assert_eq!(em.get::<CVelocity2D>(&entity), Some(&bundle.velocity));
+    }
+
+    #[test]
+    fn ecs_clear() {
+        let mut registry = registry();
+        let registered = registry.entity_manager.registered_components_len();
+
+        let mut old = vec![];
+        for i in 0..5 {
+            let entity = registry.entity_manager.create();
+            registry.entity_manager.add(&entity, CVelocity2D { x: i as f32, y: 0.0 });
+            old.push(entity);
+        }
+        registry.entity_manager.set_to_delete(old[1]);
+        registry.entity_manager.flush();
+
+        registry.clear();
+
+        let em = &mut registry.entity_manager;
+        assert_eq!(em.count(), 0);
+        assert_eq!(em.registered_components_len(), registered);
+        assert!(em.is_registered::<CVelocity2D>());
+        assert!(em.query_all::<CVelocity2D>().is_empty());
+        assert!(em.get::<CVelocity2D>(&old[4]).is_none());
+
+        // new entities reuse the freed slots, but the old handles don't
+        // validate against them
+        let new = (0..5).map(|_| em.create()).collect::<Vec<_>>();
+        assert_eq!(em.allocator_size(), 5);
+        for entity in new.iter() {
+            assert!(em.get::<CVelocity2D>(entity).is_none());
+            em.add(entity, CVelocity2D { x: 9.0, y: 0.0 });
+        }
+
+        for entity in old.iter() {
+            assert!(!new.contains(entity));
+            assert!(em.get::<CVelocity2D>(entity).is_none());
+            assert!(em.entity_id(entity).is_none());
+        }
+        assert_eq!(em.query_all::<CVelocity2D>().len(), 5);
+    }
+
+    #[test]
+    fn ecs_clear_entities_with_tag() {
+        let mut registry = EntityManager::new().unwrap();
+        registry.register_component::<CTag>();
+
+        for tag in ["bullet", "ship", "bullet"] {
+            let entity = registry.create();
+            registry.add(&entity, CTag { tag: tag.to_string() });
+        }
+
+        assert_eq!(registry.clear_entities_with_tag("bullet"), 2);
+        assert_eq!(registry.count(), 1);
+        assert!(registry.get_by_tag("bullet").is_empty());
+        assert_eq!(registry.get_by_tag("ship").len(), 1);
+    }
+
+    #[test]
+    fn ecs_on_add_on_remove_hooks() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let mut registry = EntityManager::new().unwrap();
+        registry.register_component::<CTag>();
+        registry.register_component::<CVelocity2D>();
+
+        let added = Rc::new(RefCell::new(vec![]));
+        let removed = Rc::new(RefCell::new(vec![]));
+        {
+            let added = added.clone();
+            let removed = removed.clone();
+            registry
+                .on_add::<CVelocity2D>(move |entity| added.borrow_mut().push(*entity))
+                .on_remove::<CVelocity2D>(move |entity| removed.borrow_mut().push(*entity));
+        }
+
+        let entity = registry.create();
+        registry.add(&entity, CTag { tag: "ship".to_string() });
+        registry.add(&entity, CVelocity2D::default());
+        registry.add(&entity, CVelocity2D { x: 1.0, y: 0.0 });
+
+        // hooks wait for the flush
+        assert!(added.borrow().is_empty());
+
+        registry.flush();
+        assert_eq!(*added.borrow(), vec![entity]);
+        assert!(removed.borrow().is_empty());
+
+        registry.remove::<CVelocity2D>(&entity);
+        registry.remove::<CVelocity2D>(&entity);
+        registry.flush();
+        assert_eq!(*removed.borrow(), vec![entity]);
+
+        // deleting an entity removes its components too
+        let other = registry.create();
+        registry.add(&other, CVelocity2D::default());
+        registry.set_to_delete(other);
+        registry.set_to_delete(entity);
+        registry.flush();
+
+        assert_eq!(*added.borrow(), vec![entity, other]);
+        assert_eq!(*removed.borrow(), vec![entity, other]);
+    }
+
+    #[test]
+    fn ecs_register_component_twice() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let mut registry = EntityManager::new().unwrap();
+        registry.register_component::<CVelocity2D>();
+
+        let entity = registry.create();
+        registry.add(&entity, CVelocity2D { x: 1.0, y: 0.0 });
+
+        // the second registration keeps the map and isn't listed again
+        registry.register_component::<CVelocity2D>();
+        assert_eq!(registry.registered_components_len(), 1);
+        assert_eq!(registry.registered_component_names(), ["CVelocity2D"]);
+        assert_eq!(registry.component_stats().len(), 1);
+        assert_eq!(registry.get::<CVelocity2D>(&entity).map(|v| v.x), Some(1.0));
+
+        let removed = Rc::new(RefCell::new(0));
+        {
+            let removed = removed.clone();
+            registry.on_remove::<CVelocity2D>(move |_| *removed.borrow_mut() += 1);
+        }
+        registry.set_to_delete(entity);
+        registry.flush();
+        assert_eq!(*removed.borrow(), 1);
+    }
+
+    #[test]
+    fn ecs_entity_id_survives_slot_reuse() {
+        let mut registry = EntityManager::new().unwrap();
+        registry.register_component::<CTag>();
+
+        let first = registry.create();
+        let second = registry.create();
+        let second_id = registry.entity_id(&second).unwrap();
+
+        registry.set_to_delete(first);
+        registry.flush();
+        assert_eq!(registry.entity_id(&first), None);
+
+        // reuses the first entity's slot but gets a fresh id
+        let third = registry.create();
+        assert_eq!(third.to_string().split('.').next(), first.to_string().split('.').next());
+
+        let third_id = registry.entity_id(&third).unwrap();
+        assert!(third_id > second_id);
+        assert_eq!(registry.entity_id(&second), Some(second_id));
+        assert_eq!(registry.resolve(second_id), Some(second));
+        assert_eq!(registry.resolve(third_id), Some(third));
+        assert_eq!(registry.resolve(EntityId(0)), None);
+    }
+
+    #[test]
+    fn ecs_query_without() {
+        let mut registry = registry();
+
+        let moving = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D::default())
+            .with(CVelocity2D::default())
+            .build();
+        let wall = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D::default())
+            .with(CVelocity2D::default())
+            .with(CStatic)
+            .build();
+        let tagged = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D::default())
+            .with(CTag { tag: "decal".to_string() })
+            .build();
+
+        assert_eq!(EMQuery::<CTransform2D>::query_all(&registry).len(), 3);
+        assert_eq!(
+            EMQuery::<CTransform2D>::without::<CStatic>().query_all(&registry),
+            vec![moving, tagged]
+        );
+
+        // combines with the positive join and with more exclusions
+        assert_eq!(
+            EMQuery::<CTransform2D, CVelocity2D>::without::<CStatic>().query_all(&registry),
+            vec![moving]
+        );
+        assert_eq!(
+            EMQuery::<CTransform2D>::without::<CStatic>()
+                .without::<CTag>()
+                .query_all(&registry),
+            vec![moving]
+        );
+        assert!(!EMQuery::<CTransform2D>::without::<CStatic>()
+            .query_all(&registry)
+            .contains(&wall));
+    }
+
+    #[test]
+    fn ecs_query3_only_full_matches() {
+        let mut registry = registry();
+
+        let quad = CQuad {
+            width: 1.0,
+            height: 1.0,
+            center_x: 0.0,
+            center_y: 0.0,
+        };
+        let full = |registry: &mut GlobalRegistry, x: f32| {
+            EntityBuilder::create(&mut registry.entity_manager)
+                .with(CTransform2D::default())
+                .with(CVelocity2D { x, y: 0.0 })
+                .with(CSprite::new(&quad, None, None))
+                .build()
+        };
+
+        let first = full(&mut registry, 1.0);
+        EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D::default())
+            .with(CVelocity2D::default())
+            .build();
+        EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D::default())
+            .with(CSprite::new(&quad, None, None))
+            .build();
+        let deleted = full(&mut registry, 3.0);
+        let second = full(&mut registry, 2.0);
+        for _ in 0..5 {
+            EntityBuilder::create(&mut registry.entity_manager)
+                .with(CTransform2D::default())
+                .build();
+        }
+
+        registry.entity_manager.set_to_delete(deleted);
+        registry.flush();
+
+        let found = EMQuery::<CTransform2D, CVelocity2D, CSprite>::query3(&registry)
+            .map(|(entity, _, velocity, _)| (entity, velocity.x))
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec![(first, 1.0), (second, 2.0)]);
+
+        // the order of the components doesn't change what matches
+        let found = EMQuery::<CSprite, CTransform2D, CVelocity2D>::query3(&registry)
+            .map(|(entity, ..)| entity)
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec![first, second]);
+
+        for (_, transform, velocity, sprite) in
+            EMQuery::<CTransform2D, CVelocity2D, CSprite>::query3_mut(&mut registry)
+        {
+            transform.translate.x += velocity.x;
+            velocity.x = 0.0;
+            sprite.skip = true;
+        }
+
+        let em = &registry.entity_manager;
+        assert_eq!(em.get::<CTransform2D>(&second).unwrap().translate.x, 2.0);
+        assert_eq!(em.get::<CVelocity2D>(&first).unwrap().x, 0.0);
+        assert!(em.get::<CSprite>(&first).unwrap().skip);
+        assert_eq!(
+            EMQuery::<CTransform2D>::query_all(&registry)
+                .iter()
+                .filter(|entity| em.get::<CTransform2D>(entity).unwrap().translate.x != 0.0)
+                .count(),
+            2
+        );
+
+        // the same component twice can't be borrowed mutably twice
+        assert_eq!(
+            EMQuery::<CTransform2D, CTransform2D, CSprite>::query3_mut(&mut registry).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn ecs_reserve_spawns_without_growing() {
+        let mut registry = registry();
+        let em = &mut registry.entity_manager;
+
+        em.reserve(10_000);
+        let capacity = em.capacity();
+        assert!(capacity >= 10_000);
+
+        for i in 0..10_000 {
+            EntityBuilder::create(em)
+                .with(CTransform2D::default())
+                .with(CVelocity2D {
+                    x: i as f32,
+                    y: 0.0,
+                })
+                .with(CTag { tag: "star".into() })
+                .build();
+        }
+
+        assert_eq!(em.count(), 10_000);
+        assert_eq!(em.capacity(), capacity);
+    }
+
+    #[test]
+    fn ecs_archetype_spawn() {
+        let mut registry = registry();
+
+        let mut archetypes = Archetypes::new();
+        archetypes.register("asteroid", |entity| {
+            entity
+                .with(CTag { tag: "asteroid".into() })
+                .with(CTransform2D::default())
+                .with(CHealth::new(3.0))
+        });
+        archetypes.register("star", |entity| entity.with(CTransform2D::default()));
+        assert_eq!(archetypes.names(), vec!["asteroid", "star"]);
+
+        let em = &mut registry.entity_manager;
+        let a = archetypes.spawn("asteroid", em).unwrap();
+        let b = archetypes.spawn("asteroid", em).unwrap();
+        assert_ne!(a, b);
+
+        for asteroid in [a, b] {
+            assert_eq!(em.get::<CTag>(&asteroid).unwrap().tag, "asteroid");
+            assert_eq!(em.get::<CTransform2D>(&asteroid), Some(&CTransform2D::default()));
+            assert_eq!(em.get::<CHealth>(&asteroid), Some(&CHealth::new(3.0)));
+            assert!(em.get::<CVelocity2D>(&asteroid).is_none());
+        }
+
+        let star = archetypes.spawn("star", em).unwrap();
+        assert!(em.get::<CHealth>(&star).is_none());
+
+        assert!(archetypes.spawn("comet", em).is_none());
+    }
+
+    #[derive(Component, Debug, PartialEq)]
+    struct RLevelName(String);
+
+    #[test]
+    fn ecs_register_components_macro() {
+        let mut registry = GlobalRegistry::init().unwrap();
+        let before = registry.entity_manager.registered_components_len();
+
+        crate::register_components!(registry, CTag, CHealth, CVelocity2D);
+        crate::register_assets!(registry, RLevelName);
+
+        let em = &mut registry.entity_manager;
+        assert_eq!(em.registered_components_len(), before + 3);
+        assert_eq!(
+            em.registered_component_names()[before..],
+            ["CTag", "CHealth", "CVelocity2D"]
+        );
+        assert!(!em.is_registered::<CTransform2D>());
+
+        let entity = em.create();
+        em.add(&entity, CTag { tag: "ship".into() });
+        em.add(&entity, CHealth::new(3.0));
+        em.add(&entity, CVelocity2D { x: 1.0, y: 2.0 });
+        assert_eq!(em.get::<CTag>(&entity).unwrap().tag, "ship");
+        assert_eq!(em.get::<CHealth>(&entity), Some(&CHealth::new(3.0)));
+        assert_eq!(em.get::<CVelocity2D>(&entity), Some(&CVelocity2D { x: 1.0, y: 2.0 }));
+
+        let assets = &mut registry.asset_manager;
+        let id = assets.load_asset("level", RLevelName("caves".into())).unwrap();
+        assert_eq!(assets.get::<RLevelName>(id), Some(&RLevelName("caves".into())));
+    }
+}
@@ -1,5 +1,6 @@
 use egui::{Modifiers, Pos2, pos2, Key, vec2};
 use sdl2::{
+    controller::Button,
     event::Event::{ self, * },
     keyboard::{
         Keycode::{ self, * },
@@ -8,11 +9,15 @@ use sdl2::{
     mouse::MouseButton,
 };
 
-use super::painter::Painter;
-
+/**
+* turns an sdl event into the egui event it stands for. Tab, Shift+Tab and the
+* arrow keys move egui's keyboard focus and Enter or Space presses the focused
+* widget. a game controller's d-pad and A/B buttons do the same as the arrow
+* keys, Enter and Escape, so menus work without a mouse
+*/
 pub fn parse_event(
     event: &Event,
-    painter: &Painter
+    pixels_per_point: f32
 ) -> Option<egui::Event> {
     match event {
         MouseButtonDown { mouse_btn, x, y, .. } => parse_mouse_btn(
@@ -28,8 +33,8 @@ pub fn parse_event(
             *y as f32
         ),
         MouseMotion { x, y, .. } => Some(egui::Event::PointerMoved(pos2(
-            *x as f32 / painter.pixels_per_point,
-            *y as f32 / painter.pixels_per_point
+            *x as f32 / pixels_per_point,
+            *y as f32 / pixels_per_point
         ))),
         MouseWheel { x, y, .. } => {
             let delta = vec2(*x as f32 * 15.0, *y as f32 * 15.0);
@@ -39,10 +44,36 @@ pub fn parse_event(
         KeyDown { keycode, keymod, repeat, .. } => parse_key(*keycode, *keymod, *repeat, true),
         KeyUp { keycode, keymod, repeat, .. } => parse_key(*keycode, *keymod, *repeat, false),
         TextInput { text, .. } => Some(egui::Event::Text(text.to_string())),
+        ControllerButtonDown { button, .. } => parse_controller_button(*button, true),
+        ControllerButtonUp { button, .. } => parse_controller_button(*button, false),
         _ => None
     }
 }
 
+fn parse_controller_button(
+    button: Button,
+    state: bool // true = pressed, false = released
+) -> Option<egui::Event> {
+    let key = match button {
+        Button::DPadUp => Key::ArrowUp,
+        Button::DPadDown => Key::ArrowDown,
+        Button::DPadLeft => Key::ArrowLeft,
+        Button::DPadRight => Key::ArrowRight,
+        Button::A => Key::Enter,
+        Button::B => Key::Escape,
+
+        _ => return None
+    };
+
+    Some(egui::Event::Key {
+        key,
+        physical_key: None,
+        pressed: state,
+        repeat: false,
+        modifiers: Modifiers::NONE,
+    })
+}
+
 fn parse_key(
     keycode: Option<Keycode>,
     keymod: Mod,
@@ -93,7 +124,8 @@ fn parse_key(
         Kp8 | Num8 => Key::Num8,
         Kp9 | Num9 => Key::Num7,
 
-        Return => Key::Enter,
+        Return | KpEnter => Key::Enter,
+        Escape => Key::Escape,
         Space => Key::Space,
         Tab => Key::Tab,
         Backspace => Key::Backspace,
@@ -150,3 +182,61 @@ fn parse_mouse_btn(
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tab() -> Event {
+        KeyDown {
+            timestamp: 0,
+            window_id: 0,
+            keycode: Some(Tab),
+            scancode: None,
+            keymod: Mod::NOMOD,
+            repeat: false,
+        }
+    }
+
+    // draws two buttons and returns their ids
+    fn frame(ctx: &egui::Context, events: &[Event]) -> (egui::Id, egui::Id) {
+        let raw_input = egui::RawInput {
+            events: events.iter().filter_map(|event| parse_event(event, 1.0)).collect(),
+            ..egui::RawInput::default()
+        };
+
+        let mut ids = (egui::Id::NULL, egui::Id::NULL);
+        let _ = ctx.run(raw_input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ids.0 = ui.button("Play").id;
+                ids.1 = ui.button("Quit").id;
+            });
+        });
+
+        ids
+    }
+
+    #[test]
+    fn tab_moves_focus_between_widgets() {
+        let ctx = egui::Context::default();
+
+        let (play, quit) = frame(&ctx, &[]);
+        assert_eq!(ctx.memory(|memory| memory.focus()), None);
+
+        frame(&ctx, &[tab()]);
+        assert_eq!(ctx.memory(|memory| memory.focus()), Some(play));
+
+        frame(&ctx, &[tab()]);
+        assert_eq!(ctx.memory(|memory| memory.focus()), Some(quit));
+
+        // the d-pad moves it back up
+        let up = ControllerButtonDown {
+            timestamp: 0,
+            which: 0,
+            button: Button::DPadUp,
+        };
+        frame(&ctx, &[up]);
+        frame(&ctx, &[]);
+        assert_eq!(ctx.memory(|memory| memory.focus()), Some(play));
+    }
+}
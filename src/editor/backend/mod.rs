@@ -15,6 +15,8 @@ pub mod prelude {
         pub ctx: egui::Context,
         painter: Painter,
         raw_input: RawInput,
+
+        focus_request: Option<egui::Id>,
     }
 
     impl EguiBackend {
@@ -27,11 +29,24 @@ pub mod prelude {
                 ctx,
                 painter,
                 raw_input,
+                focus_request: None,
             })
         }
 
+        /**
+         * gives the widget with this id keyboard focus when the next frame
+         * starts, e.g. the first button of a menu that was just opened
+         */
+        pub fn request_focus(&mut self, id: egui::Id) {
+            self.focus_request = Some(id);
+        }
+
         pub fn begin_frame(&mut self) {
             self.ctx.begin_frame(self.raw_input.take());
+
+            if let Some(id) = self.focus_request.take() {
+                self.ctx.memory_mut(|memory| memory.request_focus(id));
+            }
         }
 
         pub fn end_frame(&mut self, world: &mut World) {
@@ -55,7 +70,7 @@ pub mod prelude {
                         _ => (),
                     },
                     _ => {
-                        if let Some(parsed) = parse_event(&event, &self.painter) {
+                        if let Some(parsed) = parse_event(event, self.painter.pixels_per_point) {
                             self.raw_input.events.push(parsed);
                         }
                     }
@@ -229,6 +229,7 @@ fn parse_vertices(mesh: &Mesh) -> Vec<Vertex> {
             ),
             tex_coords: glm::vec2(row.uv.x, row.uv.y),
             tex_index: 0.0,
+            ..Vertex::default()
         });
     }
 
@@ -31,6 +31,16 @@ pub mod prelude {
         pub fn ctx(&self) -> &Context {
             &self.backend.ctx
         }
+
+        /**
+         * gives a widget keyboard focus from the next frame, so a menu can
+         * be driven with Tab, the arrow keys and Enter without clicking into
+         * it first. the id is the one on the widget's response, e.g.
+         * `ui.button("Play").id`
+         */
+        pub fn set_initial_focus(&mut self, id: egui::Id) {
+            self.backend.request_focus(id);
+        }
     }
 
     impl Controller for GuiManager {
@@ -69,6 +69,12 @@ pub enum QPError {
     #[error("failed to upgrade weak reference")]
     SharedReferenceDropped,
 
+    #[error("there was a problem playing audio: {0}")]
+    AudioError(String),
+
+    #[error("input recording can't be played back: {0}")]
+    RecordingMismatch(String),
+
     #[error("failed to get a lock: {0}")]
     MutexLockFailed(String),
 }
@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+
+use crate::{
+    prelude::{
+        qp_assets::RTexture,
+        qp_core::{to_abs_path, QPImage},
+        qp_gfx::texture,
+        GlobalRegistry, QPError,
+    },
+    schemas::sprite::TextureAtlas,
+    QPResult,
+};
+
+/**
+* where an image ended up in a packed atlas, in pixels from the top left
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtlasRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl AtlasRegion {
+    pub fn overlaps(&self, other: &AtlasRegion) -> bool {
+        self.x < other.x + other.width
+            && other.x < self.x + self.width
+            && self.y < other.y + other.height
+            && other.y < self.y + self.height
+    }
+}
+
+struct AtlasImage {
+    name: String,
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+/**
+* combines lots of small images into one texture so the sprites using them
+* batch together. images are packed onto shelves, tallest first
+*
+* ```no_run
+* # use quipi::prelude::{qp_gfx::AtlasPacker, GlobalRegistry, QPError};
+* # fn load(registry: &mut GlobalRegistry) -> Result<(), QPError> {
+* let mut packer = AtlasPacker::new(1024, 1024);
+* packer.add_image("ship", "assets/textures/ship.png")?;
+* packer.add_image("bullet", "assets/textures/bullet.png")?;
+*
+* let atlas = packer.pack()?;
+* let texture = atlas.upload(registry, "sprites")?;
+* let ship = atlas.texture_atlas(texture, "ship");
+* # Ok(())
+* # }
+* ```
+*/
+pub struct AtlasPacker {
+    width: u32,
+    height: u32,
+
+    /**
+     * empty pixels left around every image so filtering doesn't bleed
+     * neighbours into each other
+     */
+    pub padding: u32,
+
+    images: Vec<AtlasImage>,
+}
+
+impl AtlasPacker {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            padding: 1,
+            images: vec![],
+        }
+    }
+
+    /**
+     * rgba is 8 bit rgba, top row first. adding a name twice replaces the
+     * first image
+     */
+    pub fn add(&mut self, name: &str, width: u32, height: u32, rgba: Vec<u8>) {
+        debug_assert_eq!(rgba.len(), (width * height * 4) as usize);
+
+        self.images.retain(|image| image.name != name);
+
+        self.images.push(AtlasImage {
+            name: name.to_string(),
+            width,
+            height,
+            rgba,
+        });
+    }
+
+    /**
+     * path is relative to the executable
+     */
+    pub fn add_image(&mut self, name: &str, path: &str) -> QPResult<()> {
+        let image = QPImage::from_file(&to_abs_path(path)?)?;
+
+        self.add(name, image.width, image.height, image.rgba());
+
+        Ok(())
+    }
+
+    /**
+     * lays the images out and copies them into one buffer. fails if they
+     * don't all fit
+     */
+    pub fn pack(&self) -> QPResult<PackedAtlas> {
+        let mut order = (0..self.images.len()).collect::<Vec<_>>();
+        order.sort_by_key(|i| std::cmp::Reverse(self.images[*i].height));
+
+        let mut regions = HashMap::new();
+        let (mut x, mut y, mut shelf_height) = (self.padding, self.padding, 0);
+        for i in order {
+            let image = &self.images[i];
+
+            if x + image.width + self.padding > self.width {
+                x = self.padding;
+                y += shelf_height + self.padding;
+                shelf_height = 0;
+            }
+
+            if x + image.width + self.padding > self.width
+                || y + image.height + self.padding > self.height
+            {
+                return Err(QPError::Generic(format!(
+                    "{} doesn't fit in a {}x{} atlas",
+                    image.name, self.width, self.height
+                )));
+            }
+
+            regions.insert(
+                image.name.clone(),
+                AtlasRegion {
+                    x,
+                    y,
+                    width: image.width,
+                    height: image.height,
+                },
+            );
+
+            x += image.width + self.padding;
+            shelf_height = shelf_height.max(image.height);
+        }
+
+        let mut pixels = vec![0; (self.width * self.height * 4) as usize];
+        for image in self.images.iter() {
+            let region = regions[&image.name];
+            let row_len = (image.width * 4) as usize;
+
+            for row in 0..image.height {
+                let src = (row * image.width * 4) as usize;
+                let dst = (((region.y + row) * self.width + region.x) * 4) as usize;
+
+                pixels[dst..dst + row_len].copy_from_slice(&image.rgba[src..src + row_len]);
+            }
+        }
+
+        Ok(PackedAtlas {
+            width: self.width,
+            height: self.height,
+            pixels,
+            regions,
+        })
+    }
+}
+
+pub struct PackedAtlas {
+    pub width: u32,
+    pub height: u32,
+
+    /**
+     * 8 bit rgba, top row first
+     */
+    pub pixels: Vec<u8>,
+    pub regions: HashMap<String, AtlasRegion>,
+}
+
+impl PackedAtlas {
+    /**
+     * creates the gl texture and loads it as an RTexture called name
+     */
+    pub fn upload(&self, registry: &mut GlobalRegistry, name: &str) -> QPResult<u64> {
+        // gl expects the bottom row first
+        let row_len = (self.width * 4) as usize;
+        let flipped = self
+            .pixels
+            .chunks(row_len)
+            .rev()
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+
+        let texture = texture::from_buffer_rgba(self.width as i32, self.height as i32, &flipped);
+
+        registry.asset_manager.load_asset(
+            name,
+            RTexture {
+                texture,
+                texture_dims: glm::vec2(1.0, 1.0),
+            },
+        )
+    }
+
+    /**
+     * a TextureAtlas that shows only the named image, for a CSprite using
+     * the uploaded texture
+     */
+    pub fn texture_atlas(&self, texture: u64, name: &str) -> Option<TextureAtlas> {
+        let region = self.regions.get(name)?;
+
+        // the sprite samples (active + 0..1) / dims, with v going up
+        let dims = glm::vec2(
+            self.width as f32 / region.width as f32,
+            self.height as f32 / region.height as f32,
+        );
+        let bottom = self.height - (region.y + region.height);
+
+        Some(TextureAtlas {
+            texture,
+            texture_dims: dims,
+            active_texture: glm::vec2(
+                region.x as f32 / region.width as f32,
+                bottom as f32 / region.height as f32,
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, value: u8) -> Vec<u8> {
+        vec![value; (width * height * 4) as usize]
+    }
+
+    fn packer() -> AtlasPacker {
+        let mut packer = AtlasPacker::new(64, 64);
+        let sizes = [
+            (16, 16),
+            (8, 30),
+            (20, 10),
+            (12, 12),
+            (30, 8),
+            (5, 5),
+            (24, 18),
+        ];
+        for (i, (width, height)) in sizes.iter().enumerate() {
+            packer.add(
+                &format!("image_{}", i),
+                *width,
+                *height,
+                solid(*width, *height, i as u8 + 1),
+            );
+        }
+
+        packer
+    }
+
+    #[test]
+    fn atlas_regions_dont_overlap() {
+        let atlas = packer().pack().unwrap();
+        let regions = atlas.regions.values().collect::<Vec<_>>();
+        assert_eq!(regions.len(), 7);
+
+        for (i, a) in regions.iter().enumerate() {
+            assert!(a.x + a.width <= atlas.width && a.y + a.height <= atlas.height);
+
+            for b in regions.iter().skip(i + 1) {
+                assert!(!a.overlaps(b), "{:?} overlaps {:?}", a, b);
+            }
+        }
+
+        // every image's pixels were copied into its region
+        for (name, region) in atlas.regions.iter() {
+            let value = name.trim_start_matches("image_").parse::<u8>().unwrap() + 1;
+            for (x, y) in [(0, 0), (region.width - 1, region.height - 1)] {
+                let i = (((region.y + y) * atlas.width + region.x + x) * 4) as usize;
+                assert_eq!(atlas.pixels[i], value);
+            }
+        }
+    }
+
+    #[test]
+    fn atlas_too_small() {
+        let mut packer = packer();
+        packer.add("huge", 60, 60, solid(60, 60, 9));
+
+        assert!(packer.pack().is_err());
+    }
+
+    #[test]
+    fn atlas_texture_atlas_covers_region() {
+        let atlas = packer().pack().unwrap();
+        let region = atlas.regions["image_2"];
+        let texture_atlas = atlas.texture_atlas(7, "image_2").unwrap();
+
+        // the same maths CSprite uses for its tex coords
+        let uv = |corner: f32, axis: usize| {
+            (corner + texture_atlas.active_texture[axis]) / texture_atlas.texture_dims[axis]
+        };
+
+        let size = atlas.width as f32;
+        assert!((uv(0.0, 0) - region.x as f32 / size).abs() < 1e-5);
+        assert!((uv(1.0, 0) - (region.x + region.width) as f32 / size).abs() < 1e-5);
+
+        // v is flipped: the region's top row is the highest v
+        assert!((uv(1.0, 1) - (size - region.y as f32) / size).abs() < 1e-5);
+        assert!((uv(0.0, 1) - (size - (region.y + region.height) as f32) / size).abs() < 1e-5);
+
+        assert!(atlas.texture_atlas(7, "missing").is_none());
+    }
+}
@@ -14,31 +14,58 @@ use crate::{
     prelude::qp_assets::{RShader, RTexture},
 };
 
-pub struct BatchRenderer<const C: usize, M: Mesh> {
+/**
+* meshes per batch used by the built-in renderers
+*/
+pub const DEFAULT_BATCH_SIZE: usize = 10000;
+
+// the gl objects a batch is uploaded to and drawn from
+struct BatchBuffers {
     vao: VertexArray,
     _ebo: Buffer<EBO>,
     vbo: Buffer<VBO>,
+}
+
+pub struct BatchRenderer<M: Mesh> {
+    // None for a headless renderer, see `headless`
+    buffers: Option<BatchBuffers>,
 
     indices_count: usize,
     max_textures: i32,
-    textures: Vec<u32>,
-    mesh_count: usize,
+    state: BatchState,
     vertices: Vec<Vertex>,
+    info: RenderInfo,
 
-    pub draw_calls: u32,
+    #[cfg(test)]
+    drawn: Vec<DrawCall>,
 
     _marker: PhantomData<M>,
 }
 
-impl<const C: usize, M: Mesh> BatchRenderer<C, M> {
-    pub fn new() -> Self {
+/**
+* one flushed batch of a headless renderer
+*/
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DrawCall {
+    pub shader: u32,
+    pub textures: Vec<u32>,
+    pub vertices: Vec<Vertex>,
+}
+
+impl<M: Mesh> BatchRenderer<M> {
+    /**
+     * `max_batch_size` is the number of meshes the vertex buffer holds. going
+     * past it flushes the batch and starts a new one, costing a draw call
+     */
+    pub fn new(max_batch_size: usize) -> Self {
         let stride = std::mem::size_of::<Vertex>();
 
         let base_indices = M::indices();
-        let vertex_capacity = C * M::vertex_count();
-        let mut indices = Vec::<u32>::with_capacity(base_indices.len() * C);
+        let vertex_capacity = max_batch_size * M::vertex_count();
+        let mut indices = Vec::<u32>::with_capacity(base_indices.len() * max_batch_size);
         let offset_delta = M::vertex_count();
-        for i in 0..C {
+        for i in 0..max_batch_size {
             let offset = i * offset_delta;
             for index in &base_indices {
                 indices.push(*index as u32 + offset as u32);
@@ -75,116 +102,399 @@ impl<const C: usize, M: Mesh> BatchRenderer<C, M> {
             stride,
             offset_of!(Vertex => tex_index).get_byte_offset(),
         );
+        vertex_attribute_pointer(4, 2, stride, offset_of!(Vertex => local).get_byte_offset());
+        vertex_attribute_pointer(5, 4, stride, offset_of!(Vertex => shape).get_byte_offset());
 
         vao.unbind();
         ebo.unbind();
 
+        let max_textures = max_texture_slots();
+
         Self {
-            vao,
-            _ebo: ebo,
-            vbo,
+            buffers: Some(BatchBuffers {
+                vao,
+                _ebo: ebo,
+                vbo,
+            }),
 
             indices_count: M::indices().len(),
-            max_textures: max_texture_slots(),
-            textures: vec![],
-            mesh_count: 0,
+            max_textures,
+            state: BatchState::new(max_batch_size, max_textures as usize),
             vertices: Vec::<Vertex>::with_capacity(vertex_capacity),
-            draw_calls: 0,
+            info: RenderInfo::default(),
+
+            #[cfg(test)]
+            drawn: vec![],
+
+            _marker: PhantomData,
+        }
+    }
+
+    /**
+     * a renderer that batches like any other but never touches gl, for tests
+     * that run without a context. what it would have drawn is in `drawn`
+     */
+    #[cfg(test)]
+    pub(crate) fn headless(max_batch_size: usize, max_textures: usize) -> Self {
+        Self {
+            buffers: None,
+
+            indices_count: M::indices().len(),
+            max_textures: max_textures as i32,
+            state: BatchState::new(max_batch_size, max_textures),
+            vertices: Vec::<Vertex>::with_capacity(max_batch_size * M::vertex_count()),
+            info: RenderInfo::default(),
+
+            drawn: vec![],
 
             _marker: PhantomData,
         }
     }
 
+    /**
+     * the batches flushed since the last `reset_info`
+     */
+    #[cfg(test)]
+    pub(crate) fn drawn(&self) -> &[DrawCall] {
+        &self.drawn
+    }
+
     pub fn begin_batch(&mut self) {
-        self.mesh_count = 0;
-        self.textures.clear();
+        self.state.clear();
         self.vertices.clear();
     }
 
+    /**
+     * limit how many textures can be bound in a single draw call. a batch is
+     * flushed when a mesh needs a texture that doesn't fit, so a value of 1
+     * gives one draw per texture
+     */
+    pub fn set_texture_slots(&mut self, slots: usize) {
+        self.state.max_textures = slots.clamp(1, self.max_textures as usize);
+    }
+
     pub fn flush_batch(&mut self, shader: &RShader) {
         if self.vertices.is_empty() {
             return;
         }
 
-        shader.program.use_program();
+        if let Some(buffers) = &self.buffers {
+            shader.program.use_program();
+
+            for (i, texture) in self.state.textures().iter().enumerate() {
+                use_texture(*texture, i as i32);
+                shader
+                    .program
+                    .set_int(&format!("u_textures[{}]", i), i as i32);
+            }
 
-        for i in 0..self.textures.len() {
-            use_texture(self.textures[i], i as i32);
-            shader
-                .program
-                .set_int(&format!("u_textures[{}]", i), i as i32);
+            buffers.vao.bind();
+            opengl::draw::gl_draw(
+                DrawBuffer::Elements,
+                DrawMode::Triangles, // TODO: this is hardcoded
+                (self.indices_count * self.state.mesh_count()) as i32,
+            );
+            buffers.vao.unbind();
         }
 
-        self.vao.bind();
-        opengl::draw::gl_draw(
-            DrawBuffer::Elements,
-            DrawMode::Triangles, // TODO: this is hardcoded
-            (self.indices_count * self.mesh_count) as i32,
-        );
-        self.vao.unbind();
+        #[cfg(test)]
+        self.drawn.push(DrawCall {
+            shader: shader.program.id,
+            textures: self.state.textures().to_vec(),
+            vertices: self.vertices.clone(),
+        });
 
-        self.draw_calls += 1;
+        self.info.record(&self.state);
     }
 
     pub fn end_batch(&self) {
-        self.vbo.bind();
-        self.vbo
+        let Some(buffers) = &self.buffers else {
+            return;
+        };
+
+        buffers.vbo.bind();
+        buffers
+            .vbo
             .buffer_sub_data::<Vertex>(0, self.vertices.len(), Some(&self.vertices));
-        self.vbo.unbind();
+        buffers.vbo.unbind();
     }
 
     pub fn reset_info(&mut self) {
-        self.draw_calls = 0;
+        self.info = RenderInfo::default();
+
+        #[cfg(test)]
+        self.drawn.clear();
+    }
+
+    /**
+     * what was drawn since the last `reset_info`
+     */
+    pub fn info(&self) -> RenderInfo {
+        self.info
     }
 
     pub fn draw_mesh(&mut self, mesh: &M, shader: &RShader, texture: Option<&RTexture>) {
-        let mut texture_slot = self.max_textures as usize;
-        if let Some(texture) = texture {
-            let id = texture.texture.id;
-
-            if let Some((i, _)) = self
-                .textures
-                .iter()
-                .enumerate()
-                .find(|(_, tex)| **tex == id)
-            {
-                texture_slot = i;
-            } else {
-                if self.textures.len() >= self.max_textures as usize {
-                    self.batch_reset(shader);
-                }
+        self.draw_vertices(mesh.vertices(), shader, texture);
+    }
 
-                self.textures.push(id);
+    /**
+     * like `draw_mesh`, for a mesh's vertices that have been changed (eg. by
+     * `CSprite::shaped_vertices`). there must be `M::vertex_count` of them
+     */
+    pub fn draw_vertices(
+        &mut self,
+        vertices: Vec<Vertex>,
+        shader: &RShader,
+        texture: Option<&RTexture>,
+    ) {
+        let texture = texture.map(|texture| texture.texture.id);
+
+        self.draw_vertices_by_id(vertices, shader, texture);
+    }
 
-                texture_slot = self.textures.len() - 1;
-            }
+    /**
+     * like `draw_vertices`, for a texture known only by its gl id (eg. a glyph
+     * kept in a `TextLayoutCache`)
+     */
+    pub fn draw_vertices_by_id(
+        &mut self,
+        vertices: Vec<Vertex>,
+        shader: &RShader,
+        texture: Option<u32>,
+    ) {
+        debug_assert_eq!(vertices.len(), M::vertex_count());
+
+        if self.state.needs_flush(texture) {
+            self.batch_reset(shader);
         }
 
-        for mut vertex in mesh.vertices() {
+        // untextured meshes use a slot past the end, which the shader treats as "no texture"
+        let texture_slot = self
+            .state
+            .push(texture)
+            .unwrap_or(self.max_textures as usize);
+
+        for mut vertex in vertices {
             vertex.tex_index = texture_slot as f32;
             self.vertices.push(vertex);
         }
 
-        self.mesh_count += 1;
-
-        if self.mesh_count >= C {
+        if self.state.is_full() {
             self.batch_reset(shader);
         }
     }
 
-    fn batch_reset(&mut self, shader: &RShader) {
+    /**
+     * draws everything queued with the shader and starts a new batch. call it
+     * before drawing meshes with a different shader
+     */
+    pub fn batch_reset(&mut self, shader: &RShader) {
         self.end_batch();
         self.flush_batch(shader);
         self.begin_batch();
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+/**
+* counts of what a renderer sent to the gpu. fewer draw calls and texture
+* binds for the same meshes means the batching is doing its job
+*/
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RenderInfo {
+    pub draw_calls: u32,
+    pub texture_binds: u32,
+    pub meshes: u32,
+
+    /**
+     * the draw calls that were instanced, and the meshes they drew
+     */
+    pub instanced_draws: u32,
+    pub instances: u32,
+}
+
+impl RenderInfo {
+    /**
+     * counts the batch as one draw call
+     */
+    pub fn record(&mut self, state: &BatchState) {
+        self.draw_calls += 1;
+        self.texture_binds += state.textures().len() as u32;
+        self.meshes += state.mesh_count() as u32;
+    }
+
+    /**
+     * counts one instanced draw call of `count` meshes
+     */
+    pub fn record_instances(&mut self, count: usize, textured: bool) {
+        self.draw_calls += 1;
+        self.texture_binds += textured as u32;
+        self.meshes += count as u32;
+        self.instanced_draws += 1;
+        self.instances += count as u32;
+    }
+
+    /**
+     * the average meshes drawn by each instanced draw call
+     */
+    pub fn instances_per_draw(&self) -> f32 {
+        match self.instanced_draws {
+            0 => 0.0,
+            draws => self.instances as f32 / draws as f32,
+        }
+    }
+}
+
+impl std::ops::Add for RenderInfo {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            draw_calls: self.draw_calls + other.draw_calls,
+            texture_binds: self.texture_binds + other.texture_binds,
+            meshes: self.meshes + other.meshes,
+            instanced_draws: self.instanced_draws + other.instanced_draws,
+            instances: self.instances + other.instances,
+        }
+    }
+}
+
+/**
+* gl-free bookkeeping for the batch being built: which textures are bound to
+* which slots and how many meshes are queued
+*/
+#[derive(Debug)]
+pub struct BatchState {
+    max_meshes: usize,
+    max_textures: usize,
+    textures: Vec<u32>,
+    mesh_count: usize,
+}
+
+impl BatchState {
+    pub fn new(max_meshes: usize, max_textures: usize) -> Self {
+        Self {
+            max_meshes,
+            max_textures,
+            textures: Vec::with_capacity(max_textures),
+            mesh_count: 0,
+        }
+    }
+
+    /**
+     * true if the texture isn't bound yet and every slot is taken
+     */
+    pub fn needs_flush(&self, texture: Option<u32>) -> bool {
+        match texture {
+            Some(id) => !self.textures.contains(&id) && self.textures.len() >= self.max_textures,
+            None => false,
+        }
+    }
+
+    /**
+     * queue a mesh, returning the slot its texture is bound to
+     */
+    pub fn push(&mut self, texture: Option<u32>) -> Option<usize> {
+        self.mesh_count += 1;
+
+        let id = texture?;
+        match self.textures.iter().position(|tex| *tex == id) {
+            Some(slot) => Some(slot),
+            None => {
+                self.textures.push(id);
+
+                Some(self.textures.len() - 1)
+            }
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.mesh_count >= self.max_meshes
+    }
+
+    pub fn clear(&mut self) {
+        self.textures.clear();
+        self.mesh_count = 0;
+    }
+
+    pub fn textures(&self) -> &[u32] {
+        &self.textures
+    }
+
+    pub fn mesh_count(&self) -> usize {
+        self.mesh_count
+    }
+}
+
+/**
+* caches the draw order between frames
+*
+* meshes are drawn in the order they're submitted, layer by layer, so
+* overlapping translucent sprites blend back to front. nothing is reordered by
+* texture: a run of meshes sharing a texture, or fitting in the texture slots
+* already bound, goes out in one draw call anyway. mostly static scenes (eg. a
+* tilemap) submit the same keys every frame, so comparing against last frame's
+* keys is enough to skip the sort
+*/
+#[derive(Debug)]
+pub struct BatchOrder<T> {
+    keys: Vec<(T, Option<u64>)>,
+    sorted: Vec<(T, Option<u64>)>,
+}
+
+impl<T> Default for BatchOrder<T> {
+    fn default() -> Self {
+        Self {
+            keys: vec![],
+            sorted: vec![],
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> BatchOrder<T> {
+    /**
+     * takes this frame's (item, texture) keys in submission order and stable
+     * sorts them by layer, lowest first, so items on the same layer keep the
+     * order they came in. returns true if the keys changed and the order had
+     * to be rebuilt
+     */
+    pub fn update_layered<K: Ord>(
+        &mut self,
+        keys: Vec<(T, Option<u64>)>,
+        layer: impl Fn(&T) -> K,
+    ) -> bool {
+        if keys == self.keys {
+            return false;
+        }
+
+        self.sorted.clone_from(&keys);
+        self.sorted.sort_by_key(|(item, _)| layer(item));
+        self.keys = keys;
+
+        true
+    }
+
+    pub fn sorted(&self) -> &[(T, Option<u64>)] {
+        &self.sorted
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct Vertex {
     pub position: glm::Vec3,
     pub color: glm::Vec4,
     pub tex_coords: glm::Vec2,
     pub tex_index: f32,
+
+    /**
+     * where the vertex is relative to the middle of its shape, see `CShape`
+     */
+    pub local: glm::Vec2,
+
+    /**
+     * half width, half height, corner radius and edge softness of the shape
+     * the quad is cut into. all zero draws the whole quad
+     */
+    pub shape: glm::Vec4,
 }
 
 pub trait Mesh {
@@ -192,3 +502,210 @@ pub trait Mesh {
     fn indices() -> Vec<i32>;
     fn vertex_count() -> usize;
 }
+
+#[cfg(test)]
+mod tests {
+    use std::mem::ManuallyDrop;
+
+    use super::*;
+    use crate::platform::opengl::{shader::ShaderProgram, textures::Texture};
+
+    // one vertex per mesh, at x = the order it was drawn in
+    struct Point(f32);
+
+    impl Mesh for Point {
+        fn vertices(&self) -> Vec<Vertex> {
+            vec![Vertex {
+                position: glm::vec3(self.0, 0.0, 0.0),
+                ..Vertex::default()
+            }]
+        }
+
+        fn indices() -> Vec<i32> {
+            vec![0]
+        }
+
+        fn vertex_count() -> usize {
+            1
+        }
+    }
+
+    // headless, so never dropped
+    fn shader() -> ManuallyDrop<RShader> {
+        ManuallyDrop::new(RShader {
+            program: ShaderProgram::headless(1),
+            uniforms: vec![],
+        })
+    }
+
+    fn texture(id: u32) -> ManuallyDrop<RTexture> {
+        ManuallyDrop::new(RTexture {
+            texture: Texture::headless(id, 1, 1),
+            texture_dims: glm::vec2(1.0, 1.0),
+        })
+    }
+
+    // draws a mesh per texture in order, returning the mesh count and the
+    // textures bound for every draw call
+    fn render(
+        renderer: &mut BatchRenderer<Point>,
+        textures: &[Option<u32>],
+    ) -> Vec<(usize, Vec<u32>)> {
+        let shader = shader();
+        let textures = textures
+            .iter()
+            .map(|id| id.map(texture))
+            .collect::<Vec<_>>();
+
+        renderer.reset_info();
+        renderer.begin_batch();
+        for (i, texture) in textures.iter().enumerate() {
+            renderer.draw_mesh(&Point(i as f32), &shader, texture.as_deref());
+        }
+        renderer.batch_reset(&shader);
+
+        renderer
+            .drawn()
+            .iter()
+            .map(|draw| (draw.vertices.len(), draw.textures.clone()))
+            .collect()
+    }
+
+    fn drawn_order(renderer: &BatchRenderer<Point>) -> Vec<f32> {
+        renderer
+            .drawn()
+            .iter()
+            .flat_map(|draw| draw.vertices.iter().map(|vertex| vertex.position.x))
+            .collect()
+    }
+
+    #[test]
+    fn batch_one_draw_per_texture() {
+        let mut renderer = BatchRenderer::<Point>::headless(100, 1);
+        let textures = [1, 1, 2, 2, 3, 3].map(Some);
+
+        assert_eq!(
+            render(&mut renderer, &textures),
+            vec![(2, vec![1]), (2, vec![2]), (2, vec![3])]
+        );
+    }
+
+    #[test]
+    fn batch_interleaved_textures_keep_their_order() {
+        let textures = [1, 2, 3, 1, 2, 3].map(Some);
+
+        // one slot flushes on every switch, rather than reordering
+        let mut renderer = BatchRenderer::<Point>::headless(100, 8);
+        renderer.set_texture_slots(1);
+        render(&mut renderer, &textures);
+        let one_slot = renderer.info();
+        assert_eq!(
+            one_slot,
+            RenderInfo {
+                draw_calls: 6,
+                texture_binds: 6,
+                meshes: 6,
+                ..RenderInfo::default()
+            }
+        );
+        assert_eq!(drawn_order(&renderer), vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+
+        // with a slot each they share one draw, still back to front
+        renderer.set_texture_slots(3);
+        assert_eq!(render(&mut renderer, &textures), vec![(6, vec![1, 2, 3])]);
+        let shared = renderer.info();
+        assert_eq!(
+            shared,
+            RenderInfo {
+                draw_calls: 1,
+                texture_binds: 3,
+                meshes: 6,
+                ..RenderInfo::default()
+            }
+        );
+        assert_eq!(drawn_order(&renderer), vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!((one_slot + shared).draw_calls, 7);
+    }
+
+    #[test]
+    fn batch_interleaved_textures_flush() {
+        let mut renderer = BatchRenderer::<Point>::headless(100, 1);
+        let textures = [Some(1), Some(2), Some(1), None, Some(1)];
+
+        // every switch flushes, untextured meshes ride along with the current batch
+        let draws = render(&mut renderer, &textures);
+        assert_eq!(draws, vec![(1, vec![1]), (1, vec![2]), (3, vec![1])]);
+        assert_eq!(drawn_order(&renderer), vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn batch_size_limit_flushes() {
+        let mut renderer = BatchRenderer::<Point>::headless(10, 32);
+        let textures = vec![None; 25];
+
+        let draws = render(&mut renderer, &textures)
+            .into_iter()
+            .map(|(meshes, _)| meshes)
+            .collect::<Vec<_>>();
+
+        assert_eq!(draws, vec![10, 10, 5]);
+        assert_eq!(renderer.info().meshes, 25);
+    }
+
+    #[test]
+    fn batch_shares_slots() {
+        let mut state = BatchState::new(100, 2);
+
+        assert_eq!(state.push(Some(7)), Some(0));
+        assert_eq!(state.push(Some(9)), Some(1));
+        assert_eq!(state.push(Some(7)), Some(0));
+        assert_eq!(state.push(None), None);
+        assert!(!state.needs_flush(Some(9)));
+        assert!(state.needs_flush(Some(4)));
+        assert!(!state.needs_flush(None));
+    }
+
+    #[test]
+    fn batch_order_skips_unchanged_frames() {
+        let mut order = BatchOrder::<usize>::default();
+        let frame = vec![(0, Some(2)), (1, Some(1)), (2, None), (3, Some(2))];
+
+        // one layer, so nothing moves
+        assert!(order.update_layered(frame.clone(), |_| 0));
+        assert_eq!(order.sorted(), &frame[..]);
+
+        // nothing changed, nothing to sort
+        assert!(!order.update_layered(frame.clone(), |_| 0));
+        assert!(!order.update_layered(frame.clone(), |_| 0));
+
+        // a texture swap or a new sprite forces a rebuild
+        let mut changed = frame.clone();
+        changed[2].1 = Some(1);
+        assert!(order.update_layered(changed.clone(), |_| 0));
+        assert_eq!(order.sorted(), &changed[..]);
+
+        changed.push((4, None));
+        assert!(order.update_layered(changed, |_| 0));
+    }
+
+    #[test]
+    fn batch_order_keeps_layers_in_order() {
+        let mut order = BatchOrder::<(i32, usize)>::default();
+        let frame = vec![
+            ((1, 0), Some(1)),
+            ((-1, 1), Some(2)),
+            ((0, 2), Some(2)),
+            ((-1, 3), Some(1)),
+            ((1, 4), None),
+        ];
+
+        // the background layer first, each layer in the order it came in
+        assert!(order.update_layered(frame, |(layer, _)| *layer));
+        let sorted = order
+            .sorted()
+            .iter()
+            .map(|((_, i), _)| *i)
+            .collect::<Vec<_>>();
+        assert_eq!(sorted, vec![1, 3, 2, 0, 4]);
+    }
+}
@@ -1,7 +1,11 @@
 // mod grid;
+mod atlas;
 mod batch_renderer;
+mod pixel_perfect;
+mod render_graph;
 mod renderers;
 mod shaders;
+mod stencil;
 mod texture;
 mod viewport;
 
@@ -14,10 +18,17 @@ pub mod prelude {
     use super::*;
 
     // pub use grid::*;
+    pub use atlas::*;
     pub use batch_renderer::*;
+    pub use pixel_perfect::*;
+    pub use render_graph::*;
     pub use renderers::*;
     pub use shaders::*;
+    pub use stencil::*;
     pub use texture::texture;
+    pub use viewport::ClearColor;
+    pub use viewport::ClearConfig;
+    pub use viewport::{integer_scale, pixel_perfect_rect};
     pub use viewport::Viewport;
 
     pub fn init(window_api: &QPWindow) -> QPResult<()> {
@@ -0,0 +1,105 @@
+use crate::{
+    platform::opengl::{
+        buffer::clear_buffers_masked, framebuffer::FrameBuffer,
+        functions::gl_set_viewport_dimensions,
+    },
+    QPResult,
+};
+
+use super::viewport::{integer_scale, pixel_perfect_rect, ClearConfig, Viewport};
+
+/**
+* renders the scene at a fixed internal resolution, then scales it up to the
+* window by the biggest whole number that fits, with nearest filtering and
+* black bars around it. for pixel art that should stay crisp at any window
+* size
+*
+* set it up with `App::set_pixel_perfect`, which also sizes the viewport to
+* match so mouse positions line up
+*/
+pub struct PixelPerfectCamera {
+    pub width: i32,
+    pub height: i32,
+
+    // made on the first frame, there's no gl context before then in tests
+    framebuffer: Option<FrameBuffer>,
+}
+
+impl PixelPerfectCamera {
+    pub fn new(width: i32, height: i32) -> Self {
+        Self {
+            width,
+            height,
+            framebuffer: None,
+        }
+    }
+
+    /**
+     * how many window pixels each internal pixel takes up
+     */
+    pub fn scale(&self, window_width: i32, window_height: i32) -> i32 {
+        integer_scale(window_width, window_height, self.width, self.height)
+    }
+
+    /**
+     * where the scaled up scene goes in the window, (x, y, width, height)
+     */
+    pub fn output_rect(&self, window_width: i32, window_height: i32) -> (i32, i32, i32, i32) {
+        pixel_perfect_rect(window_width, window_height, self.width, self.height)
+    }
+
+    /**
+     * call before drawing. everything until `end` is drawn at the internal
+     * resolution, starting from the configured clear
+     */
+    pub fn begin(&mut self, clear: &ClearConfig) -> QPResult<()> {
+        if self.framebuffer.is_none() {
+            self.framebuffer = Some(FrameBuffer::new(self.width, self.height)?);
+        }
+
+        if let Some(framebuffer) = &self.framebuffer {
+            framebuffer.bind();
+            gl_set_viewport_dimensions(0, 0, self.width, self.height);
+            clear_buffers_masked(clear.color, clear.clear_depth);
+        }
+
+        Ok(())
+    }
+
+    /**
+     * scales what was drawn up into the viewport, and goes back to drawing
+     * on the window
+     */
+    pub fn end(&self, viewport: &Viewport) {
+        let (x, y, width, height) = viewport.get_dimensions();
+
+        if let Some(framebuffer) = &self.framebuffer {
+            framebuffer.blit_to_window(x, y, width, height);
+        }
+        gl_set_viewport_dimensions(x, y, width, height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pixel_perfect_scales_by_whole_numbers() {
+        let camera = PixelPerfectCamera::new(320, 180);
+
+        assert_eq!(camera.scale(1280, 720), 4);
+        assert_eq!(camera.output_rect(1280, 720), (0, 0, 1280, 720));
+
+        // the spare pixels become bars either side
+        assert_eq!(camera.scale(1366, 768), 4);
+        assert_eq!(camera.output_rect(1366, 768), (43, 24, 1280, 720));
+
+        // the smaller side decides
+        assert_eq!(camera.scale(1920, 400), 2);
+        assert_eq!(camera.output_rect(1920, 400), (640, 20, 640, 360));
+
+        // a window smaller than the target still draws it, cropped
+        assert_eq!(camera.scale(200, 100), 1);
+    }
+}
@@ -0,0 +1,259 @@
+use super::batch_renderer::RenderInfo;
+use crate::prelude::{QPError, Renderer, World};
+use crate::QPResult;
+
+struct RenderPass {
+    name: String,
+    order: i32,
+    renderer: Box<dyn Renderer>,
+}
+
+/**
+* the renderers drawn each frame, in order. passes are drawn from the lowest
+* order to the highest, and passes with the same order in the order they were
+* added
+*
+* the built-in orders leave gaps so custom passes can go between them. each
+* pass gets the world and reads its camera from the asset manager, the same as
+* any other renderer
+*/
+#[derive(Default)]
+pub struct RenderGraph {
+    passes: Vec<RenderPass>,
+}
+
+impl RenderGraph {
+    pub const BACKGROUND: i32 = 0;
+    pub const WORLD: i32 = 100;
+    pub const PARTICLES: i32 = 200;
+    pub const DEBUG: i32 = 300;
+    pub const UI: i32 = 400;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * names must be unique
+     */
+    pub fn add_pass(
+        &mut self,
+        name: &str,
+        order: i32,
+        renderer: impl Renderer + 'static,
+    ) -> QPResult<()> {
+        if self.has_pass(name) {
+            return Err(QPError::Generic(format!(
+                "render pass {} already exists",
+                name
+            )));
+        }
+
+        // after every pass with the same order
+        let index = self.passes.partition_point(|pass| pass.order <= order);
+        self.passes.insert(
+            index,
+            RenderPass {
+                name: name.to_string(),
+                order,
+                renderer: Box::new(renderer),
+            },
+        );
+
+        Ok(())
+    }
+
+    /**
+     * adds a pass drawn straight before an existing one
+     */
+    pub fn insert_before(
+        &mut self,
+        existing: &str,
+        name: &str,
+        renderer: impl Renderer + 'static,
+    ) -> QPResult<()> {
+        self.insert_at(existing, 0, name, renderer)
+    }
+
+    /**
+     * adds a pass drawn straight after an existing one
+     */
+    pub fn insert_after(
+        &mut self,
+        existing: &str,
+        name: &str,
+        renderer: impl Renderer + 'static,
+    ) -> QPResult<()> {
+        self.insert_at(existing, 1, name, renderer)
+    }
+
+    pub fn remove_pass(&mut self, name: &str) -> bool {
+        let Some(index) = self.index_of(name) else {
+            return false;
+        };
+        self.passes.remove(index);
+
+        true
+    }
+
+    /**
+     * pass names in the order they're drawn
+     */
+    pub fn pass_names(&self) -> Vec<&str> {
+        self.passes.iter().map(|pass| pass.name.as_str()).collect()
+    }
+
+    pub fn has_pass(&self, name: &str) -> bool {
+        self.index_of(name).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.passes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    /**
+     * draws every pass, returning the total draw calls
+     */
+    pub fn draw(&mut self, world: &mut World) -> u32 {
+        self.passes
+            .iter_mut()
+            .filter_map(|pass| pass.renderer.draw(world))
+            .sum()
+    }
+
+    /**
+     * what the last `draw` sent to the gpu, summed over the passes that keep
+     * count
+     */
+    pub fn render_info(&self) -> RenderInfo {
+        self.passes
+            .iter()
+            .filter_map(|pass| pass.renderer.render_info())
+            .fold(RenderInfo::default(), |total, info| total + info)
+    }
+
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.passes.iter().position(|pass| pass.name == name)
+    }
+
+    fn insert_at(
+        &mut self,
+        existing: &str,
+        offset: usize,
+        name: &str,
+        renderer: impl Renderer + 'static,
+    ) -> QPResult<()> {
+        if self.has_pass(name) {
+            return Err(QPError::Generic(format!(
+                "render pass {} already exists",
+                name
+            )));
+        }
+        let Some(index) = self.index_of(existing) else {
+            return Err(QPError::Generic(format!(
+                "render pass {} doesn't exist",
+                existing
+            )));
+        };
+
+        // takes the order of its neighbour so later add_pass calls sort around it
+        let order = self.passes[index].order;
+        self.passes.insert(
+            index + offset,
+            RenderPass {
+                name: name.to_string(),
+                order,
+                renderer: Box::new(renderer),
+            },
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::qp_gfx::Viewport;
+    use std::{cell::RefCell, rc::Rc};
+
+    struct Recorder {
+        name: &'static str,
+        log: Rc<RefCell<Vec<&'static str>>>,
+    }
+
+    impl Renderer for Recorder {
+        fn draw(&mut self, _world: &mut World) -> Option<u32> {
+            self.log.borrow_mut().push(self.name);
+
+            Some(1)
+        }
+    }
+
+    #[test]
+    fn render_graph_runs_passes_in_order() {
+        let log = Rc::new(RefCell::new(vec![]));
+        let pass = |name| Recorder {
+            name,
+            log: log.clone(),
+        };
+
+        let mut graph = RenderGraph::new();
+        graph.add_pass("ui", RenderGraph::UI, pass("ui")).unwrap();
+        graph
+            .add_pass("world", RenderGraph::WORLD, pass("world"))
+            .unwrap();
+        graph
+            .add_pass("background", RenderGraph::BACKGROUND, pass("background"))
+            .unwrap();
+        graph
+            .add_pass("debug", RenderGraph::DEBUG, pass("debug"))
+            .unwrap();
+        graph
+            .add_pass("world_2", RenderGraph::WORLD, pass("world_2"))
+            .unwrap();
+        assert!(graph.add_pass("ui", 0, pass("ui")).is_err());
+
+        let mut world = World::new(Viewport::headless(800, 600, None), 0).unwrap();
+        assert_eq!(graph.draw(&mut world), 5);
+        assert_eq!(
+            *log.borrow(),
+            vec!["background", "world", "world_2", "debug", "ui"]
+        );
+
+        // a custom pass slotted in between the existing ones
+        log.borrow_mut().clear();
+        graph
+            .add_pass("particles", RenderGraph::PARTICLES, pass("particles"))
+            .unwrap();
+        graph
+            .insert_before("world", "parallax", pass("parallax"))
+            .unwrap();
+        graph.insert_after("ui", "cursor", pass("cursor")).unwrap();
+        assert!(graph.insert_after("missing", "nope", pass("nope")).is_err());
+
+        graph.draw(&mut world);
+        assert_eq!(
+            *log.borrow(),
+            vec![
+                "background",
+                "parallax",
+                "world",
+                "world_2",
+                "particles",
+                "debug",
+                "ui",
+                "cursor"
+            ]
+        );
+        assert_eq!(graph.pass_names(), *log.borrow());
+
+        assert!(graph.remove_pass("debug"));
+        assert!(!graph.remove_pass("debug"));
+        assert_eq!(graph.len(), 7);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    prelude::{
+        qp_assets::{RCamera2D, RShader},
+        qp_ecs::components::{CQuad, CSprite, CTransform2D},
+        qp_physics::obb_corners,
+        GlobalRegistry, QPError, Renderer, World,
+    },
+    QPResult,
+};
+
+use super::super::batch_renderer::{BatchRenderer, RenderInfo, DEFAULT_BATCH_SIZE};
+
+/**
+* outlines the oriented bounding box of every entity with a CQuad and a
+* CTransform2D, but only while `world.debug_mode` is on
+*/
+pub struct DebugRenderer {
+    camera: u64,
+    shader: u64,
+
+    pub color: glm::Vec4,
+    pub thickness: f32,
+
+    renderer: BatchRenderer<CSprite>,
+}
+
+impl DebugRenderer {
+    pub fn new(registry: &mut GlobalRegistry, camera: &str, shader: &str) -> QPResult<Self> {
+        let Some(camera) = registry.asset_manager.get_asset_id(camera) else {
+            return Err(QPError::CameraNotLoaded);
+        };
+
+        let Some(shader) = registry.asset_manager.get_asset_id(shader) else {
+            return Err(QPError::ShaderNotLoaded);
+        };
+
+        Ok(Self {
+            camera,
+            shader,
+            color: glm::vec4(0.0, 1.0, 0.0, 1.0),
+            thickness: 1.0,
+            renderer: BatchRenderer::new(DEFAULT_BATCH_SIZE),
+        })
+    }
+}
+
+impl Renderer for DebugRenderer {
+    fn draw(&mut self, world: &mut World) -> Option<u32> {
+        if !world.debug_mode {
+            return None;
+        }
+
+        let shader = world.registry.asset_manager.get::<RShader>(self.shader)?;
+        let camera = world.registry.asset_manager.get::<RCamera2D>(self.camera)?;
+
+        self.renderer.reset_info();
+        self.renderer.begin_batch();
+        for entity in world.registry.entity_manager.query_all::<CQuad>() {
+            let (Some(quad), Some(transform)) = (
+                world.registry.entity_manager.get::<CQuad>(&entity),
+                world.registry.entity_manager.get::<CTransform2D>(&entity),
+            ) else {
+                continue;
+            };
+
+            let corners = obb_corners(transform, glm::vec2(quad.width, quad.height) / 2.0);
+            for (i, from) in corners.iter().enumerate() {
+                let to = corners[(i + 1) % corners.len()];
+                let edge = to - from;
+
+                let mut line = CSprite::new(
+                    &CQuad {
+                        width: edge.magnitude(),
+                        height: self.thickness,
+                        center_x: 0.0,
+                        center_y: 0.0,
+                    },
+                    Some(self.color),
+                    None,
+                );
+                let model = CTransform2D {
+                    translate: from + edge / 2.0,
+                    rotate: edge.y.atan2(edge.x),
+                    ..CTransform2D::default()
+                }
+                .to_matrix();
+                line.apply_matrices(model, camera.view, camera.projection);
+
+                self.renderer.draw_mesh(&line, shader, None);
+            }
+        }
+        self.renderer.end_batch();
+        self.renderer.flush_batch(shader);
+
+        Some(self.renderer.info().draw_calls)
+    }
+
+    fn render_info(&self) -> Option<RenderInfo> {
+        Some(self.renderer.info())
+    }
+}
@@ -0,0 +1,441 @@
+use std::ops::Range;
+
+use field_offset::offset_of;
+
+use crate::{
+    platform::opengl::{
+        buffer::{
+            create_ebo, vertex_attribute_divisor, vertex_attribute_pointer, Buffer, BufferUsage,
+            VertexArray, EBO, VBO,
+        },
+        capabilities::{gl_blending_func, gl_enable, GLBlendingFactor, GLCapability},
+        draw::{gl_draw_instanced, DrawMode},
+        textures::use_texture,
+    },
+    prelude::{
+        qp_assets::{RCamera2D, RShader, RTexture},
+        qp_ecs::components::{CQuad, CScreenSprite, CSprite, CTag},
+        qp_ecs::EntityManager,
+        GlobalRegistry, QPError, Renderer, World,
+    },
+    QPResult,
+};
+
+use super::super::batch_renderer::{RenderInfo, DEFAULT_BATCH_SIZE};
+use super::sprite::sprite_model;
+
+/**
+* what changes between copies of the quad in an instanced draw
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteInstance {
+    pub model: glm::Mat4,
+    pub color: glm::Vec4,
+
+    /**
+     * the atlas cell, and one over the atlas dimensions
+     */
+    pub cell: glm::Vec2,
+    pub cell_scale: glm::Vec2,
+
+    /**
+     * 0.0 samples the bound texture, past the texture slots is untextured
+     */
+    pub tex_index: f32,
+}
+
+#[derive(Debug)]
+struct QuadVertex {
+    position: glm::Vec3,
+    tex_coords: glm::Vec2,
+}
+
+/**
+* draws every CSprite with a tag as copies of one quad, with a single draw
+* call per texture. much cheaper than batching for lots of identical
+* sprites, eg. a star field
+*
+* the shader should use SPRITE_INSTANCED_VERT. instanced sprites are plain
+* quads: their CShape and shader override are ignored. tell the
+* SpriteRenderer to skip the tag, see `SpriteRenderer::skip_tag`
+*/
+pub struct InstancedSpriteRenderer {
+    camera: u64,
+    shader: u64,
+    tag: String,
+
+    vao: VertexArray,
+    _ebo: Buffer<EBO>,
+    _quad: Buffer<VBO>,
+    instance_buffer: Buffer<VBO>,
+
+    max_instances: usize,
+    instances: Vec<(Option<u64>, SpriteInstance)>,
+    info: RenderInfo,
+}
+
+impl InstancedSpriteRenderer {
+    pub fn new(
+        registry: &mut GlobalRegistry,
+        camera: &str,
+        shader: &str,
+        tag: &str,
+    ) -> QPResult<Self> {
+        let Some(camera) = registry.asset_manager.get_asset_id(camera) else {
+            return Err(QPError::CameraNotLoaded);
+        };
+
+        let Some(shader) = registry.asset_manager.get_asset_id(shader) else {
+            return Err(QPError::ShaderNotLoaded);
+        };
+
+        // a unit quad, in the same corner order as CQuad
+        let quad = [(0.5, 0.5), (0.5, -0.5), (-0.5, -0.5), (-0.5, 0.5)].map(|(x, y)| QuadVertex {
+            position: glm::vec3(x, y, 0.0),
+            tex_coords: glm::vec2(x + 0.5, y + 0.5),
+        });
+        let indices = CQuad::indices().map(|index| index as u32);
+
+        let vao = VertexArray::new();
+        vao.bind();
+
+        let ebo = create_ebo(&indices, &BufferUsage::StaticDraw);
+        ebo.bind();
+
+        let quad_buffer = Buffer::<VBO>::new();
+        quad_buffer.bind();
+        quad_buffer.buffer_data::<QuadVertex>(quad.len(), Some(&quad), &BufferUsage::StaticDraw);
+
+        let stride = std::mem::size_of::<QuadVertex>();
+        vertex_attribute_pointer(
+            0,
+            3,
+            stride,
+            offset_of!(QuadVertex => position).get_byte_offset(),
+        );
+        vertex_attribute_pointer(
+            1,
+            2,
+            stride,
+            offset_of!(QuadVertex => tex_coords).get_byte_offset(),
+        );
+
+        let max_instances = DEFAULT_BATCH_SIZE;
+        let instance_buffer = Buffer::<VBO>::new();
+        instance_buffer.bind();
+        instance_buffer.buffer_data::<SpriteInstance>(
+            max_instances,
+            None,
+            &BufferUsage::DynamicDraw,
+        );
+
+        let stride = std::mem::size_of::<SpriteInstance>();
+        let model = offset_of!(SpriteInstance => model).get_byte_offset();
+        let color = offset_of!(SpriteInstance => color).get_byte_offset();
+        let cell = offset_of!(SpriteInstance => cell).get_byte_offset();
+        let cell_scale = offset_of!(SpriteInstance => cell_scale).get_byte_offset();
+        let tex_index = offset_of!(SpriteInstance => tex_index).get_byte_offset();
+
+        // the model matrix takes a location per column
+        let column = std::mem::size_of::<glm::Vec4>();
+        let attributes = [
+            (2, 4, model),
+            (3, 4, model + column),
+            (4, 4, model + column * 2),
+            (5, 4, model + column * 3),
+            (6, 4, color),
+            (7, 2, cell),
+            (8, 2, cell_scale),
+            (9, 1, tex_index),
+        ];
+        for (location, size, offset) in attributes {
+            vertex_attribute_pointer(location, size, stride, offset);
+            vertex_attribute_divisor(location, 1);
+        }
+
+        vao.unbind();
+        ebo.unbind();
+        instance_buffer.unbind();
+
+        Ok(Self {
+            camera,
+            shader,
+            tag: tag.to_string(),
+
+            vao,
+            _ebo: ebo,
+            _quad: quad_buffer,
+            instance_buffer,
+
+            max_instances,
+            instances: Vec::with_capacity(max_instances),
+            info: RenderInfo::default(),
+        })
+    }
+
+    /**
+     * draws the sprites tagged `tag`, returning what was drawn. None if the
+     * camera or shader isn't loaded
+     */
+    pub fn instance_render(
+        &mut self,
+        tag: &str,
+        registry: &mut GlobalRegistry,
+    ) -> Option<RenderInfo> {
+        self.info = RenderInfo::default();
+
+        let Some(shader) = registry.asset_manager.get::<RShader>(self.shader) else {
+            #[cfg(debug_assertions)]
+            println!("[instanced sprites] tried to use a shader that is not loaded");
+
+            return None;
+        };
+
+        let Some(camera) = registry.asset_manager.get::<RCamera2D>(self.camera) else {
+            #[cfg(debug_assertions)]
+            println!("[instanced sprites] tried to use a camera that is not loaded");
+
+            return None;
+        };
+
+        collect_instances(
+            &mut registry.entity_manager,
+            tag,
+            &camera.transform.translate,
+            &mut self.instances,
+        );
+        group_by_texture(&mut self.instances, |(texture, _)| *texture);
+
+        gl_enable(GLCapability::AlphaBlending);
+        gl_blending_func(
+            GLBlendingFactor::SrcAlpha,
+            GLBlendingFactor::OneMinusSrcAlpha,
+        );
+
+        shader.program.use_program();
+        shader.program.set_mat4("view", &camera.view);
+        shader.program.set_mat4("projection", &camera.projection);
+        shader.program.set_int("u_textures[0]", 0);
+
+        for (texture, range) in instance_draws(&self.instances, self.max_instances) {
+            let texture =
+                texture.and_then(|texture| registry.asset_manager.get::<RTexture>(texture));
+            if let Some(texture) = texture {
+                use_texture(texture.texture.id, 0);
+            }
+
+            let count = range.len();
+            let data = self.instances[range]
+                .iter()
+                .map(|(_, instance)| instance.clone())
+                .collect::<Vec<_>>();
+
+            self.instance_buffer.bind();
+            self.instance_buffer
+                .buffer_sub_data::<SpriteInstance>(0, count, Some(&data));
+            self.instance_buffer.unbind();
+
+            self.vao.bind();
+            gl_draw_instanced(
+                DrawMode::Triangles,
+                CQuad::indices().len() as i32,
+                count as i32,
+            );
+            self.vao.unbind();
+
+            self.info.record_instances(count, texture.is_some());
+        }
+
+        Some(self.info)
+    }
+}
+
+impl Renderer for InstancedSpriteRenderer {
+    fn draw(&mut self, world: &mut World) -> Option<u32> {
+        let tag = std::mem::take(&mut self.tag);
+        let info = self.instance_render(&tag, &mut world.registry);
+        self.tag = tag;
+
+        info.map(|info| info.draw_calls)
+    }
+
+    fn render_info(&self) -> Option<RenderInfo> {
+        Some(self.info)
+    }
+}
+
+/**
+* replaces `instances` with the visible sprites tagged `tag`, and the
+* texture each one is drawn with
+*/
+fn collect_instances(
+    em: &mut EntityManager,
+    tag: &str,
+    camera_translate: &glm::Vec2,
+    instances: &mut Vec<(Option<u64>, SpriteInstance)>,
+) {
+    instances.clear();
+
+    let entities = em.query(CTag {
+        tag: tag.to_string(),
+    });
+    for entity in entities.iter() {
+        match em.get::<CSprite>(entity) {
+            Some(sprite) if !sprite.skip => (),
+            _ => continue,
+        }
+        if em.get::<CScreenSprite>(entity).is_some() {
+            continue;
+        }
+
+        let Some(model) = sprite_model(em, entity, camera_translate) else {
+            continue;
+        };
+        let Some(sprite) = em.get::<CSprite>(entity) else {
+            continue;
+        };
+
+        let (center, size) = sprite.bounds();
+        let model = glm::scale(
+            &glm::translate(&model, &glm::vec3(center.x, center.y, 0.0)),
+            &glm::vec3(size.x, size.y, 1.0),
+        );
+
+        let atlas = sprite.texture_atlas.as_ref();
+        let instance = SpriteInstance {
+            model,
+            color: sprite.color,
+            cell: atlas.map_or(glm::vec2(0.0, 0.0), |atlas| atlas.active_texture),
+            cell_scale: atlas.map_or(glm::vec2(1.0, 1.0), |atlas| {
+                glm::vec2(1.0 / atlas.texture_dims.x, 1.0 / atlas.texture_dims.y)
+            }),
+            tex_index: match atlas {
+                Some(_) => 0.0,
+                None => 32.0,
+            },
+        };
+
+        instances.push((atlas.map(|atlas| atlas.texture), instance));
+    }
+}
+
+/**
+* stable sort so instances sharing a texture are drawn back to back. an
+* instanced draw uses one texture, so unlike batched sprites, instances can't
+* keep their draw order across textures
+*/
+fn group_by_texture<T>(items: &mut [T], texture: impl Fn(&T) -> Option<u64>) {
+    items.sort_by_key(|item| texture(item));
+}
+
+/**
+* splits texture-grouped instances into draw calls of at most `max_instances`
+* sharing a texture
+*/
+fn instance_draws<T>(
+    instances: &[(Option<u64>, T)],
+    max_instances: usize,
+) -> Vec<(Option<u64>, Range<usize>)> {
+    let mut draws = vec![];
+
+    let mut start = 0;
+    for run in instances.chunk_by(|(a, _), (b, _)| a == b) {
+        for chunk in (0..run.len()).step_by(max_instances.max(1)) {
+            let end = (chunk + max_instances.max(1)).min(run.len());
+            draws.push((run[0].0, start + chunk..start + end));
+        }
+        start += run.len();
+    }
+
+    draws
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{qp_ecs::components::CTransform2D, EntityBuilder};
+    use crate::schemas::sprite::TextureAtlas;
+    use crate::test_utils::registry;
+
+    #[test]
+    fn instanced_sprites_draw_in_one_call() {
+        let mut registry = registry();
+
+        let quad = CQuad {
+            width: 4.0,
+            height: 2.0,
+            ..CQuad::default()
+        };
+        let atlas = TextureAtlas {
+            texture: 9,
+            texture_dims: glm::vec2(8.0, 4.0),
+            active_texture: glm::vec2(7.0, 2.0),
+        };
+        let mut spawn = |tag: &str, x: f32| {
+            EntityBuilder::create(&mut registry.entity_manager)
+                .with(CTag {
+                    tag: tag.to_string(),
+                })
+                .with(CTransform2D {
+                    translate: glm::vec2(x, 0.0),
+                    ..CTransform2D::default()
+                })
+                .with(CSprite::new(&quad, None, Some(atlas.clone())))
+                .build()
+        };
+        for i in 0..500 {
+            spawn("star", i as f32);
+        }
+        spawn("ship", 0.0);
+
+        let mut instances = vec![];
+        let camera = glm::vec2(0.0, 0.0);
+        collect_instances(
+            &mut registry.entity_manager,
+            "star",
+            &camera,
+            &mut instances,
+        );
+        group_by_texture(&mut instances, |(texture, _)| *texture);
+
+        let mut info = RenderInfo::default();
+        let draws = instance_draws(&instances, DEFAULT_BATCH_SIZE);
+        for (texture, range) in draws.iter() {
+            info.record_instances(range.len(), texture.is_some());
+        }
+
+        assert_eq!(draws, vec![(Some(9), 0..500)]);
+        assert_eq!(info.draw_calls, 1);
+        assert_eq!(info.instances, 500);
+        assert_eq!(info.instances_per_draw(), 500.0);
+
+        // the unit quad is scaled to the sprite and its atlas cell is passed along
+        let (_, instance) = instances
+            .iter()
+            .find(|(_, instance)| instance.model[(0, 3)] == 3.0)
+            .unwrap();
+        let corner = instance.model * glm::vec4(0.5, 0.5, 0.0, 1.0);
+        assert_eq!(corner.xy(), glm::vec2(5.0, 1.0));
+        assert_eq!(instance.cell, glm::vec2(7.0, 2.0));
+        assert_eq!(instance.cell_scale, glm::vec2(0.125, 0.25));
+
+        // past the buffer's size it takes more calls
+        let draws = instance_draws(&instances, 200);
+        let sizes = draws
+            .iter()
+            .map(|(_, range)| range.len())
+            .collect::<Vec<_>>();
+        assert_eq!(sizes, vec![200, 200, 100]);
+    }
+
+    #[test]
+    fn instanced_draws_split_by_texture() {
+        let mut instances = [Some(2), None, Some(1), Some(2), None].map(|texture| (texture, ()));
+        group_by_texture(&mut instances, |(texture, _)| *texture);
+
+        assert_eq!(
+            instance_draws(&instances, 100),
+            vec![(None, 0..2), (Some(1), 2..3), (Some(2), 3..5)]
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use crate::prelude::{
+    qp_gfx::{begin_stencil_mask, draw_masked, end_stencil_mask, StencilMode},
+    Renderer, World,
+};
+
+/**
+* draws `content` clipped to whatever `mask` draws, eg. a circular minimap
+* or a spotlight reveal. what mask draws only shapes the stencil, it isn't
+* shown
+*/
+pub struct MaskedRenderer<M: Renderer, C: Renderer> {
+    pub mask: M,
+    pub content: C,
+    pub mode: StencilMode,
+}
+
+impl<M: Renderer, C: Renderer> MaskedRenderer<M, C> {
+    pub fn new(mask: M, content: C, mode: StencilMode) -> Self {
+        Self {
+            mask,
+            content,
+            mode,
+        }
+    }
+}
+
+impl<M: Renderer, C: Renderer> Renderer for MaskedRenderer<M, C> {
+    fn draw(&mut self, world: &mut World) -> Option<u32> {
+        begin_stencil_mask();
+        let mask_calls = self.mask.draw(world);
+
+        draw_masked(self.mode);
+        let content_calls = self.content.draw(world);
+        end_stencil_mask();
+
+        match (mask_calls, content_calls) {
+            (None, None) => None,
+            (mask, content) => Some(mask.unwrap_or(0) + content.unwrap_or(0)),
+        }
+    }
+}
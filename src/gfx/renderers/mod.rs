@@ -1,5 +1,13 @@
+mod debug;
+mod instanced;
+mod masked;
+mod screen_sprite;
 mod sprite;
 mod text;
 
+pub use debug::DebugRenderer;
+pub use instanced::{InstancedSpriteRenderer, SpriteInstance};
+pub use masked::MaskedRenderer;
+pub use screen_sprite::ScreenSpriteRenderer;
 pub use sprite::SpriteRenderer;
 pub use text::*;
\ No newline at end of file
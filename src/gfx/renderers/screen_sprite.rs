@@ -0,0 +1,138 @@
+use crate::{
+    platform::opengl::capabilities::{gl_blending_func, gl_enable, GLBlendingFactor, GLCapability},
+    prelude::{
+        qp_assets::RShader,
+        qp_ecs::components::{CScreenSprite, CShape, CSprite, CTransform2D},
+        qp_gfx::Viewport,
+        GlobalRegistry, QPError, Renderer, World,
+    },
+    QPResult,
+};
+
+use super::super::batch_renderer::{BatchRenderer, RenderInfo, DEFAULT_BATCH_SIZE};
+use super::sprite::draw_layer;
+
+/**
+* draws CSprites marked with CScreenSprite in pixel coordinates, ignoring the
+* world camera. sprites are drawn in CDrawLayer order
+*
+* register it after the world renderers so HUD sprites are drawn on top
+*/
+pub struct ScreenSpriteRenderer {
+    shader: u64,
+
+    renderer: BatchRenderer<CSprite>,
+}
+
+impl ScreenSpriteRenderer {
+    pub fn new(registry: &mut GlobalRegistry, shader: &str) -> QPResult<Self> {
+        let Some(shader) = registry.asset_manager.get_asset_id(shader) else {
+            return Err(QPError::ShaderNotLoaded);
+        };
+
+        Ok(Self {
+            shader,
+            renderer: BatchRenderer::new(DEFAULT_BATCH_SIZE),
+        })
+    }
+}
+
+impl Renderer for ScreenSpriteRenderer {
+    fn draw(&mut self, world: &mut World) -> Option<u32> {
+        let mut entities = world.registry.entity_manager.query_all::<CScreenSprite>();
+        if entities.is_empty() {
+            return None;
+        }
+
+        // stable, so HUD sprites on the same layer stack in the order they were made
+        entities.sort_by_key(|entity| draw_layer(&world.registry.entity_manager, entity));
+
+        gl_enable(GLCapability::AlphaBlending);
+        gl_blending_func(
+            GLBlendingFactor::SrcAlpha,
+            GLBlendingFactor::OneMinusSrcAlpha,
+        );
+
+        let shader = world.registry.asset_manager.get::<RShader>(self.shader)?;
+
+        self.renderer.reset_info();
+        self.renderer.begin_batch();
+        for entity in entities.iter() {
+            let Some(transform) = world.registry.entity_manager.get::<CTransform2D>(entity).copied() else {
+                continue;
+            };
+            let shape = world.registry.entity_manager.get::<CShape>(entity).copied();
+            let Some(sprite) = world.registry.entity_manager.get_mut::<CSprite>(entity) else {
+                continue;
+            };
+
+            if sprite.skip {
+                continue;
+            }
+
+            apply_screen_matrices(sprite, &transform, &world.viewport);
+
+            let texture = sprite
+                .texture_atlas
+                .as_ref()
+                .and_then(|atlas| world.registry.asset_manager.get(atlas.texture));
+            match shape {
+                Some(shape) => {
+                    let vertices = sprite.shaped_vertices(&shape);
+                    self.renderer.draw_vertices(vertices, shader, texture)
+                }
+                None => self.renderer.draw_mesh(sprite, shader, texture),
+            }
+        }
+        self.renderer.end_batch();
+        self.renderer.flush_batch(shader);
+
+        Some(self.renderer.info().draw_calls)
+    }
+
+    fn render_info(&self) -> Option<RenderInfo> {
+        Some(self.renderer.info())
+    }
+}
+
+fn apply_screen_matrices(sprite: &mut CSprite, transform: &CTransform2D, viewport: &Viewport) {
+    sprite.apply_matrices(
+        transform.to_matrix(),
+        glm::Mat4::identity(),
+        viewport.screen_projection(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{qp_ecs::components::CQuad, qp_gfx::Mesh};
+
+    #[test]
+    fn screen_sprite_vertices_are_in_pixels() {
+        let viewport = Viewport::headless(800, 600, None);
+        let quad = CQuad {
+            width: 20.0,
+            height: 10.0,
+            center_x: 0.0,
+            center_y: 0.0,
+        };
+
+        let mut sprite = CSprite::new(&quad, None, None);
+        apply_screen_matrices(
+            &mut sprite,
+            &CTransform2D {
+                translate: glm::vec2(760.0, 580.0),
+                ..CTransform2D::default()
+            },
+            &viewport,
+        );
+
+        // back from clip space to window pixels
+        let to_pixels = |clip: glm::Vec3| glm::vec2((clip.x + 1.0) * 400.0, (clip.y + 1.0) * 300.0);
+
+        let vertices = sprite.vertices();
+        assert!(glm::distance(&to_pixels(vertices[0].position), &glm::vec2(770.0, 585.0)) < 0.01);
+        assert!(glm::distance(&to_pixels(vertices[2].position), &glm::vec2(750.0, 575.0)) < 0.01);
+    }
+}
@@ -2,23 +2,44 @@ use crate::{
     platform::opengl::capabilities::{gl_blending_func, gl_enable, GLBlendingFactor, GLCapability},
     prelude::{
         qp_assets::{RCamera2D, RShader},
-        qp_ecs::components::{CSprite, CTransform2D},
-        GlobalRegistry, QPError, Renderer, World,
+        qp_ecs::components::{
+            CDrawLayer, CLayerOffset, CModelMatrix2D, CMotionBlur, CParallaxDepth, CScreenSprite,
+            CShape, CSprite, CTag, CVelocity2D,
+        },
+        qp_ecs::EntityManager,
+        qp_physics::world_transform,
+        GlobalRegistry, QPError, Renderer, VersionedIndex, World,
     },
     QPResult,
 };
 
-use super::super::batch_renderer::BatchRenderer;
+use super::super::batch_renderer::{BatchOrder, BatchRenderer, RenderInfo, DEFAULT_BATCH_SIZE};
 
 pub struct SpriteRenderer {
     camera: u64,
     shader: u64,
 
-    renderer: BatchRenderer<10000, CSprite>,
+    renderer: BatchRenderer<CSprite>,
+    order: BatchOrder<(i32, u64, VersionedIndex)>,
+
+    // drawn by another renderer, see `skip_tag`
+    skipped_tags: Vec<String>,
 }
 
 impl SpriteRenderer {
     pub fn new(registry: &mut GlobalRegistry, camera: &str, shader: &str) -> QPResult<Self> {
+        Self::with_batch_size(registry, camera, shader, DEFAULT_BATCH_SIZE)
+    }
+
+    /**
+     * sprites past `max_batch_size` in a frame are drawn in extra batches
+     */
+    pub fn with_batch_size(
+        registry: &mut GlobalRegistry,
+        camera: &str,
+        shader: &str,
+        max_batch_size: usize,
+    ) -> QPResult<Self> {
         let Some(camera) = registry.asset_manager.get_asset_id(camera) else {
             return Err(QPError::CameraNotLoaded);
         };
@@ -30,15 +51,45 @@ impl SpriteRenderer {
         Ok(Self {
             camera,
             shader,
-            renderer: BatchRenderer::new(),
+            renderer: BatchRenderer::new(max_batch_size),
+            order: BatchOrder::default(),
+            skipped_tags: vec![],
         })
     }
+
+    /**
+     * a renderer with a headless batch renderer, for tests that run without a
+     * context
+     */
+    #[cfg(test)]
+    fn headless(camera: u64, shader: u64) -> Self {
+        Self {
+            camera,
+            shader,
+            renderer: BatchRenderer::headless(DEFAULT_BATCH_SIZE, 16),
+            order: BatchOrder::default(),
+            skipped_tags: vec![],
+        }
+    }
+
+    /**
+     * leaves sprites with the tag to another renderer, eg. an
+     * InstancedSpriteRenderer drawing the same tag
+     */
+    pub fn skip_tag(&mut self, tag: &str) {
+        self.skipped_tags.push(tag.to_string());
+    }
+
+    /**
+     * see `BatchRenderer::set_texture_slots`
+     */
+    pub fn set_texture_slots(&mut self, slots: usize) {
+        self.renderer.set_texture_slots(slots);
+    }
 }
 
 impl Renderer for SpriteRenderer {
     fn draw(&mut self, world: &mut World) -> Option<u32> {
-        let entities = world.registry.entity_manager.query_all::<CSprite>();
-
         gl_enable(GLCapability::AlphaBlending);
         gl_blending_func(
             GLBlendingFactor::SrcAlpha,
@@ -57,17 +108,64 @@ impl Renderer for SpriteRenderer {
             return None;
         };
 
-        let Some(camera) = world.registry.asset_manager.get::<RCamera2D>(self.camera) else {
+        let assets = &world.registry.asset_manager;
+        let Some(camera) = assets.get::<RCamera2D>(self.camera) else {
             #[cfg(debug_assertions)]
             println!("[sprite controller] tried to use a camera that is not loaded");
 
             return None;
         };
 
+        self.queue_sprites(&mut world.registry.entity_manager, camera, |shader| {
+            assets.get::<RShader>(shader).is_some()
+        });
+
         self.renderer.reset_info();
         self.renderer.begin_batch();
+        for (shader, run) in shader_runs(self.order.sorted()) {
+            let shader = world.registry.asset_manager.get(shader)?;
+
+            for ((_, _, entity), texture) in run {
+                let Some(sprite) = world.registry.entity_manager.get::<CSprite>(entity) else {
+                    continue;
+                };
+                let texture =
+                    texture.and_then(|texture| world.registry.asset_manager.get(texture));
+
+                match world.registry.entity_manager.get::<CShape>(entity) {
+                    Some(shape) => {
+                        let vertices = sprite.shaped_vertices(shape);
+                        self.renderer.draw_vertices(vertices, shader, texture)
+                    }
+                    None => self.renderer.draw_mesh(sprite, shader, texture),
+                }
+            }
+
+            self.renderer.batch_reset(shader);
+        }
+
+        Some(self.renderer.info().draw_calls)
+    }
+
+    fn render_info(&self) -> Option<RenderInfo> {
+        Some(self.renderer.info())
+    }
+}
+
+impl SpriteRenderer {
+    // works out this frame's draw order, without touching gl. `loaded` says
+    // whether a shader override can be used
+    fn queue_sprites(
+        &mut self,
+        em: &mut EntityManager,
+        camera: &RCamera2D,
+        loaded: impl Fn(u64) -> bool,
+    ) {
+        let entities = em.query_all::<CSprite>();
+
+        let mut batch = Vec::with_capacity(entities.len());
         for entity in entities.iter() {
-            let Some(sprite) = world.registry.entity_manager.get::<CSprite>(&entity) else {
+            let Some(sprite) = em.get::<CSprite>(entity) else {
                 #[cfg(debug_assertions)]
                 println!("[sprite controller] tried to render a sprite without a sprite component");
 
@@ -78,7 +176,20 @@ impl Renderer for SpriteRenderer {
                 continue;
             }
 
-            let Some(transform) = world.registry.entity_manager.get::<CTransform2D>(&entity) else {
+            // drawn by the ScreenSpriteRenderer
+            if em.get::<CScreenSprite>(entity).is_some() {
+                continue;
+            }
+
+            if !self.skipped_tags.is_empty()
+                && em
+                    .get::<CTag>(entity)
+                    .is_some_and(|tag| self.skipped_tags.contains(&tag.tag))
+            {
+                continue;
+            }
+
+            let Some(model) = sprite_model(em, entity, &camera.transform.translate) else {
                 #[cfg(debug_assertions)]
                 println!(
                     "[sprite controller] tried to render a sprite without a tranform component"
@@ -86,33 +197,267 @@ impl Renderer for SpriteRenderer {
 
                 continue;
             };
-            let model = transform.to_matrix();
+            let layer = draw_layer(em, entity);
 
-            let Some(sprite) = world.registry.entity_manager.get_mut::<CSprite>(&entity) else {
+            let Some(sprite) = em.get_mut::<CSprite>(entity) else {
                 continue;
             };
             sprite.apply_matrices(model, camera.view, camera.projection);
 
-            let sprite = world
-                .registry
-                .entity_manager
-                .get::<CSprite>(&entity)
-                .unwrap();
-            let texture = match &sprite.texture_atlas {
-                Some(atlas) => world.registry.asset_manager.get(atlas.texture),
-                _ => None,
-            };
+            let texture = sprite.texture_atlas.as_ref().map(|atlas| atlas.texture);
 
-            self.renderer.draw_mesh(
-                sprite,
-                world.registry.asset_manager.get(self.shader)?,
-                texture,
-            );
+            // an override that isn't loaded falls back to the renderer's shader
+            let shader = sprite
+                .shader
+                .filter(|shader| loaded(*shader))
+                .unwrap_or(self.shader);
+            batch.push(((layer, shader, *entity), texture));
         }
-        self.renderer.end_batch();
-        self.renderer
-            .flush_batch(world.registry.asset_manager.get(self.shader)?);
 
-        Some(self.renderer.draw_calls)
+        // draw back to front by layer. within a layer the sprites keep the
+        // order they came in, whatever their shader or texture
+        self.order.update_layered(batch, |(layer, _, _)| *layer);
+    }
+}
+
+type SpriteKey = ((i32, u64, VersionedIndex), Option<u64>);
+
+/**
+* splits the sorted sprites wherever the shader changes from one sprite to
+* the next. each run is drawn in its own batches with that shader bound
+*/
+fn shader_runs(sorted: &[SpriteKey]) -> impl Iterator<Item = (u64, &[SpriteKey])> {
+    sorted
+        .chunk_by(|((_, a, _), _), ((_, b, _), _)| a == b)
+        .map(|run| (run[0].0 .1, run))
+}
+
+/**
+* the sprite's CDrawLayer, falling back to its CLayerOffset's layer, or 0
+*/
+pub(super) fn draw_layer(em: &EntityManager, entity: &VersionedIndex) -> i32 {
+    em.get::<CDrawLayer>(entity)
+        .map(|layer| layer.0)
+        .or_else(|| em.get::<CLayerOffset>(entity).map(|offset| offset.layer))
+        .unwrap_or_default()
+}
+
+/**
+* the sprite's model matrix. sprites in a hierarchy draw at
+* their CWorldTransform2D, everything else at its CTransform2D, see
+* `world_transform`. layer offsets and parallax are applied on top
+*/
+pub(super) fn sprite_model(
+    em: &mut EntityManager,
+    entity: &VersionedIndex,
+    camera_translate: &glm::Vec2,
+) -> Option<glm::Mat4> {
+    let mut transform = *world_transform(em, entity)?;
+
+    if let Some(offset) = em.get::<CLayerOffset>(entity) {
+        transform.translate = offset.apply(&transform.translate, camera_translate);
+    } else if let Some(depth) = em.get::<CParallaxDepth>(entity) {
+        transform.translate = depth.apply(&transform.translate, camera_translate);
+    }
+
+    let model = match (em.get::<CMotionBlur>(entity), em.get::<CVelocity2D>(entity)) {
+        (Some(blur), Some(velocity)) => blur.model(&transform, velocity),
+        _ => match em.get_mut::<CModelMatrix2D>(entity) {
+            Some(cache) => cache.model_matrix(&transform),
+            None => transform.model_matrix(),
+        },
+    };
+
+    Some(model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{
+        qp_assets::camera::OrthographicCameraParams,
+        qp_ecs::components::{
+            CChildren, CQuad, CTransform2D, CTransformDirty, CWorldTransform2D,
+        },
+        qp_physics::s_propagate_transforms,
+        EntityBuilder,
+    };
+    use crate::schemas::sprite::TextureAtlas;
+    use crate::test_utils::registry;
+
+    fn camera() -> RCamera2D {
+        RCamera2D::new(
+            OrthographicCameraParams::default(),
+            1.0,
+            CTransform2D::default(),
+        )
+    }
+
+    // the entities of each run of the renderer's draw order, with its shader
+    fn runs(renderer: &SpriteRenderer) -> Vec<(u64, Vec<VersionedIndex>)> {
+        shader_runs(renderer.order.sorted())
+            .map(|(shader, run)| {
+                let entities = run.iter().map(|((_, _, entity), _)| *entity).collect();
+
+                (shader, entities)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sprite_renderer_batches_shader_overrides() {
+        let mut registry = registry();
+
+        let (default_shader, dissolve, missing) = (1, 2, 3);
+        let entities = [None, Some(dissolve), None, Some(dissolve), Some(missing)]
+            .iter()
+            .enumerate()
+            .map(|(i, shader)| {
+                let atlas = TextureAtlas {
+                    texture: 10 - i as u64,
+                    texture_dims: glm::vec2(1.0, 1.0),
+                    active_texture: glm::vec2(0.0, 0.0),
+                };
+                let mut sprite = CSprite::new(&CQuad::default(), None, Some(atlas));
+                if let Some(shader) = shader {
+                    sprite = sprite.with_shader(*shader);
+                }
+
+                EntityBuilder::create(&mut registry.entity_manager)
+                    .with(CTransform2D::default())
+                    .with(sprite)
+                    .build()
+            })
+            .collect::<Vec<_>>();
+
+        let mut renderer = SpriteRenderer::headless(0, default_shader);
+        renderer.queue_sprites(&mut registry.entity_manager, &camera(), |shader| {
+            shader != missing
+        });
+
+        // an override only splits the batch where it changes, so the sprites
+        // still draw in the order they were made. the missing override falls
+        // back to the default shader
+        assert_eq!(
+            runs(&renderer),
+            vec![
+                (default_shader, vec![entities[0]]),
+                (dissolve, vec![entities[1]]),
+                (default_shader, vec![entities[2]]),
+                (dissolve, vec![entities[3]]),
+                (default_shader, vec![entities[4]]),
+            ]
+        );
+
+        // next to each other, overrides share a run
+        let em = &mut registry.entity_manager;
+        em.get_mut::<CSprite>(&entities[2]).unwrap().shader = Some(dissolve);
+        renderer.queue_sprites(em, &camera(), |_| true);
+        assert_eq!(
+            runs(&renderer),
+            vec![
+                (default_shader, vec![entities[0]]),
+                (dissolve, vec![entities[1], entities[2], entities[3]]),
+                (missing, vec![entities[4]]),
+            ]
+        );
+    }
+
+    #[test]
+    fn sprite_renderer_orders_by_draw_layer() {
+        let mut registry = registry();
+
+        let mut spawn = |layer: Option<i32>, texture: u64| {
+            let atlas = TextureAtlas {
+                texture,
+                texture_dims: glm::vec2(1.0, 1.0),
+                active_texture: glm::vec2(0.0, 0.0),
+            };
+            let mut builder = EntityBuilder::create(&mut registry.entity_manager)
+                .with(CTransform2D::default())
+                .with(CSprite::new(&CQuad::default(), None, Some(atlas)));
+            if let Some(layer) = layer {
+                builder = builder.with(CDrawLayer(layer));
+            }
+            builder.build()
+        };
+        let entities = [
+            spawn(Some(2), 5),
+            spawn(Some(0), 9),
+            spawn(Some(1), 5),
+            spawn(None, 3),
+            spawn(Some(0), 9),
+            spawn(Some(2), 1),
+        ];
+
+        let mut renderer = SpriteRenderer::headless(0, 1);
+        renderer.queue_sprites(&mut registry.entity_manager, &camera(), |_| true);
+
+        let drawn = renderer
+            .order
+            .sorted()
+            .iter()
+            .map(|((layer, _, entity), _)| (*layer, *entity))
+            .collect::<Vec<_>>();
+
+        // no layer is layer 0. within a layer the sprites keep the order
+        // they were made in, whatever their texture
+        assert_eq!(
+            drawn,
+            vec![
+                (0, entities[1]),
+                (0, entities[3]),
+                (0, entities[4]),
+                (1, entities[2]),
+                (2, entities[0]),
+                (2, entities[5]),
+            ]
+        );
+    }
+
+    #[test]
+    fn sprite_renderer_draws_children_at_world_position() {
+        let mut registry = registry();
+
+        let quad = CQuad {
+            width: 10.0,
+            height: 10.0,
+            ..CQuad::default()
+        };
+        let child = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D {
+                translate: glm::vec2(20.0, 0.0),
+                ..CTransform2D::default()
+            })
+            .with(CWorldTransform2D::default())
+            .with(CSprite::new(&quad, None, None))
+            .build();
+        let child_id = registry.entity_manager.entity_id(&child).unwrap();
+        EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D {
+                translate: glm::vec2(100.0, 50.0),
+                rotate: glm::half_pi(),
+                ..CTransform2D::default()
+            })
+            .with(CWorldTransform2D::default())
+            .with(CTransformDirty)
+            .with(CChildren {
+                list: vec![child_id],
+            })
+            .build();
+        s_propagate_transforms(&mut registry);
+
+        let camera = glm::vec2(0.0, 0.0);
+        let model = sprite_model(&mut registry.entity_manager, &child, &camera).unwrap();
+
+        // rotated a quarter turn by the parent, not at its local offset
+        let center = model * glm::vec4(0.0, 0.0, 0.0, 1.0);
+        assert!(glm::distance(&center.xy(), &glm::vec2(100.0, 70.0)) < 1e-3);
+
+        // without a hierarchy it falls back to the local transform
+        registry.entity_manager.remove::<CWorldTransform2D>(&child);
+        let model = sprite_model(&mut registry.entity_manager, &child, &camera).unwrap();
+        let center = model * glm::vec4(0.0, 0.0, 0.0, 1.0);
+        assert!(glm::distance(&center.xy(), &glm::vec2(20.0, 0.0)) < 1e-3);
     }
 }
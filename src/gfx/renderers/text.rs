@@ -1,9 +1,15 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+
 use crate::{
+    asset_manager::{assets::font::resolve_glyph, AssetManager},
     gfx::batch_renderer::{Mesh, Vertex},
     platform::opengl::capabilities::*,
     prelude::{
         qp_assets::{RFont, RShader},
-        qp_gfx::BatchRenderer,
+        qp_gfx::{BatchRenderer, RenderInfo, DEFAULT_BATCH_SIZE},
         Renderer, World,
     },
     QPResult,
@@ -12,7 +18,8 @@ use crate::{
 pub struct TextRenderer {
     shader: RShader,
 
-    renderer: BatchRenderer<10000, CharacterMesh>,
+    renderer: BatchRenderer<CharacterMesh>,
+    layouts: TextLayoutCache,
 }
 
 impl TextRenderer {
@@ -21,7 +28,8 @@ impl TextRenderer {
 
         Ok(Self {
             shader,
-            renderer: BatchRenderer::new(),
+            renderer: BatchRenderer::new(DEFAULT_BATCH_SIZE),
+            layouts: TextLayoutCache::default(),
         })
     }
 }
@@ -34,55 +42,58 @@ impl Renderer for TextRenderer {
             GLBlendingFactor::OneMinusSrcAlpha,
         );
 
-        let (_x, _y, width, height) = world.viewport.get_dimensions();
-
-        let projection = &glm::ortho(0.0, width as f32, 0.0, height as f32, 0.0, 0.2);
+        let projection = &world.viewport.screen_projection();
 
         self.renderer.reset_info();
         self.renderer.begin_batch();
-        for text_obj in world.text_buffer.iter_mut() {
-            let Some(font) = world
-                .registry
-                .asset_manager
-                .get::<RFont>(text_obj.style.font)
-            else {
-                #[cfg(debug_assertions)]
-                {
-                    println!("font is not loaded");
-                }
-
-                continue;
-            };
-
-            for c in text_obj.text.chars() {
-                let Some(ch) = font.characters.get(c as usize) else {
-                    continue;
-                };
+        let asset_manager = &world.registry.asset_manager;
+        let metrics = |style: &QPTextStyle, c: char| glyph_metrics(asset_manager, style, c);
 
-                let x_pos = text_obj.pos.x + ch.bearing.x * text_obj.style.scale;
-                let y_pos = text_obj.pos.y - (ch.size.y - ch.bearing.y) * text_obj.style.scale;
+        for text_obj in world.text_buffer.iter() {
+            // a font that isn't loaded yet would be cached as empty space
+            let uncached;
+            let glyphs = match text_obj
+                .runs()
+                .all(|(_, style)| asset_manager.get::<RFont>(style.font).is_some())
+            {
+                true => self.layouts.get_or_layout(text_obj, metrics),
+                false => {
+                    #[cfg(debug_assertions)]
+                    {
+                        println!("font is not loaded");
+                    }
 
-                let w = ch.size.x * text_obj.style.scale;
-                let h = ch.size.y * text_obj.style.scale;
+                    uncached = layout_glyphs(text_obj, metrics);
+                    &uncached
+                }
+            };
 
+            for glyph in glyphs {
+                let pos = text_obj.pos + glyph.pos;
                 let mesh = CharacterMesh {
-                    pos: glm::vec4(x_pos, y_pos, 0.0, 1.0),
+                    pos: glm::vec4(pos.x, pos.y, 0.0, 1.0),
                     projection: *projection,
-                    color: text_obj.style.color,
-                    w,
-                    h,
+                    color: glyph.color,
+                    w: glyph.size.x,
+                    h: glyph.size.y,
                 };
 
-                self.renderer
-                    .draw_mesh(&mesh, &self.shader, Some(&ch.texture));
-
-                text_obj.pos.x += (ch.advance_x >> 6) as f32 * text_obj.style.scale;
+                self.renderer.draw_vertices_by_id(
+                    mesh.vertices(),
+                    &self.shader,
+                    Some(glyph.texture),
+                );
             }
         }
+        self.layouts.end_frame();
         self.renderer.end_batch();
         self.renderer.flush_batch(&self.shader);
 
-        Some(self.renderer.draw_calls)
+        Some(self.renderer.info().draw_calls)
+    }
+
+    fn render_info(&self) -> Option<RenderInfo> {
+        Some(self.renderer.info())
     }
 }
 
@@ -91,6 +102,64 @@ pub struct QPText {
     pub text: String,
     pub pos: glm::Vec2,
     pub style: QPTextStyle,
+
+    /**
+     * more text laid out straight after `text`, each with its own style
+     */
+    pub spans: Vec<QPTextSpan>,
+}
+
+impl QPText {
+    /**
+     * text made only of styled spans
+     */
+    pub fn rich(pos: glm::Vec2, spans: Vec<QPTextSpan>) -> Self {
+        Self {
+            pos,
+            spans,
+            ..Self::default()
+        }
+    }
+
+    /**
+     * every run of text with its style, starting with `text`
+     */
+    pub fn runs(&self) -> impl Iterator<Item = (&str, &QPTextStyle)> {
+        std::iter::once((self.text.as_str(), &self.style))
+            .chain(self.spans.iter().map(|span| (span.text.as_str(), &span.style)))
+    }
+
+    /**
+     * where each run starts, given how far a character moves the pen in its
+     * style. all runs share the baseline at `pos.y`
+     */
+    pub fn layout(&self, advance: impl Fn(&QPTextStyle, char) -> f32) -> Vec<glm::Vec2> {
+        let mut pen = self.pos;
+
+        self.runs()
+            .map(|(text, style)| {
+                let start = pen;
+                pen.x += text.chars().map(|c| advance(style, c)).sum::<f32>();
+
+                start
+            })
+            .collect()
+    }
+
+    /**
+     * the width of every run put together
+     */
+    pub fn measure(&self, advance: impl Fn(&QPTextStyle, char) -> f32) -> f32 {
+        self.runs()
+            .map(|(text, style)| text.chars().map(|c| advance(style, c)).sum::<f32>())
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct QPTextSpan {
+    pub text: String,
+    pub style: QPTextStyle,
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -98,6 +167,362 @@ pub struct QPTextStyle {
     pub font: u64,
     pub color: glm::Vec4,
     pub scale: f32,
+
+    /**
+     * where each line sits relative to the text's pos. only the style of
+     * `QPText::text` is used for this, spans follow it
+     */
+    pub align: TextAlign,
+
+    /**
+     * lines longer than this are broken at whitespace, or mid word for a
+     * word that doesn't fit on a line by itself. like align, only the
+     * style of `QPText::text` is used
+     */
+    pub max_width: Option<f32>,
+}
+
+/**
+* which part of each line lines up with the text's pos
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/**
+* the gap between baselines, as a multiple of the tallest glyph in the text
+*/
+pub const LINE_SPACING: f32 = 1.2;
+
+/**
+* how a character of a font sits on the baseline and moves the pen, at scale 1
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphMetrics {
+    pub bearing: glm::Vec2,
+    pub size: glm::Vec2,
+    pub advance: f32,
+
+    /**
+     * the font and character actually drawn, after fallbacks and
+     * replacements, and the gl id of that glyph's texture
+     */
+    pub font: u64,
+    pub glyph: char,
+    pub texture: u32,
+}
+
+/**
+* a character placed by `layout_glyphs`. pos is the bottom left corner of the
+* glyph, relative to the text's pos. c is the character from the text, while
+* font, glyph and texture are what gets drawn for it (see `GlyphMetrics`)
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacedGlyph {
+    pub c: char,
+    pub font: u64,
+    pub glyph: char,
+    pub texture: u32,
+    pub pos: glm::Vec2,
+    pub size: glm::Vec2,
+    pub color: glm::Vec4,
+}
+
+/**
+* the metrics of the glyph drawn for c in style, from the style's font, one of
+* its fallbacks, or a replacement if none have it (see `RFont::find_glyph`)
+*/
+pub fn glyph_metrics(
+    asset_manager: &AssetManager,
+    style: &QPTextStyle,
+    c: char,
+) -> Option<GlyphMetrics> {
+    let fonts = |id| asset_manager.get::<RFont>(id);
+    let (font, glyph) = resolve_glyph(style.font, c, fonts)?;
+    let ch = fonts(font)?.characters.get(&glyph)?;
+
+    Some(ch.metrics(font, glyph))
+}
+
+/**
+* places every character of the text. characters metrics has nothing for take
+* no room and aren't drawn
+*
+* the text is split into lines at '\n' and wrapped at the style's max_width.
+* the first line's baseline is at the text's pos and the rest go down from
+* there, each aligned on its own
+*/
+pub fn layout_glyphs(
+    text: &QPText,
+    metrics: impl Fn(&QPTextStyle, char) -> Option<GlyphMetrics>,
+) -> Vec<PlacedGlyph> {
+    let lines = wrap_lines(text, &metrics);
+    let line_height = line_height(&lines);
+
+    let mut glyphs = vec![];
+    for (i, line) in lines.iter().enumerate() {
+        let mut pen = match text.style.align {
+            TextAlign::Left => 0.0,
+            TextAlign::Center => -line.width / 2.0,
+            TextAlign::Right => -line.width,
+        };
+        let baseline = -(i as f32) * line_height;
+
+        for (c, style, glyph) in line.glyphs.iter() {
+            let Some(glyph) = glyph else {
+                continue;
+            };
+
+            glyphs.push(PlacedGlyph {
+                c: *c,
+                font: glyph.font,
+                glyph: glyph.glyph,
+                texture: glyph.texture,
+                pos: glm::vec2(
+                    pen + glyph.bearing.x * style.scale,
+                    baseline - (glyph.size.y - glyph.bearing.y) * style.scale,
+                ),
+                size: glyph.size * style.scale,
+                color: style.color,
+            });
+            pen += glyph.advance * style.scale;
+        }
+    }
+
+    glyphs
+}
+
+/**
+* the width and height of the text as `layout_glyphs` places it. the width is
+* the widest line, without trailing whitespace, and every line is one line
+* height tall
+*/
+pub fn measure_glyphs(
+    text: &QPText,
+    metrics: impl Fn(&QPTextStyle, char) -> Option<GlyphMetrics>,
+) -> glm::Vec2 {
+    if text.runs().all(|(run, _)| run.is_empty()) {
+        return glm::vec2(0.0, 0.0);
+    }
+
+    let lines = wrap_lines(text, &metrics);
+    let width = lines.iter().map(|line| line.width).fold(0.0, f32::max);
+
+    glm::vec2(width, lines.len() as f32 * line_height(&lines))
+}
+
+// the gap between baselines, from the tallest glyph in the text
+fn line_height(lines: &[TextLine]) -> f32 {
+    lines
+        .iter()
+        .flat_map(|line| line.glyphs.iter())
+        .filter_map(|(_, style, glyph)| glyph.map(|glyph| glyph.size.y * style.scale))
+        .fold(0.0, f32::max)
+        * LINE_SPACING
+}
+
+struct TextLine<'a> {
+    glyphs: Vec<(char, &'a QPTextStyle, Option<GlyphMetrics>)>,
+
+    // where the pen is after the last glyph
+    end: f32,
+
+    // without trailing whitespace, so aligned lines line up on what's visible
+    width: f32,
+}
+
+impl<'a> TextLine<'a> {
+    fn new() -> Self {
+        Self {
+            glyphs: vec![],
+            end: 0.0,
+            width: 0.0,
+        }
+    }
+
+    fn push(&mut self, glyph: (char, &'a QPTextStyle, Option<GlyphMetrics>)) {
+        self.end += advance(&glyph);
+        if !glyph.0.is_whitespace() {
+            self.width = self.end;
+        }
+
+        self.glyphs.push(glyph);
+    }
+}
+
+fn advance((_, style, glyph): &(char, &QPTextStyle, Option<GlyphMetrics>)) -> f32 {
+    glyph.map_or(0.0, |glyph| glyph.advance * style.scale)
+}
+
+/**
+* the text's characters split into lines at '\n', then greedily wrapped at
+* whitespace to fit max_width
+*/
+fn wrap_lines<'a>(
+    text: &'a QPText,
+    metrics: &impl Fn(&QPTextStyle, char) -> Option<GlyphMetrics>,
+) -> Vec<TextLine<'a>> {
+    let mut lines = vec![TextLine::new()];
+    let max_width = text.style.max_width;
+
+    // a word and the whitespace after it, carried together onto the next line
+    let mut word: Vec<(char, &QPTextStyle, Option<GlyphMetrics>)> = vec![];
+
+    let place_word = |lines: &mut Vec<TextLine<'a>>, word: &mut Vec<_>| {
+        let Some(max_width) = max_width else {
+            let line = lines.last_mut().unwrap();
+            word.drain(..).for_each(|glyph| line.push(glyph));
+            return;
+        };
+
+        let visible = word
+            .iter()
+            .take_while(|(c, _, _)| !c.is_whitespace())
+            .map(advance)
+            .sum::<f32>();
+        let line = lines.last().unwrap();
+        if !line.glyphs.is_empty() && line.end + visible > max_width {
+            lines.push(TextLine::new());
+        }
+
+        for glyph in word.drain(..) {
+            let line = lines.last_mut().unwrap();
+
+            // a word longer than a whole line is broken wherever it runs out
+            let over = line.end + advance(&glyph) > max_width;
+            if over && !glyph.0.is_whitespace() && !line.glyphs.is_empty() {
+                lines.push(TextLine::new());
+            }
+
+            lines.last_mut().unwrap().push(glyph);
+        }
+    };
+
+    for (run, style) in text.runs() {
+        for c in run.chars() {
+            if c == '\n' {
+                place_word(&mut lines, &mut word);
+                lines.push(TextLine::new());
+
+                continue;
+            }
+
+            // whitespace ends the word, but goes with it
+            if !c.is_whitespace() && word.last().is_some_and(|(c, _, _)| c.is_whitespace()) {
+                place_word(&mut lines, &mut word);
+            }
+
+            word.push((c, style, metrics(style, c)));
+        }
+    }
+    place_word(&mut lines, &mut word);
+
+    lines
+}
+
+/**
+* the glyph layouts of the texts drawn recently, so text that rarely changes,
+* like a score or instructions, isn't laid out again every frame. layouts are
+* keyed by a hash of the text and styles, not the position, and a layout that
+* wasn't used for a whole frame is dropped
+*/
+#[derive(Debug, Default)]
+pub struct TextLayoutCache {
+    entries: HashMap<u64, CachedLayout>,
+    layouts: usize,
+}
+
+#[derive(Debug)]
+struct CachedLayout {
+    text: QPText,
+    glyphs: Vec<PlacedGlyph>,
+    used: bool,
+}
+
+impl TextLayoutCache {
+    /**
+     * the cached layout of text, or a new one if its string or styles
+     * changed
+     */
+    pub fn get_or_layout(
+        &mut self,
+        text: &QPText,
+        metrics: impl Fn(&QPTextStyle, char) -> Option<GlyphMetrics>,
+    ) -> &[PlacedGlyph] {
+        let text = QPText {
+            pos: glm::vec2(0.0, 0.0),
+            ..text.clone()
+        };
+        let mut hasher = DefaultHasher::new();
+        hash_text(&text, &mut hasher);
+        let key = hasher.finish();
+
+        // a different text with the same hash replaces the old layout
+        let stale = self
+            .entries
+            .get(&key)
+            .is_none_or(|entry| entry.text != text);
+        if stale {
+            self.layouts += 1;
+            self.entries.insert(
+                key,
+                CachedLayout {
+                    glyphs: layout_glyphs(&text, metrics),
+                    text,
+                    used: false,
+                },
+            );
+        }
+
+        let entry = self.entries.get_mut(&key).unwrap();
+        entry.used = true;
+
+        &entry.glyphs
+    }
+
+    /**
+     * drops the layouts that weren't used since the last call
+     */
+    pub fn end_frame(&mut self) {
+        self.entries.retain(|_, entry| entry.used);
+
+        for entry in self.entries.values_mut() {
+            entry.used = false;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /**
+     * how many times a text had to be laid out instead of coming from the
+     * cache
+     */
+    pub fn layouts(&self) -> usize {
+        self.layouts
+    }
+}
+
+// floats are hashed by their bits. +0.0 and -0.0 hash differently though
+// they're equal, which only costs a layout
+fn hash_text(text: &QPText, state: &mut impl Hasher) {
+    for (run, style) in text.runs() {
+        run.hash(state);
+        style.font.hash(state);
+        style.color.iter().for_each(|v| v.to_bits().hash(state));
+        style.scale.to_bits().hash(state);
+        style.align.hash(state);
+        style.max_width.map(f32::to_bits).hash(state);
+    }
 }
 
 struct CharacterMesh {
@@ -127,24 +552,28 @@ impl Mesh for CharacterMesh {
                 color: self.color,
                 tex_coords: glm::vec2(0.0, 0.0),
                 tex_index: 0.0,
+                ..Vertex::default()
             },
             Vertex {
                 position: pos2.xyz(),
                 color: self.color,
                 tex_coords: glm::vec2(0.0, 1.0),
                 tex_index: 0.0,
+                ..Vertex::default()
             },
             Vertex {
                 position: pos3.xyz(),
                 color: self.color,
                 tex_coords: glm::vec2(1.0, 1.0),
                 tex_index: 0.0,
+                ..Vertex::default()
             },
             Vertex {
                 position: pos4.xyz(),
                 color: self.color,
                 tex_coords: glm::vec2(1.0, 0.0),
                 tex_index: 0.0,
+                ..Vertex::default()
             },
         ]
     }
@@ -190,3 +619,217 @@ void main()
     fragColor = color * sampled;
 }
 "#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // every character is 10px wide at scale 1
+    fn advance(style: &QPTextStyle, _c: char) -> f32 {
+        10.0 * style.scale
+    }
+
+    #[test]
+    fn text_spans_laid_out_contiguously() {
+        let span = |text: &str, scale: f32| QPTextSpan {
+            text: text.into(),
+            style: QPTextStyle {
+                scale,
+                ..QPTextStyle::default()
+            },
+        };
+
+        let text = QPText::rich(
+            glm::vec2(20.0, 40.0),
+            vec![span("score: ", 0.5), span("1200", 2.0), span("!", 1.0)],
+        );
+
+        let starts = text.layout(advance);
+
+        // the empty `text` run takes no room
+        assert_eq!(starts[0], glm::vec2(20.0, 40.0));
+        assert_eq!(starts[1], glm::vec2(20.0, 40.0));
+        assert_eq!(starts[2], glm::vec2(20.0 + 7.0 * 5.0, 40.0));
+        assert_eq!(starts[3], glm::vec2(20.0 + 7.0 * 5.0 + 4.0 * 20.0, 40.0));
+        assert_eq!(text.measure(advance), 7.0 * 5.0 + 4.0 * 20.0 + 10.0);
+    }
+
+    // 'é' comes from the fallback font 7, as a replacement
+    fn metrics(style: &QPTextStyle, c: char) -> Option<GlyphMetrics> {
+        let (font, glyph) = match c {
+            'é' => (7, '?'),
+            _ => (style.font, c),
+        };
+
+        (c != ' ').then_some(GlyphMetrics {
+            bearing: glm::vec2(1.0, 8.0),
+            size: glm::vec2(8.0, 10.0),
+            advance: 10.0,
+            font,
+            glyph,
+            texture: font as u32 * 1000 + glyph as u32,
+        })
+    }
+
+    #[test]
+    fn text_layout_cache_reuses_unchanged_text() {
+        let mut cache = TextLayoutCache::default();
+        let mut score = QPText {
+            text: "score: 10".into(),
+            pos: glm::vec2(20.0, 40.0),
+            style: QPTextStyle {
+                scale: 2.0,
+                ..QPTextStyle::default()
+            },
+            ..QPText::default()
+        };
+
+        let glyphs = cache.get_or_layout(&score, metrics).to_vec();
+        assert_eq!(glyphs, layout_glyphs(&score, metrics));
+        assert_eq!(glyphs.len(), 8);
+        assert_eq!(glyphs[1].c, 'c');
+        assert_eq!(glyphs[1].pos, glm::vec2(20.0 + 2.0, -4.0));
+        assert_eq!(glyphs[1].size, glm::vec2(16.0, 20.0));
+        assert_eq!(glyphs[1].texture, 'c' as u32);
+        cache.end_frame();
+
+        // the same string, even somewhere else, comes from the cache
+        score.pos = glm::vec2(0.0, 0.0);
+        assert_eq!(cache.get_or_layout(&score, metrics), glyphs);
+        assert_eq!(cache.layouts(), 1);
+        cache.end_frame();
+
+        score.text = "score: 20".into();
+        cache.get_or_layout(&score, metrics);
+        assert_eq!(cache.layouts(), 2);
+
+        score.style.scale = 1.0;
+        let glyphs = cache.get_or_layout(&score, metrics);
+        assert_eq!(glyphs[1].pos, glm::vec2(11.0, -2.0));
+        assert_eq!(cache.layouts(), 3);
+
+        // the old score wasn't drawn this frame
+        cache.end_frame();
+        assert_eq!(cache.len(), 2);
+        cache.end_frame();
+        assert!(cache.is_empty());
+
+        // what's drawn for a character is resolved once, with the layout
+        score.text = "café".into();
+        let glyphs = cache.get_or_layout(&score, metrics).to_vec();
+        assert_eq!((glyphs[2].c, glyphs[2].font, glyphs[2].glyph), ('f', 0, 'f'));
+        assert_eq!((glyphs[3].c, glyphs[3].font, glyphs[3].glyph), ('é', 7, '?'));
+        assert_eq!(glyphs[3].texture, 7000 + '?' as u32);
+        assert_eq!(cache.get_or_layout(&score, metrics), glyphs);
+        assert_eq!(cache.layouts(), 4);
+    }
+
+    // like metrics, but spaces take up room too
+    fn spaced(style: &QPTextStyle, _c: char) -> Option<GlyphMetrics> {
+        metrics(style, 'x')
+    }
+
+    // the text of every line, in order, with the x of its first pen position
+    fn lines(glyphs: &[PlacedGlyph]) -> Vec<(String, f32)> {
+        let mut lines: Vec<(f32, String, f32)> = vec![];
+        for glyph in glyphs {
+            let baseline = glyph.pos.y + 2.0 * 2.0;
+            match lines.last_mut() {
+                Some((y, text, _)) if *y == baseline => text.push(glyph.c),
+                _ => lines.push((baseline, glyph.c.into(), glyph.pos.x - 2.0)),
+            }
+        }
+
+        lines.into_iter().map(|(_, text, x)| (text, x)).collect()
+    }
+
+    #[test]
+    fn text_wraps_at_max_width() {
+        // 20px a character at scale 2, 10 characters to a line
+        let mut text = QPText {
+            text: "the quick brown fox jumps over the lazy dog".into(),
+            style: QPTextStyle {
+                scale: 2.0,
+                max_width: Some(200.0),
+                ..QPTextStyle::default()
+            },
+            ..QPText::default()
+        };
+
+        let wrapped = lines(&layout_glyphs(&text, spaced));
+        let words = wrapped.iter().map(|(text, _)| text.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            words,
+            vec!["the quick ", "brown fox ", "jumps over ", "the lazy ", "dog"]
+        );
+
+        // a word too long for a line is broken up, newlines always break
+        text.text = "supercalifragilistic\nok".into();
+        let wrapped = lines(&layout_glyphs(&text, spaced));
+        let words = wrapped.iter().map(|(text, _)| text.as_str()).collect::<Vec<_>>();
+        assert_eq!(words, vec!["supercalif", "ragilistic", "ok"]);
+
+        text.style.max_width = None;
+        assert_eq!(lines(&layout_glyphs(&text, spaced)).len(), 2);
+    }
+
+    #[test]
+    fn text_measure_matches_layout() {
+        let mut text = QPText {
+            text: "Press Enter\nto\nstart again ".into(),
+            style: QPTextStyle {
+                scale: 2.0,
+                ..QPTextStyle::default()
+            },
+            ..QPText::default()
+        };
+
+        // glyphs are 10px tall at scale 1, the widest line is 10 glyphs
+        let line_height = 10.0 * 2.0 * LINE_SPACING;
+        let size = measure_glyphs(&text, metrics);
+        assert_eq!(size, glm::vec2(10.0 * 20.0, 3.0 * line_height));
+
+        // the last line's baseline is two line heights down
+        let glyphs = layout_glyphs(&text, metrics);
+        let last = glyphs.last().unwrap();
+        assert_eq!(last.pos.y + 2.0 * 2.0, -2.0 * line_height);
+
+        // replacements from other fonts take room like any other glyph
+        text.text = "café".into();
+        assert_eq!(measure_glyphs(&text, metrics), glm::vec2(80.0, line_height));
+
+        text.style.max_width = Some(100.0);
+        text.text = "game over".into();
+        assert_eq!(measure_glyphs(&text, metrics).y, 2.0 * line_height);
+
+        text.text = "".into();
+        assert_eq!(measure_glyphs(&text, metrics), glm::vec2(0.0, 0.0));
+    }
+
+    #[test]
+    fn text_align_offsets_each_line() {
+        let mut text = QPText {
+            text: "game over\npress enter to play".into(),
+            style: QPTextStyle {
+                scale: 2.0,
+                align: TextAlign::Center,
+                ..QPTextStyle::default()
+            },
+            ..QPText::default()
+        };
+
+        // each line is centered on its own width, 9 and 19 characters
+        let centered = lines(&layout_glyphs(&text, spaced));
+        assert_eq!(centered[0].1, -9.0 * 20.0 / 2.0);
+        assert_eq!(centered[1].1, -19.0 * 20.0 / 2.0);
+
+        text.style.align = TextAlign::Right;
+        let right = lines(&layout_glyphs(&text, spaced));
+        assert_eq!(right[0].1, -9.0 * 20.0);
+        assert_eq!(right[1].1, -19.0 * 20.0);
+
+        // trailing whitespace doesn't count
+        text.text = "game over   ".into();
+        assert_eq!(lines(&layout_glyphs(&text, spaced))[0].1, -9.0 * 20.0);
+    }
+}
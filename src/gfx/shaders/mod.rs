@@ -3,12 +3,21 @@ use serde::{Deserialize, Serialize};
 pub static SPRITE_VERT: &str = include_str!("sprite.vert");
 pub static SPRITE_FRAG: &str = include_str!("sprite.frag");
 
+/**
+* the vertex shader for the InstancedSpriteRenderer. pairs with SPRITE_FRAG
+*/
+pub static SPRITE_INSTANCED_VERT: &str = include_str!("sprite_instanced.vert");
+
 pub fn get_shader(shader: &str) -> ShaderResult {
     match shader {
         "sprite" => ShaderResult {
             vert: SPRITE_VERT,
             frag: SPRITE_FRAG,
         },
+        "sprite_instanced" => ShaderResult {
+            vert: SPRITE_INSTANCED_VERT,
+            frag: SPRITE_FRAG,
+        },
         _ => ShaderResult {
             vert: SPRITE_VERT,
             frag: SPRITE_FRAG,
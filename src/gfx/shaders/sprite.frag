@@ -3,11 +3,20 @@
 in vec4 color;
 in vec2 texCoords;
 in float texIndex;
+in vec2 local;
+in vec4 shape;
 
 uniform sampler2D u_textures[32];
 
 out vec4 fragColor;
 
+// signed distance to a rounded rect (half size, corner radius), see CShape
+float shapeDistance(vec2 p, vec2 half_size, float radius) {
+    vec2 q = abs(p) - half_size + radius;
+
+    return length(max(q, 0.0)) + min(max(q.x, q.y), 0.0) - radius;
+}
+
 void main() {
     int texId = int(texIndex);
 
@@ -16,4 +25,12 @@ void main() {
     } else {
         fragColor = color * texture(u_textures[texId], texCoords);
     }
+
+    // zero half size means a plain quad
+    if (shape.x > 0.0) {
+        float d = shapeDistance(local, shape.xy, shape.z);
+        float edge = max(shape.w, fwidth(d));
+
+        fragColor.a *= 1.0 - smoothstep(-edge, edge, d);
+    }
 }
@@ -4,6 +4,8 @@ layout (location = 0) in vec3 aPos;
 layout (location = 1) in vec4 aColor;
 layout (location = 2) in vec2 aTexCoords;
 layout (location = 3) in float aTexIndex;
+layout (location = 4) in vec2 aLocal;
+layout (location = 5) in vec4 aShape;
 
 uniform mat4 view;
 uniform mat4 projection;
@@ -11,6 +13,8 @@ uniform mat4 projection;
 out vec4 color;
 out vec2 texCoords;
 out float texIndex;
+out vec2 local;
+out vec4 shape;
 
 void main(){
     gl_Position = vec4(aPos, 1.0);
@@ -18,4 +22,6 @@ void main(){
     color = aColor;
     texCoords = aTexCoords;
     texIndex = aTexIndex;
+    local = aLocal;
+    shape = aShape;
 }
@@ -0,0 +1,32 @@
+#version 450 core
+
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec2 aTexCoords;
+
+// per instance, see SpriteInstance
+layout (location = 2) in mat4 iModel;
+layout (location = 6) in vec4 iColor;
+layout (location = 7) in vec2 iCell;
+layout (location = 8) in vec2 iCellScale;
+layout (location = 9) in float iTexIndex;
+
+uniform mat4 view;
+uniform mat4 projection;
+
+out vec4 color;
+out vec2 texCoords;
+out float texIndex;
+out vec2 local;
+out vec4 shape;
+
+void main(){
+    gl_Position = projection * view * iModel * vec4(aPos, 1.0);
+
+    color = iColor;
+    texCoords = (aTexCoords + iCell) * iCellScale;
+    texIndex = iTexIndex;
+
+    // instanced sprites are plain quads
+    local = vec2(0.0);
+    shape = vec4(0.0);
+}
@@ -0,0 +1,216 @@
+use crate::platform::opengl::{
+    capabilities::{gl_disable, gl_enable, GLCapability},
+    stencil::{
+        clear_stencil, gl_color_mask, gl_depth_mask, gl_stencil_func, gl_stencil_mask,
+        gl_stencil_op, GLStencilFunc, GLStencilOp,
+    },
+};
+
+/**
+* which side of the mask shape later draws are kept on
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StencilMode {
+    Inside,
+    Outside,
+}
+
+/**
+* starts a mask. anything drawn until `draw_masked` marks the stencil buffer
+* instead of the screen
+*
+* ```no_run
+* # use quipi::prelude::qp_gfx::{begin_stencil_mask, draw_masked, end_stencil_mask, StencilMode};
+* begin_stencil_mask();
+* // draw the minimap circle
+* draw_masked(StencilMode::Inside);
+* // draw the minimap, clipped to the circle
+* end_stencil_mask();
+* ```
+*/
+pub fn begin_stencil_mask() {
+    gl_enable(GLCapability::StencilTest);
+    gl_stencil_mask(0xFF);
+    clear_stencil(0);
+
+    gl_stencil_func(GLStencilFunc::Always, 1, 0xFF);
+    gl_stencil_op(GLStencilOp::Keep, GLStencilOp::Keep, GLStencilOp::Replace);
+    gl_color_mask(false);
+    gl_depth_mask(false);
+}
+
+/**
+* stops writing the mask. anything drawn until `end_stencil_mask` only shows
+* on the given side of it
+*/
+pub fn draw_masked(mode: StencilMode) {
+    gl_color_mask(true);
+    gl_depth_mask(true);
+    gl_stencil_mask(0x00);
+
+    let func = match mode {
+        StencilMode::Inside => GLStencilFunc::Equal,
+        StencilMode::Outside => GLStencilFunc::NotEqual,
+    };
+    gl_stencil_func(func, 1, 0xFF);
+}
+
+/**
+* back to drawing everywhere
+*/
+pub fn end_stencil_mask() {
+    gl_stencil_mask(0xFF);
+    gl_stencil_func(GLStencilFunc::Always, 0, 0xFF);
+    gl_stencil_op(GLStencilOp::Keep, GLStencilOp::Keep, GLStencilOp::Keep);
+    gl_disable(GLCapability::StencilTest);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        gfx::batch_renderer::{BatchRenderer, Mesh, Vertex},
+        prelude::qp_assets::RShader,
+    };
+    use sdl2::{
+        video::{GLContext, GLProfile, Window},
+        Sdl, VideoSubsystem,
+    };
+
+    // a rect straight in clip space
+    struct Rect {
+        min: glm::Vec2,
+        max: glm::Vec2,
+    }
+
+    impl Mesh for Rect {
+        fn indices() -> Vec<i32> {
+            vec![0, 1, 2, 0, 2, 3]
+        }
+        fn vertex_count() -> usize {
+            4
+        }
+        fn vertices(&self) -> Vec<Vertex> {
+            [
+                glm::vec2(self.min.x, self.max.y),
+                glm::vec2(self.min.x, self.min.y),
+                glm::vec2(self.max.x, self.min.y),
+                glm::vec2(self.max.x, self.max.y),
+            ]
+            .iter()
+            .map(|pos| Vertex {
+                position: glm::vec3(pos.x, pos.y, 0.0),
+                color: glm::vec4(1.0, 1.0, 1.0, 1.0),
+                tex_coords: glm::vec2(0.0, 0.0),
+                tex_index: 0.0,
+                ..Vertex::default()
+            })
+            .collect()
+        }
+    }
+
+    const VERT: &str = r#"
+    #version 450 core
+    layout (location = 0) in vec3 aPos;
+    layout (location = 1) in vec4 aColor;
+    out vec4 color;
+    void main() {
+        gl_Position = vec4(aPos, 1.0);
+        color = aColor;
+    }
+    "#;
+
+    const FRAG: &str = r#"
+    #version 450 core
+    in vec4 color;
+    out vec4 fragColor;
+    void main() {
+        fragColor = color;
+    }
+    "#;
+
+    // None when there's no display to make a gl context on
+    fn gl_context() -> Option<(Sdl, VideoSubsystem, Window, GLContext)> {
+        let sdl = sdl2::init().ok()?;
+        let video = sdl.video().ok()?;
+
+        let gl_attr = video.gl_attr();
+        gl_attr.set_context_profile(GLProfile::Core);
+        gl_attr.set_context_version(4, 5);
+        gl_attr.set_stencil_size(8);
+
+        let window = video
+            .window("stencil", 64, 64)
+            .opengl()
+            .hidden()
+            .build()
+            .ok()?;
+        let ctx = window.gl_create_context().ok()?;
+        gl::load_with(|name| video.gl_get_proc_address(name) as *const _);
+
+        Some((sdl, video, window, ctx))
+    }
+
+    fn pixel(x: i32, y: i32) -> [u8; 4] {
+        let mut out = [0u8; 4];
+        unsafe {
+            gl::ReadPixels(
+                x,
+                y,
+                1,
+                1,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                out.as_mut_ptr() as *mut _,
+            );
+        }
+
+        out
+    }
+
+    fn draw(renderer: &mut BatchRenderer<Rect>, shader: &RShader, rect: Rect) {
+        renderer.begin_batch();
+        renderer.draw_mesh(&rect, shader, None);
+        renderer.end_batch();
+        renderer.flush_batch(shader);
+    }
+
+    #[test]
+    fn stencil_mask_clips_drawing() {
+        let Some(_gl) = gl_context() else {
+            println!("no gl context, skipping");
+            return;
+        };
+
+        let shader = RShader::from_str(VERT, FRAG, vec![]).unwrap();
+        let mut renderer = BatchRenderer::<Rect>::new(1);
+
+        unsafe {
+            gl::Viewport(0, 0, 64, 64);
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+        }
+
+        // mask the left half, then fill the whole screen through it
+        begin_stencil_mask();
+        let left = Rect {
+            min: glm::vec2(-1.0, -1.0),
+            max: glm::vec2(0.0, 1.0),
+        };
+        draw(&mut renderer, &shader, left);
+
+        // writing the mask doesn't touch the screen
+        assert_eq!(pixel(16, 32), [0, 0, 0, 255]);
+
+        draw_masked(StencilMode::Inside);
+        let all = Rect {
+            min: glm::vec2(-1.0, -1.0),
+            max: glm::vec2(1.0, 1.0),
+        };
+        draw(&mut renderer, &shader, all);
+        end_stencil_mask();
+
+        assert_eq!(pixel(16, 32), [255, 255, 255, 255]);
+        assert_eq!(pixel(48, 32), [0, 0, 0, 255]);
+    }
+}
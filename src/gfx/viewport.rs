@@ -1,10 +1,24 @@
-use crate::platform::opengl::functions::gl_set_viewport_dimensions;
+use crate::{
+    platform::opengl::{
+        buffer::clear_buffers_masked,
+        capabilities::{gl_disable, gl_enable, GLCapability},
+        functions::{gl_scissor, gl_set_viewport_dimensions},
+    },
+    prelude::qp_assets::RCamera2D,
+};
 
 pub struct Viewport {
     x: i32,
     y: i32,
     width: i32,
     height: i32,
+
+    aspect: Option<f32>,
+    pixel_perfect: Option<(i32, i32)>,
+    window: (i32, i32),
+
+    // false for `headless` viewports, which never touch gl
+    gl: bool,
 }
 
 impl Viewport {
@@ -14,6 +28,10 @@ impl Viewport {
             y,
             width,
             height,
+            aspect: None,
+            pixel_perfect: None,
+            window: (x + width, y + height),
+            gl: true,
         };
 
         viewport.set_dimensions(x, y, width, height);
@@ -21,13 +39,130 @@ impl Viewport {
         viewport
     }
 
+    /**
+     * a viewport that never touches gl, for tests that run without a context
+     */
+    #[cfg(test)]
+    pub(crate) fn headless(window_width: i32, window_height: i32, aspect: Option<f32>) -> Self {
+        let (x, y, width, height) = match aspect {
+            Some(aspect) => letterbox(window_width, window_height, aspect),
+            None => (0, 0, window_width, window_height),
+        };
+
+        Self {
+            x,
+            y,
+            width,
+            height,
+            aspect,
+            pixel_perfect: None,
+            window: (window_width, window_height),
+            gl: false,
+        }
+    }
+
     pub fn set_dimensions(&mut self, x: i32, y: i32, width: i32, height: i32) {
         self.x = x;
         self.y = y;
         self.width = width;
         self.height = height;
 
-        gl_set_viewport_dimensions(x, y, width, height);
+        if self.gl {
+            gl_set_viewport_dimensions(x, y, width, height);
+        }
+    }
+
+    /**
+     * keep the viewport at a fixed aspect ratio (width / height), centered in
+     * the window with black bars filling the rest. `None` fills the window
+     */
+    pub fn set_aspect(&mut self, aspect: Option<f32>) {
+        self.aspect = aspect;
+
+        self.fit_window(self.window.0, self.window.1);
+    }
+
+    /**
+     * keep the viewport at a whole number multiple of the target resolution,
+     * as big as fits the window and centered with black bars. takes the
+     * place of `set_aspect`. see `PixelPerfectCamera`
+     */
+    pub fn set_pixel_perfect(&mut self, target: Option<(i32, i32)>) {
+        self.pixel_perfect = target;
+
+        self.fit_window(self.window.0, self.window.1);
+    }
+
+    pub fn pixel_perfect(&self) -> Option<(i32, i32)> {
+        self.pixel_perfect
+    }
+
+    /**
+     * call this when the window is resized
+     */
+    pub fn fit_window(&mut self, width: i32, height: i32) {
+        self.window = (width, height);
+
+        let (x, y, width, height) = match (self.pixel_perfect, self.aspect) {
+            (Some((target_width, target_height)), _) => {
+                pixel_perfect_rect(width, height, target_width, target_height)
+            }
+            (None, Some(aspect)) => letterbox(width, height, aspect),
+            (None, None) => (0, 0, width, height),
+        };
+
+        self.set_dimensions(x, y, width, height);
+    }
+
+    /**
+     * clears the viewport as configured, with any letterbox bars cleared to
+     * black. a config without a color leaves the last frame in place
+     */
+    pub fn clear(&self, config: &ClearConfig) {
+        self.clear_with(config, |scissor, color, depth| {
+            if let Some((x, y, width, height)) = scissor {
+                gl_enable(GLCapability::ScissorTest);
+                gl_scissor(x, y, width, height);
+                clear_buffers_masked(color, depth);
+                gl_disable(GLCapability::ScissorTest);
+            } else {
+                clear_buffers_masked(color, depth);
+            }
+        });
+    }
+
+    // works out the clears without touching gl so it can be tested
+    fn clear_with(
+        &self,
+        config: &ClearConfig,
+        mut clear: impl FnMut(Option<(i32, i32, i32, i32)>, Option<ClearColor>, bool),
+    ) {
+        let boxed = self.aspect.is_some() || self.pixel_perfect.is_some();
+        match (config.color, boxed) {
+            (None, _) if !config.clear_depth => (),
+            (None, _) => clear(None, None, true),
+            (Some(color), false) => clear(None, Some(color), config.clear_depth),
+            (Some(color), true) => {
+                clear(None, Some((0.0, 0.0, 0.0, 1.0)), config.clear_depth);
+                clear(Some(self.get_dimensions()), Some(color), false);
+            }
+        }
+    }
+
+    /**
+     * maps a position in window coordinates (origin top left, like sdl mouse
+     * events) to viewport coordinates (origin at the top left of the game
+     * area). anything in the letterbox bars falls outside 0..width, 0..height
+     */
+    pub fn window_to_viewport(&self, pos: &glm::Vec2) -> glm::Vec2 {
+        // gl measures the viewport from the bottom of the window
+        let top = self.window.1 - (self.y + self.height);
+
+        glm::vec2(pos.x - self.x as f32, pos.y - top as f32)
+    }
+
+    pub fn contains(&self, pos: &glm::Vec2) -> bool {
+        (0.0..self.width as f32).contains(&pos.x) && (0.0..self.height as f32).contains(&pos.y)
     }
 
     /**
@@ -36,4 +171,209 @@ impl Viewport {
     pub fn get_dimensions(&self) -> (i32, i32, i32, i32) {
         (self.x, self.y, self.width, self.height)
     }
+
+    /**
+     * orthographic projection in pixels, with (0, 0) at the bottom left
+     */
+    pub fn screen_projection(&self) -> glm::Mat4 {
+        glm::ortho(0.0, self.width as f32, 0.0, self.height as f32, 0.0, 0.2)
+    }
+
+    /**
+     * where a point in the world ends up on screen through the camera, in the
+     * same pixels as `screen_projection`
+     */
+    pub fn world_to_screen(&self, camera: &RCamera2D, pos: &glm::Vec2) -> glm::Vec2 {
+        let clip = camera.projection * camera.view * glm::vec4(pos.x, pos.y, 0.0, 1.0);
+        let ndc = clip.xy() / clip.w;
+
+        glm::vec2(
+            (ndc.x + 1.0) / 2.0 * self.width as f32,
+            (ndc.y + 1.0) / 2.0 * self.height as f32,
+        )
+    }
+}
+
+pub type ClearColor = (f32, f32, f32, f32);
+
+/**
+ * what gets cleared at the start of every frame
+ *
+ * leaving out the color keeps the previous frame around, eg. for trails
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClearConfig {
+    pub color: Option<ClearColor>,
+    pub clear_depth: bool,
+}
+
+impl Default for ClearConfig {
+    fn default() -> Self {
+        Self {
+            color: Some((0.0, 0.0, 0.0, 1.0)),
+            clear_depth: true,
+        }
+    }
+}
+
+/**
+ * the largest (x, y, width, height) with the given aspect ratio that fits
+ * centered in the window
+ */
+pub fn letterbox(window_width: i32, window_height: i32, aspect: f32) -> (i32, i32, i32, i32) {
+    let width = (window_height as f32 * aspect).round() as i32;
+
+    if width <= window_width {
+        // pillarbox: bars on the left and right
+        return ((window_width - width) / 2, 0, width, window_height);
+    }
+
+    // letterbox: bars on the top and bottom
+    let height = (window_width as f32 / aspect).round() as i32;
+
+    (0, (window_height - height) / 2, window_width, height)
+}
+
+/**
+ * the biggest whole number the target resolution can be scaled up by and
+ * still fit in the window. never less than 1
+ */
+pub fn integer_scale(
+    window_width: i32,
+    window_height: i32,
+    target_width: i32,
+    target_height: i32,
+) -> i32 {
+    let scale_x = window_width / target_width.max(1);
+    let scale_y = window_height / target_height.max(1);
+
+    scale_x.min(scale_y).max(1)
+}
+
+/**
+ * the (x, y, width, height) of the target resolution scaled by
+ * `integer_scale`, centered in the window
+ */
+pub fn pixel_perfect_rect(
+    window_width: i32,
+    window_height: i32,
+    target_width: i32,
+    target_height: i32,
+) -> (i32, i32, i32, i32) {
+    let scale = integer_scale(window_width, window_height, target_width, target_height);
+    let (width, height) = (target_width * scale, target_height * scale);
+
+    ((window_width - width) / 2, (window_height - height) / 2, width, height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letterbox_wide_target_in_narrow_window() {
+        let (x, y, width, height) = letterbox(800, 600, 16.0 / 9.0);
+
+        assert_eq!((x, width, height), (0, 800, 450));
+
+        // one 75px bar above and one below
+        assert_eq!(y, 75);
+        assert_eq!(600 - (y + height), 75);
+    }
+
+    #[test]
+    fn letterbox_narrow_target_in_wide_window() {
+        assert_eq!(letterbox(1600, 900, 4.0 / 3.0), (200, 0, 1200, 900));
+        assert_eq!(letterbox(1600, 900, 16.0 / 9.0), (0, 0, 1600, 900));
+    }
+
+    #[test]
+    fn clear_skips_color_when_disabled() {
+        let mut calls = vec![];
+        let record = |calls: &mut Vec<_>, viewport: &Viewport, config: ClearConfig| {
+            viewport.clear_with(&config, |scissor, color, depth| calls.push((scissor, color, depth)));
+        };
+
+        let viewport = Viewport::headless(800, 600, None);
+        let red = (1.0, 0.0, 0.0, 1.0);
+
+        record(&mut calls, &viewport, ClearConfig { color: None, clear_depth: false });
+        assert!(calls.is_empty());
+
+        record(&mut calls, &viewport, ClearConfig { color: None, clear_depth: true });
+        assert_eq!(calls, vec![(None, None, true)]);
+
+        calls.clear();
+        record(&mut calls, &viewport, ClearConfig { color: Some(red), clear_depth: true });
+        assert_eq!(calls, vec![(None, Some(red), true)]);
+
+        // letterboxed, the bars are cleared black before the game area
+        calls.clear();
+        let viewport = Viewport::headless(800, 600, Some(16.0 / 9.0));
+        record(&mut calls, &viewport, ClearConfig { color: Some(red), clear_depth: false });
+        assert_eq!(
+            calls,
+            vec![
+                (None, Some((0.0, 0.0, 0.0, 1.0)), false),
+                (Some((0, 75, 800, 450)), Some(red), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn pixel_perfect_follows_window_resizes() {
+        let mut viewport = Viewport::headless(1600, 900, None);
+
+        viewport.set_pixel_perfect(Some((320, 180)));
+        assert_eq!(viewport.pixel_perfect(), Some((320, 180)));
+        assert_eq!(viewport.get_dimensions(), (0, 0, 1600, 900));
+
+        // 3x is the biggest that fits, with the rest split into bars
+        viewport.fit_window(1000, 700);
+        assert_eq!(viewport.get_dimensions(), (20, 80, 960, 540));
+
+        // too small for even 1x still draws at 1x
+        viewport.fit_window(200, 100);
+        assert_eq!(viewport.get_dimensions(), (-60, -40, 320, 180));
+
+        viewport.set_pixel_perfect(None);
+        assert_eq!(viewport.get_dimensions(), (0, 0, 200, 100));
+
+        // takes the place of an aspect ratio, which comes back once it's off
+        let mut viewport = Viewport::headless(1600, 900, Some(4.0 / 3.0));
+        viewport.set_pixel_perfect(Some((320, 180)));
+        assert_eq!(viewport.get_dimensions(), (0, 0, 1600, 900));
+        viewport.fit_window(1700, 900);
+        assert_eq!(viewport.get_dimensions(), (50, 0, 1600, 900));
+
+        viewport.set_pixel_perfect(None);
+        assert_eq!(viewport.get_dimensions(), (250, 0, 1200, 900));
+    }
+
+    #[test]
+    fn clear_draws_pixel_perfect_bars() {
+        let mut calls = vec![];
+        let red = (1.0, 0.0, 0.0, 1.0);
+        let config = ClearConfig {
+            color: Some(red),
+            clear_depth: true,
+        };
+
+        let mut viewport = Viewport::headless(1000, 700, None);
+        viewport.set_pixel_perfect(Some((320, 180)));
+        viewport.clear_with(&config, |scissor, color, depth| calls.push((scissor, color, depth)));
+        assert_eq!(
+            calls,
+            vec![
+                (None, Some((0.0, 0.0, 0.0, 1.0)), true),
+                (Some((20, 80, 960, 540)), Some(red), false),
+            ]
+        );
+
+        // no bars once it's off
+        calls.clear();
+        viewport.set_pixel_perfect(None);
+        viewport.clear_with(&config, |scissor, color, depth| calls.push((scissor, color, depth)));
+        assert_eq!(calls, vec![(None, Some(red), true)]);
+    }
 }
@@ -0,0 +1,104 @@
+use sdl2::controller::GameController;
+
+use crate::prelude::QPError;
+use crate::QPResult;
+
+/**
+* what `QPInput::rumble` managed to do. none of these are errors, a game
+* shouldn't care whether the player's pad can shake
+*/
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Rumble {
+    Started,
+
+    /**
+     * there are gamepads, but none of them can rumble
+     */
+    Unsupported,
+
+    NoGamepad,
+}
+
+/**
+* a gamepad that may be able to rumble. implemented for SDL's GameController,
+* and by stand-ins in tests
+*/
+pub trait RumbleDevice {
+    /**
+     * the joystick instance id SDL reports in controller events
+     */
+    fn instance_id(&self) -> u32;
+
+    fn has_rumble(&self) -> bool;
+
+    /**
+     * low and high are the strength of the low and high frequency motors
+     */
+    fn set_rumble(&mut self, low: u16, high: u16, duration_ms: u32) -> QPResult<()>;
+}
+
+impl RumbleDevice for GameController {
+    fn instance_id(&self) -> u32 {
+        GameController::instance_id(self)
+    }
+
+    fn has_rumble(&self) -> bool {
+        GameController::has_rumble(self)
+    }
+
+    fn set_rumble(&mut self, low: u16, high: u16, duration_ms: u32) -> QPResult<()> {
+        GameController::set_rumble(self, low, high, duration_ms)
+            .map_err(|e| QPError::Generic(e.to_string()))
+    }
+}
+
+/**
+* a connected gamepad
+*/
+pub struct Gamepad {
+    device: Box<dyn RumbleDevice>,
+}
+
+impl Gamepad {
+    pub fn new(device: impl RumbleDevice + 'static) -> Self {
+        Self {
+            device: Box::new(device),
+        }
+    }
+
+    pub fn instance_id(&self) -> u32 {
+        self.device.instance_id()
+    }
+
+    pub fn has_rumble(&self) -> bool {
+        self.device.has_rumble()
+    }
+
+    /**
+     * low and high are 0.0 to 1.0. does nothing on a gamepad that can't
+     * rumble
+     */
+    pub fn rumble(&mut self, low: f32, high: f32, duration_ms: u32) -> QPResult<Rumble> {
+        if !self.device.has_rumble() {
+            return Ok(Rumble::Unsupported);
+        }
+
+        self.device
+            .set_rumble(motor_strength(low), motor_strength(high), duration_ms)?;
+
+        Ok(Rumble::Started)
+    }
+}
+
+impl std::fmt::Debug for Gamepad {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Gamepad")
+            .field("instance_id", &self.instance_id())
+            .field("has_rumble", &self.has_rumble())
+            .finish()
+    }
+}
+
+fn motor_strength(strength: f32) -> u16 {
+    (strength.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16
+}
@@ -1,5 +1,259 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use sdl2::{
+    event::Event,
+    keyboard::{Keycode, Scancode},
+};
+
+use crate::prelude::qp_gfx::Viewport;
+use crate::QPResult;
+
+mod gamepad;
+mod recording;
+pub use gamepad::*;
+pub use recording::*;
+
+/**
+* a key by what it types (keycode, follows the keyboard layout) or by where it
+* sits on the keyboard (scancode, the same physical key on every layout)
+*/
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum InputKey {
+    Keycode(Keycode),
+    Scancode(Scancode),
+}
+
+/**
+* a file dropped onto the window, eg. an image or scene to load in an editor
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDropped(pub PathBuf);
+
+#[derive(Debug, Default)]
+pub(crate) struct FrameEdges {
+    pressed: HashSet<Keycode>,
+    released: HashSet<Keycode>,
+    dropped: Vec<FileDropped>,
+}
+
+#[derive(Debug, Default)]
 pub struct QPInput {
-    
+    mouse: glm::Vec2,
+
+    keycodes: HashSet<Keycode>,
+    scancodes: HashSet<Scancode>,
+
+    // keys that went down or up this frame
+    pressed: HashSet<Keycode>,
+    released: HashSet<Keycode>,
+
+    dropped: Vec<FileDropped>,
+
+    // keys whose presses a controller consumed, hidden until they come back
+    // up, and releases consumed this frame. see `World::consume_events`
+    consumed_keycodes: HashSet<Keycode>,
+    consumed_scancodes: HashSet<Scancode>,
+    consumed_releases: HashSet<Keycode>,
+
+    gamepads: Vec<Gamepad>,
+}
+
+impl QPInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * call once per frame with that frame's events
+     */
+    pub fn update(&mut self, events: &[Event]) {
+        self.dropped.clear();
+        self.pressed.clear();
+        self.released.clear();
+        self.consumed_releases.clear();
+
+        self.extend(events);
+    }
+
+    /**
+     * like `update`, but keeps the presses, releases and dropped files
+     * already seen this frame. for a frame that ran no fixed update, so
+     * they're still there for the next one
+     */
+    pub fn extend(&mut self, events: &[Event]) {
+        for event in events {
+            match event {
+                Event::MouseMotion { x, y, .. }
+                | Event::MouseButtonDown { x, y, .. }
+                | Event::MouseButtonUp { x, y, .. } => {
+                    self.mouse = glm::vec2(*x as f32, *y as f32);
+                }
+                Event::KeyDown {
+                    keycode, scancode, ..
+                } => {
+                    if let Some(keycode) = keycode {
+                        if self.keycodes.insert(*keycode) {
+                            self.pressed.insert(*keycode);
+                        }
+                    }
+                    self.scancodes.extend(*scancode);
+                }
+                Event::KeyUp {
+                    keycode, scancode, ..
+                } => {
+                    if let Some(keycode) = keycode {
+                        self.keycodes.remove(keycode);
+                        self.released.insert(*keycode);
+
+                        // whoever took the press takes the release too
+                        if self.consumed_keycodes.remove(keycode) {
+                            self.consumed_releases.insert(*keycode);
+                        }
+                    }
+                    if let Some(scancode) = scancode {
+                        self.scancodes.remove(scancode);
+                        self.consumed_scancodes.remove(scancode);
+                    }
+                }
+                Event::DropFile { filename, .. } => {
+                    self.dropped.push(FileDropped(PathBuf::from(filename)));
+                }
+                Event::ControllerDeviceRemoved { which, .. } => {
+                    self.gamepads.retain(|gamepad| gamepad.instance_id() != *which);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /**
+     * hands over this frame's presses, releases and dropped files, so a
+     * second fixed update in the same frame doesn't see them again
+     */
+    pub(crate) fn take_frame_edges(&mut self) -> FrameEdges {
+        FrameEdges {
+            pressed: std::mem::take(&mut self.pressed),
+            released: std::mem::take(&mut self.released),
+            dropped: std::mem::take(&mut self.dropped),
+        }
+    }
+
+    pub(crate) fn restore_frame_edges(&mut self, edges: FrameEdges) {
+        self.pressed = edges.pressed;
+        self.released = edges.released;
+        self.dropped = edges.dropped;
+    }
+
+    /**
+     * hides the keys of consumed events: a consumed press hides the key until
+     * it's released, along with that release, and a consumed release hides
+     * the release for the rest of the frame
+     */
+    pub(crate) fn consume(&mut self, events: &[Event]) {
+        for event in events {
+            match event {
+                Event::KeyDown {
+                    keycode, scancode, ..
+                } => {
+                    self.consumed_keycodes.extend(*keycode);
+                    self.consumed_scancodes.extend(*scancode);
+                }
+                Event::KeyUp { keycode, .. } => {
+                    self.consumed_releases.extend(*keycode);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    pub fn is_key_down(&self, keycode: Keycode) -> bool {
+        self.keycodes.contains(&keycode) && !self.consumed_keycodes.contains(&keycode)
+    }
+
+    /**
+     * the key went down this frame. held keys and key repeats don't count
+     */
+    pub fn was_pressed(&self, keycode: Keycode) -> bool {
+        self.pressed.contains(&keycode) && !self.consumed_keycodes.contains(&keycode)
+    }
+
+    /**
+     * the key came up this frame
+     */
+    pub fn was_released(&self, keycode: Keycode) -> bool {
+        self.released.contains(&keycode) && !self.consumed_releases.contains(&keycode)
+    }
+
+    /**
+     * use this for movement keys like WASD so they stay in the same place on
+     * AZERTY and other layouts
+     */
+    pub fn is_scancode_down(&self, scancode: Scancode) -> bool {
+        self.scancodes.contains(&scancode) && !self.consumed_scancodes.contains(&scancode)
+    }
+
+    pub fn is_down(&self, key: InputKey) -> bool {
+        match key {
+            InputKey::Keycode(keycode) => self.is_key_down(keycode),
+            InputKey::Scancode(scancode) => self.is_scancode_down(scancode),
+        }
+    }
+
+    /**
+     * files dropped onto the window this frame, in the order they were dropped
+     */
+    pub fn dropped_files(&self) -> &[FileDropped] {
+        &self.dropped
+    }
+
+    /**
+     * starts tracking a gamepad. the window opens them as they are plugged
+     * in, see `QPWindow::open_gamepads`, and they are dropped again when
+     * they are unplugged
+     */
+    pub fn connect_gamepad(&mut self, device: impl RumbleDevice + 'static) {
+        self.gamepads.push(Gamepad::new(device));
+    }
+
+    pub fn gamepads(&self) -> &[Gamepad] {
+        &self.gamepads
+    }
+
+    /**
+     * shakes every gamepad that can, eg. on a hit or when firing. low and
+     * high are the strength of the low and high frequency motors, 0.0 to 1.0.
+     * a new rumble replaces the one playing
+     *
+     * without a gamepad that can rumble this does nothing and says why, it
+     * only errors if SDL fails to start a rumble the pad says it supports
+     */
+    pub fn rumble(&mut self, low: f32, high: f32, duration_ms: u32) -> QPResult<Rumble> {
+        let mut result = Rumble::NoGamepad;
+
+        for gamepad in self.gamepads.iter_mut() {
+            match gamepad.rumble(low, high, duration_ms)? {
+                Rumble::Started => result = Rumble::Started,
+                _ if result == Rumble::NoGamepad => result = Rumble::Unsupported,
+                _ => (),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /**
+     * mouse position in window coordinates
+     */
+    pub fn mouse(&self) -> glm::Vec2 {
+        self.mouse
+    }
+
+    /**
+     * mouse position relative to the game area. see `Viewport::window_to_viewport`
+     */
+    pub fn mouse_in_viewport(&self, viewport: &Viewport) -> glm::Vec2 {
+        viewport.window_to_viewport(&self.mouse)
+    }
 }
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
@@ -65,3 +319,203 @@ pub enum MouseState {
     Pos((f32, f32, f32, f32)), // (x, y, relx, rely)
     Wheel(bool)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdl2::{keyboard::Mod, mouse::MouseButton};
+    use std::{cell::RefCell, rc::Rc};
+
+    fn click(x: i32, y: i32) -> Event {
+        Event::MouseButtonDown {
+            timestamp: 0,
+            window_id: 0,
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x,
+            y,
+        }
+    }
+
+    #[test]
+    fn input_mouse_in_letterboxed_viewport() {
+        // 16:9 in a 4:3 window leaves 75px bars above and below
+        let viewport = Viewport::headless(800, 600, Some(16.0 / 9.0));
+        let mut input = QPInput::new();
+
+        input.update(&[click(400, 30)]);
+        let pos = input.mouse_in_viewport(&viewport);
+        assert_eq!(pos, glm::vec2(400.0, -45.0));
+        assert!(!viewport.contains(&pos));
+
+        input.update(&[click(400, 575)]);
+        assert!(!viewport.contains(&input.mouse_in_viewport(&viewport)));
+
+        input.update(&[click(100, 75)]);
+        let pos = input.mouse_in_viewport(&viewport);
+        assert_eq!(pos, glm::vec2(100.0, 0.0));
+        assert!(viewport.contains(&pos));
+
+        input.update(&[click(799, 524)]);
+        assert_eq!(input.mouse_in_viewport(&viewport), glm::vec2(799.0, 449.0));
+    }
+
+    fn key(down: bool, keycode: Keycode, scancode: Scancode) -> Event {
+        match down {
+            true => Event::KeyDown {
+                timestamp: 0,
+                window_id: 0,
+                keycode: Some(keycode),
+                scancode: Some(scancode),
+                keymod: Mod::NOMOD,
+                repeat: false,
+            },
+            false => Event::KeyUp {
+                timestamp: 0,
+                window_id: 0,
+                keycode: Some(keycode),
+                scancode: Some(scancode),
+                keymod: Mod::NOMOD,
+                repeat: false,
+            },
+        }
+    }
+
+    #[test]
+    fn input_scancode_ignores_layout() {
+        let mut input = QPInput::new();
+
+        // on AZERTY the key in the W position types Z
+        input.update(&[key(true, Keycode::Z, Scancode::W)]);
+
+        assert!(input.is_scancode_down(Scancode::W));
+        assert!(input.is_down(InputKey::Scancode(Scancode::W)));
+        assert!(input.is_key_down(Keycode::Z));
+        assert!(!input.is_key_down(Keycode::W));
+        assert!(!input.is_scancode_down(Scancode::Z));
+
+        input.update(&[key(false, Keycode::Z, Scancode::W)]);
+
+        assert!(!input.is_scancode_down(Scancode::W));
+        assert!(!input.is_down(InputKey::Keycode(Keycode::Z)));
+    }
+
+    #[test]
+    fn input_surfaces_dropped_files() {
+        let drop = |filename: &str| Event::DropFile {
+            timestamp: 0,
+            window_id: 0,
+            filename: filename.to_string(),
+        };
+
+        let mut input = QPInput::new();
+        input.update(&[
+            drop("/home/user/art/ship.png"),
+            click(10, 10),
+            drop("/home/user/scenes/level 1.yaml"),
+        ]);
+
+        assert_eq!(
+            input.dropped_files(),
+            &[
+                FileDropped(PathBuf::from("/home/user/art/ship.png")),
+                FileDropped(PathBuf::from("/home/user/scenes/level 1.yaml")),
+            ]
+        );
+
+        // only for the frame they were dropped in
+        input.update(&[]);
+        assert!(input.dropped_files().is_empty());
+    }
+
+    #[test]
+    fn input_pressed_and_released_last_one_frame() {
+        let mut input = QPInput::new();
+        let space = |down| key(down, Keycode::Space, Scancode::Space);
+        let repeat = Event::KeyDown {
+            timestamp: 0,
+            window_id: 0,
+            keycode: Some(Keycode::Space),
+            scancode: Some(Scancode::Space),
+            keymod: Mod::NOMOD,
+            repeat: true,
+        };
+
+        input.update(&[space(true)]);
+        assert!(input.was_pressed(Keycode::Space));
+        assert!(input.is_key_down(Keycode::Space));
+
+        // still held, with key repeats, but not pressed again
+        input.update(&[repeat]);
+        assert!(!input.was_pressed(Keycode::Space));
+        assert!(input.is_key_down(Keycode::Space));
+        assert!(!input.was_released(Keycode::Space));
+
+        input.update(&[space(false)]);
+        assert!(input.was_released(Keycode::Space));
+        assert!(!input.is_key_down(Keycode::Space));
+
+        input.update(&[]);
+        assert!(!input.was_released(Keycode::Space));
+
+        // a tap inside one frame is both
+        input.update(&[space(true), space(false)]);
+        assert!(input.was_pressed(Keycode::Space));
+        assert!(input.was_released(Keycode::Space));
+        assert!(!input.is_key_down(Keycode::Space));
+    }
+
+    struct MockGamepad {
+        id: u32,
+        can_rumble: bool,
+        rumbles: Rc<RefCell<Vec<(u16, u16, u32)>>>,
+    }
+
+    impl RumbleDevice for MockGamepad {
+        fn instance_id(&self) -> u32 {
+            self.id
+        }
+
+        fn has_rumble(&self) -> bool {
+            self.can_rumble
+        }
+
+        fn set_rumble(&mut self, low: u16, high: u16, duration_ms: u32) -> QPResult<()> {
+            self.rumbles.borrow_mut().push((low, high, duration_ms));
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn rumble_without_capable_gamepad_is_unsupported() {
+        let rumbles = Rc::new(RefCell::new(vec![]));
+        let mut input = QPInput::new();
+        assert_eq!(input.rumble(1.0, 1.0, 100).unwrap(), Rumble::NoGamepad);
+
+        input.connect_gamepad(MockGamepad {
+            id: 3,
+            can_rumble: false,
+            rumbles: rumbles.clone(),
+        });
+        assert_eq!(input.rumble(1.0, 1.0, 100).unwrap(), Rumble::Unsupported);
+        assert!(rumbles.borrow().is_empty());
+
+        input.connect_gamepad(MockGamepad {
+            id: 4,
+            can_rumble: true,
+            rumbles: rumbles.clone(),
+        });
+        assert_eq!(input.rumble(0.5, 2.0, 250).unwrap(), Rumble::Started);
+        assert_eq!(*rumbles.borrow(), vec![(32768, u16::MAX, 250)]);
+
+        // unplugging the one that rumbles leaves nothing to shake
+        input.update(&[Event::ControllerDeviceRemoved {
+            timestamp: 0,
+            which: 4,
+        }]);
+        assert_eq!(input.gamepads().len(), 1);
+        assert_eq!(input.rumble(1.0, 1.0, 100).unwrap(), Rumble::Unsupported);
+    }
+}
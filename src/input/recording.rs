@@ -0,0 +1,405 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::BufReader,
+};
+
+use sdl2::{
+    event::Event,
+    keyboard::{Keycode, Mod, Scancode},
+    mouse::{MouseButton, MouseState},
+};
+
+use crate::{
+    prelude::{qp_core::to_abs_path, QPError, World},
+    QPResult,
+};
+
+/**
+ * bumped whenever the on-disk layout of `InputRecording` changes
+ */
+pub const RECORDING_FORMAT_VERSION: u32 = 1;
+
+/**
+ * recordings only play back on the engine version that made them
+ */
+pub const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/**
+ * the parts of an sdl event that input cares about, in a form that can be
+ * written to disk. keys and buttons are stored as their sdl values
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RecordedEvent {
+    KeyDown {
+        keycode: Option<i32>,
+        scancode: Option<i32>,
+    },
+    KeyUp {
+        keycode: Option<i32>,
+        scancode: Option<i32>,
+    },
+    MouseMotion {
+        x: i32,
+        y: i32,
+        xrel: i32,
+        yrel: i32,
+    },
+    MouseButtonDown {
+        button: u8,
+        x: i32,
+        y: i32,
+    },
+    MouseButtonUp {
+        button: u8,
+        x: i32,
+        y: i32,
+    },
+    Quit,
+}
+
+impl RecordedEvent {
+    /**
+     * events that don't affect input (window, text, etc) are dropped
+     */
+    pub fn from_event(event: &Event) -> Option<Self> {
+        Some(match event {
+            Event::KeyDown {
+                keycode, scancode, ..
+            } => Self::KeyDown {
+                keycode: keycode.map(|k| k as i32),
+                scancode: scancode.map(|s| s as i32),
+            },
+            Event::KeyUp {
+                keycode, scancode, ..
+            } => Self::KeyUp {
+                keycode: keycode.map(|k| k as i32),
+                scancode: scancode.map(|s| s as i32),
+            },
+            Event::MouseMotion {
+                x, y, xrel, yrel, ..
+            } => Self::MouseMotion {
+                x: *x,
+                y: *y,
+                xrel: *xrel,
+                yrel: *yrel,
+            },
+            Event::MouseButtonDown {
+                mouse_btn, x, y, ..
+            } => Self::MouseButtonDown {
+                button: *mouse_btn as u8,
+                x: *x,
+                y: *y,
+            },
+            Event::MouseButtonUp {
+                mouse_btn, x, y, ..
+            } => Self::MouseButtonUp {
+                button: *mouse_btn as u8,
+                x: *x,
+                y: *y,
+            },
+            Event::Quit { .. } => Self::Quit,
+            _ => return None,
+        })
+    }
+
+    pub fn to_event(&self) -> Event {
+        match *self {
+            Self::KeyDown { keycode, scancode } => Event::KeyDown {
+                timestamp: 0,
+                window_id: 0,
+                keycode: keycode.and_then(Keycode::from_i32),
+                scancode: scancode.and_then(Scancode::from_i32),
+                keymod: Mod::NOMOD,
+                repeat: false,
+            },
+            Self::KeyUp { keycode, scancode } => Event::KeyUp {
+                timestamp: 0,
+                window_id: 0,
+                keycode: keycode.and_then(Keycode::from_i32),
+                scancode: scancode.and_then(Scancode::from_i32),
+                keymod: Mod::NOMOD,
+                repeat: false,
+            },
+            Self::MouseMotion { x, y, xrel, yrel } => Event::MouseMotion {
+                timestamp: 0,
+                window_id: 0,
+                which: 0,
+                mousestate: MouseState::from_sdl_state(0),
+                x,
+                y,
+                xrel,
+                yrel,
+            },
+            Self::MouseButtonDown { button, x, y } => Event::MouseButtonDown {
+                timestamp: 0,
+                window_id: 0,
+                which: 0,
+                mouse_btn: MouseButton::from_ll(button),
+                clicks: 1,
+                x,
+                y,
+            },
+            Self::MouseButtonUp { button, x, y } => Event::MouseButtonUp {
+                timestamp: 0,
+                window_id: 0,
+                which: 0,
+                mouse_btn: MouseButton::from_ll(button),
+                clicks: 1,
+                x,
+                y,
+            },
+            Self::Quit => Event::Quit { timestamp: 0 },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    pub delta: f32,
+    pub events: Vec<RecordedEvent>,
+}
+
+/**
+ * every frame's delta and input events for a session, along with the seed
+ * the world was created with. saved as yaml so it can be attached to bug
+ * reports
+ *
+ * ```no_run
+ * # use quipi::{input::InputRecording, prelude::{QPError, World}};
+ * # fn update(world: &mut World, recording: &mut InputRecording) -> Result<(), QPError> {
+ * // once per frame, after `world.new_frame`
+ * recording.record_world(world);
+ *
+ * // when the session ends
+ * recording.save("recordings/crash.yaml")?;
+ * # Ok(())
+ * # }
+ * ```
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputRecording {
+    pub format_version: u32,
+    pub engine_version: String,
+    pub seed: u64,
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl InputRecording {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            format_version: RECORDING_FORMAT_VERSION,
+            engine_version: ENGINE_VERSION.to_string(),
+            seed,
+            frames: vec![],
+        }
+    }
+
+    pub fn record(&mut self, delta: f32, events: &[Event]) {
+        self.frames.push(RecordedFrame {
+            delta,
+            events: events
+                .iter()
+                .filter_map(RecordedEvent::from_event)
+                .collect(),
+        });
+    }
+
+    /**
+     * records the frame the world is currently on
+     */
+    pub fn record_world(&mut self, world: &World) {
+        self.record(world.delta, &world.events);
+    }
+
+    /**
+     * path is relative to the executable
+     */
+    pub fn save(&self, path: &str) -> QPResult<()> {
+        let str = serde_yaml::to_string(self).map_err(|e| QPError::Generic(e.to_string()))?;
+
+        fs::write(to_abs_path(path)?, str)?;
+
+        Ok(())
+    }
+
+    /**
+     * path is relative to the executable. see `validate` before playing it
+     */
+    pub fn load(path: &str) -> QPResult<Self> {
+        let reader = BufReader::new(File::open(to_abs_path(path)?)?);
+
+        serde_yaml::from_reader(reader).map_err(|e| QPError::Generic(e.to_string()))
+    }
+
+    /**
+     * checks the recording can reproduce a session in a world created with
+     * `seed` on this engine version
+     */
+    pub fn validate(&self, seed: u64) -> QPResult<()> {
+        if self.format_version != RECORDING_FORMAT_VERSION {
+            return Err(QPError::RecordingMismatch(format!(
+                "format version {} but expected {}",
+                self.format_version, RECORDING_FORMAT_VERSION
+            )));
+        }
+
+        if self.engine_version != ENGINE_VERSION {
+            return Err(QPError::RecordingMismatch(format!(
+                "recorded on engine {} but this is {}",
+                self.engine_version, ENGINE_VERSION
+            )));
+        }
+
+        if self.seed != seed {
+            return Err(QPError::RecordingMismatch(format!(
+                "recorded with seed {} but the world uses {}",
+                self.seed, seed
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/**
+ * feeds a validated recording back into a world one frame at a time, in
+ * place of the window's events and the frame timer
+ */
+#[derive(Debug)]
+pub struct InputPlayback {
+    recording: InputRecording,
+    next: usize,
+}
+
+impl InputPlayback {
+    pub fn new(recording: InputRecording, seed: u64) -> QPResult<Self> {
+        recording.validate(seed)?;
+
+        Ok(Self { recording, next: 0 })
+    }
+
+    /**
+     * loads and validates a recording in one go
+     */
+    pub fn load(path: &str, seed: u64) -> QPResult<Self> {
+        Self::new(InputRecording::load(path)?, seed)
+    }
+
+    /**
+     * use instead of `world.new_frame`. returns false once every frame has
+     * been played
+     */
+    pub fn next_frame(&mut self, world: &mut World) -> bool {
+        let Some(frame) = self.recording.frames.get(self.next) else {
+            return false;
+        };
+        self.next += 1;
+
+        world.events = frame.events.iter().map(RecordedEvent::to_event).collect();
+        world.input.update(&world.events);
+        world.delta = frame.delta;
+
+        true
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.recording.frames.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::qp_gfx::Viewport;
+
+    const SEED: u64 = 42;
+
+    fn key(scancode: Scancode, down: bool) -> RecordedEvent {
+        let keycode = None;
+        let scancode = Some(scancode as i32);
+
+        match down {
+            true => RecordedEvent::KeyDown { keycode, scancode },
+            false => RecordedEvent::KeyUp { keycode, scancode },
+        }
+    }
+
+    // a tiny game that depends on both input and the world's rng
+    fn step(world: &mut World, pos: &mut glm::Vec2) {
+        if world.input.is_scancode_down(Scancode::W) {
+            pos.y += 100.0 * world.delta;
+        }
+        if world.input.is_scancode_down(Scancode::D) {
+            pos.x += 100.0 * world.delta;
+        }
+
+        pos.x += world.rand.random();
+    }
+
+    fn new_world() -> World {
+        World::new(Viewport::headless(800, 600, None), SEED).unwrap()
+    }
+
+    #[test]
+    fn input_recording_plays_back_to_same_state() {
+        let script = [
+            vec![key(Scancode::W, true)],
+            vec![],
+            vec![key(Scancode::D, true)],
+            vec![key(Scancode::W, false)],
+            vec![],
+            vec![
+                key(Scancode::D, false),
+                RecordedEvent::MouseMotion {
+                    x: 4,
+                    y: 2,
+                    xrel: 4,
+                    yrel: 2,
+                },
+            ],
+        ];
+
+        let mut world = new_world();
+        let mut recording = InputRecording::new(SEED);
+        let mut recorded = glm::vec2(0.0, 0.0);
+        for (i, events) in script.iter().enumerate() {
+            world.events = events.iter().map(RecordedEvent::to_event).collect();
+            world.input.update(&world.events);
+            world.delta = 0.016 + i as f32 * 0.001;
+
+            recording.record_world(&world);
+            step(&mut world, &mut recorded);
+        }
+
+        let path = std::env::temp_dir().join("quipi_input_recording.yaml");
+        let path = path.to_string_lossy();
+        recording.save(&path).unwrap();
+        let loaded = InputRecording::load(&path).unwrap();
+        assert_eq!(loaded, recording);
+
+        assert!(matches!(
+            InputPlayback::new(loaded.clone(), SEED + 1),
+            Err(QPError::RecordingMismatch(_))
+        ));
+
+        let mut world = new_world();
+        let mut playback = InputPlayback::new(loaded, SEED).unwrap();
+        let mut played = glm::vec2(0.0, 0.0);
+        while playback.next_frame(&mut world) {
+            step(&mut world, &mut played);
+        }
+
+        assert!(playback.is_finished());
+        assert_eq!(played, recorded);
+        assert!(played.y > 0.0);
+    }
+
+    #[test]
+    fn input_recording_rejects_other_engine_version() {
+        let mut recording = InputRecording::new(SEED);
+        recording.engine_version = "0.0.0-old".to_string();
+
+        assert!(recording.validate(SEED).is_err());
+    }
+}
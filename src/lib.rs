@@ -12,6 +12,7 @@ pub mod core;
 pub mod ecs;
 pub mod errors;
 pub mod gfx;
+pub mod input;
 pub mod physics;
 pub mod platform;
 pub mod registry;
@@ -24,6 +25,9 @@ mod editor;
 #[cfg(feature = "qp_profiling")]
 mod profiling;
 
+#[cfg(test)]
+mod test_utils;
+
 type QPResult<T> = Result<T, errors::QPError>;
 
 pub mod prelude {
@@ -39,10 +43,12 @@ pub mod prelude {
 
     pub use self::app::App;
     pub use self::app::Controller;
+    pub use self::app::ControllerHandle;
     pub use self::app::FrameResult;
     pub use self::app::Renderer;
     pub use self::errors::QPError;
     pub use self::qp_ecs::EntityBuilder;
+    pub use self::qp_ecs::EntityId;
     pub use self::qp_ecs::VersionedIndex;
     pub use self::registry::GlobalRegistry;
     pub use self::schemas::prelude::Schema;
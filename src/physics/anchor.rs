@@ -0,0 +1,111 @@
+use crate::prelude::{
+    qp_assets::RCamera2D,
+    qp_ecs::components::{CAnchorToEntity, CTransform2D, CWorldTransform2D},
+    qp_gfx::Viewport,
+    GlobalRegistry,
+};
+
+/**
+* moves every anchored element to its target's screen position. elements
+* whose target or camera is gone stay where they are
+*
+* text isn't an entity, so give it an entity with only CAnchorToEntity and
+* CTransform2D and read the text position from that
+*
+* requires the following components:
+* - element: CAnchorToEntity, CTransform2D
+* - target: CTransform2D or CWorldTransform2D
+*/
+pub fn s_anchor_to_entities(registry: &mut GlobalRegistry, viewport: &Viewport) {
+    for entity in registry.entity_manager.query_all::<CAnchorToEntity>() {
+        let Some(anchor) = registry
+            .entity_manager
+            .get::<CAnchorToEntity>(&entity)
+            .copied()
+        else {
+            continue;
+        };
+
+        let em = &registry.entity_manager;
+        let Some(target) = em
+            .get::<CWorldTransform2D>(&anchor.target)
+            .map(|world| world.0.translate)
+            .or_else(|| em.get::<CTransform2D>(&anchor.target).map(|t| t.translate))
+        else {
+            continue;
+        };
+        let Some(camera) = registry.asset_manager.get::<RCamera2D>(anchor.camera) else {
+            continue;
+        };
+
+        let screen = viewport.world_to_screen(camera, &target) + anchor.offset;
+        if let Some(transform) = registry.entity_manager.get_mut::<CTransform2D>(&entity) {
+            transform.translate = screen;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{qp_assets::camera::OrthographicCameraParams, EntityBuilder};
+    use crate::test_utils::registry;
+
+    #[test]
+    fn anchor_follows_world_entity() {
+        let mut registry = registry();
+
+        // the camera is scrolled 100px right and shows 800x600 world units
+        // in a 400x300 viewport
+        let mut camera = RCamera2D::new(
+            OrthographicCameraParams::default(),
+            1.0,
+            CTransform2D::default(),
+        );
+        camera.transform.translate = glm::vec2(100.0, 0.0);
+        camera.view = camera.calc_view_matrix();
+        let camera = registry.asset_manager.load_asset("camera", camera).unwrap();
+        let viewport = Viewport::headless(400, 300, None);
+
+        let enemy = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D {
+                translate: glm::vec2(300.0, 200.0),
+                ..CTransform2D::default()
+            })
+            .build();
+        let health_bar = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D::default())
+            .with(CAnchorToEntity {
+                target: enemy,
+                camera,
+                offset: glm::vec2(0.0, 20.0),
+            })
+            .build();
+
+        let screen_pos = |registry: &GlobalRegistry| {
+            registry
+                .entity_manager
+                .get::<CTransform2D>(&health_bar)
+                .unwrap()
+                .translate
+        };
+
+        s_anchor_to_entities(&mut registry, &viewport);
+        assert!((screen_pos(&registry) - glm::vec2(100.0, 120.0)).magnitude() < 1e-3);
+
+        registry
+            .entity_manager
+            .get_mut::<CTransform2D>(&enemy)
+            .unwrap()
+            .translate += glm::vec2(40.0, -60.0);
+
+        s_anchor_to_entities(&mut registry, &viewport);
+        assert!((screen_pos(&registry) - glm::vec2(120.0, 90.0)).magnitude() < 1e-3);
+
+        // losing the target leaves the element where it was
+        registry.entity_manager.set_to_delete(enemy);
+        registry.flush();
+        s_anchor_to_entities(&mut registry, &viewport);
+        assert!((screen_pos(&registry) - glm::vec2(120.0, 90.0)).magnitude() < 1e-3);
+    }
+}
@@ -0,0 +1,122 @@
+use crate::prelude::{
+    qp_ecs::components::{CSprite, CSpriteAnimation},
+    GlobalRegistry,
+};
+
+/**
+* moves sprite animations along and shows their current frame. animations
+* that run out of frames loop or stop, see `CSpriteAnimation`
+*
+* requires the following components:
+* - CSpriteAnimation
+* - CSprite with a texture atlas
+*/
+pub fn s_animate_sprites(registry: &mut GlobalRegistry, delta: f32) {
+    for entity in registry.entity_manager.query_all::<CSpriteAnimation>() {
+        let Some(animation) = registry.entity_manager.get_mut::<CSpriteAnimation>(&entity) else {
+            continue;
+        };
+
+        if !animation.finished && animation.fps > 0.0 && !animation.frames.is_empty() {
+            animation.elapsed += delta;
+
+            let frame_time = 1.0 / animation.fps;
+            while animation.elapsed >= frame_time {
+                animation.elapsed -= frame_time;
+
+                if animation.frame + 1 < animation.frames.len() {
+                    animation.frame += 1;
+                } else if animation.looping {
+                    animation.frame = 0;
+                } else {
+                    animation.elapsed = 0.0;
+                    animation.finished = true;
+                    break;
+                }
+            }
+        }
+
+        let Some(cell) = animation.current() else {
+            continue;
+        };
+        let Some(atlas) = registry
+            .entity_manager
+            .get_mut::<CSprite>(&entity)
+            .and_then(|sprite| sprite.texture_atlas.as_mut())
+        else {
+            continue;
+        };
+
+        atlas.active_texture = cell;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::{qp_ecs::components::CQuad, EntityBuilder, VersionedIndex},
+        schemas::sprite::TextureAtlas,
+        test_utils::registry,
+    };
+
+    fn animated(registry: &mut GlobalRegistry, looping: bool) -> VersionedIndex {
+        let frames = vec![
+            glm::vec2(0.0, 1.0),
+            glm::vec2(1.0, 1.0),
+            glm::vec2(2.0, 1.0),
+        ];
+        let atlas = TextureAtlas {
+            texture: 0,
+            texture_dims: glm::vec2(4.0, 4.0),
+            active_texture: glm::vec2(3.0, 3.0),
+        };
+
+        EntityBuilder::create(&mut registry.entity_manager)
+            .with(CSprite::new(&CQuad::default(), None, Some(atlas)))
+            .with(CSpriteAnimation::new(frames, 4.0, looping))
+            .build()
+    }
+
+    fn cell(registry: &GlobalRegistry, entity: &VersionedIndex) -> glm::Vec2 {
+        let sprite = registry.entity_manager.get::<CSprite>(entity).unwrap();
+
+        sprite.texture_atlas.as_ref().unwrap().active_texture
+    }
+
+    #[test]
+    fn sprite_animation_loops_or_stops() {
+        let mut registry = registry();
+
+        let looping = animated(&mut registry, true);
+        let once = animated(&mut registry, false);
+
+        // the first frame shows straight away
+        s_animate_sprites(&mut registry, 0.0);
+        assert_eq!(cell(&registry, &looping), glm::vec2(0.0, 1.0));
+
+        let mut seen = vec![];
+        for _ in 0..4 {
+            s_animate_sprites(&mut registry, 0.25);
+            seen.push((cell(&registry, &looping).x, cell(&registry, &once).x));
+        }
+        assert_eq!(seen, vec![(1.0, 1.0), (2.0, 2.0), (0.0, 2.0), (1.0, 2.0)]);
+
+        let em = &registry.entity_manager;
+        assert!(!em.get::<CSpriteAnimation>(&looping).unwrap().finished);
+        assert!(em.get::<CSpriteAnimation>(&once).unwrap().finished);
+
+        // a long frame skips ahead, wrapping around
+        s_animate_sprites(&mut registry, 1.0);
+        assert_eq!(cell(&registry, &looping), glm::vec2(2.0, 1.0));
+        assert_eq!(cell(&registry, &once), glm::vec2(2.0, 1.0));
+
+        registry
+            .entity_manager
+            .get_mut::<CSpriteAnimation>(&once)
+            .unwrap()
+            .restart();
+        s_animate_sprites(&mut registry, 0.25);
+        assert_eq!(cell(&registry, &once), glm::vec2(1.0, 1.0));
+    }
+}
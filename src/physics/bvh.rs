@@ -0,0 +1,314 @@
+use crate::prelude::{
+    qp_ecs::components::{CQuad, CStatic, CTransform2D},
+    GlobalRegistry,
+    VersionedIndex,
+};
+
+use super::collision::obb_corners;
+
+/**
+* axis aligned bounding box
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: glm::Vec2,
+    pub max: glm::Vec2,
+}
+
+impl Aabb {
+    pub fn new(min: glm::Vec2, max: glm::Vec2) -> Self {
+        Self { min, max }
+    }
+
+    /**
+     * the box around a CQuad placed with a CTransform2D, rotation included
+     */
+    pub fn from_quad(transform: &CTransform2D, quad: &CQuad) -> Self {
+        let corners = obb_corners(transform, glm::vec2(quad.width, quad.height) / 2.0);
+
+        corners.iter().skip(1).fold(Self::new(corners[0], corners[0]), |aabb, corner| {
+            aabb.merge(&Self::new(*corner, *corner))
+        })
+    }
+
+    pub fn merge(&self, other: &Aabb) -> Self {
+        Self {
+            min: glm::min2(&self.min, &other.min),
+            max: glm::max2(&self.max, &other.max),
+        }
+    }
+
+    pub fn center(&self) -> glm::Vec2 {
+        (self.min + self.max) / 2.0
+    }
+
+    pub fn contains(&self, point: &glm::Vec2) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x && point.y >= self.min.y && point.y <= self.max.y
+    }
+
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x
+            && self.max.x >= other.min.x
+            && self.min.y <= other.max.y
+            && self.max.y >= other.min.y
+    }
+
+    /**
+     * distance along the ray to where it enters the box, 0.0 when it starts
+     * inside. uses the slab method
+     */
+    pub fn raycast(&self, origin: &glm::Vec2, direction: &glm::Vec2) -> Option<f32> {
+        let mut near = f32::NEG_INFINITY;
+        let mut far = f32::INFINITY;
+
+        for axis in 0..2 {
+            if direction[axis] == 0.0 {
+                if origin[axis] < self.min[axis] || origin[axis] > self.max[axis] {
+                    return None;
+                }
+
+                continue;
+            }
+
+            let t1 = (self.min[axis] - origin[axis]) / direction[axis];
+            let t2 = (self.max[axis] - origin[axis]) / direction[axis];
+
+            near = near.max(t1.min(t2));
+            far = far.min(t1.max(t2));
+        }
+
+        if far < near.max(0.0) {
+            return None;
+        }
+
+        Some(near.max(0.0))
+    }
+}
+
+#[derive(Debug)]
+enum BvhNode {
+    Leaf(Aabb, VersionedIndex),
+    Branch(Aabb, usize, usize),
+}
+
+impl BvhNode {
+    fn bounds(&self) -> &Aabb {
+        match self {
+            BvhNode::Leaf(bounds, _) => bounds,
+            BvhNode::Branch(bounds, _, _) => bounds,
+        }
+    }
+}
+
+/**
+* bounding volume hierarchy over static geometry
+*
+* it's built once, so rebuild it after adding or removing static entities.
+* moving things belong in a broadphase that is rebuilt every frame instead
+*/
+#[derive(Debug, Default)]
+pub struct StaticBvh {
+    nodes: Vec<BvhNode>,
+    root: Option<usize>,
+}
+
+impl StaticBvh {
+    /**
+     * builds over every entity marked CStatic that has a CQuad and a
+     * CTransform2D
+     */
+    pub fn build(registry: &GlobalRegistry) -> Self {
+        let em = &registry.entity_manager;
+        let boxes = em
+            .query_all_in_spawn_order::<CStatic>()
+            .into_iter()
+            .filter_map(|entity| {
+                let transform = em.get::<CTransform2D>(&entity)?;
+                let quad = em.get::<CQuad>(&entity)?;
+
+                Some((entity, Aabb::from_quad(transform, quad)))
+            })
+            .collect();
+
+        Self::from_boxes(boxes)
+    }
+
+    pub fn from_boxes(mut boxes: Vec<(VersionedIndex, Aabb)>) -> Self {
+        let mut bvh = Self::default();
+        if !boxes.is_empty() {
+            bvh.root = Some(bvh.split(&mut boxes));
+        }
+
+        bvh
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| matches!(node, BvhNode::Leaf(..)))
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /**
+     * every entity whose box contains the point
+     */
+    pub fn query_point(&self, point: &glm::Vec2) -> Vec<VersionedIndex> {
+        self.query(|bounds| bounds.contains(point))
+    }
+
+    /**
+     * every entity whose box overlaps the rect
+     */
+    pub fn query_rect(&self, rect: &Aabb) -> Vec<VersionedIndex> {
+        self.query(|bounds| bounds.overlaps(rect))
+    }
+
+    /**
+     * the closest entity hit by the ray within max_distance, with how far
+     * along the ray the hit is
+     */
+    pub fn raycast(
+        &self,
+        origin: &glm::Vec2,
+        direction: &glm::Vec2,
+        max_distance: f32,
+    ) -> Option<(VersionedIndex, f32)> {
+        if direction.magnitude() == 0.0 {
+            return None;
+        }
+        let direction = direction.normalize();
+
+        let mut closest: Option<(VersionedIndex, f32)> = None;
+        let mut stack = Vec::from_iter(self.root);
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let Some(distance) = node.bounds().raycast(origin, &direction) else {
+                continue;
+            };
+
+            let best = closest.map_or(max_distance, |(_, best)| best);
+            if distance > best {
+                continue;
+            }
+
+            match node {
+                BvhNode::Leaf(_, entity) => closest = Some((*entity, distance)),
+                BvhNode::Branch(_, left, right) => stack.extend([*left, *right]),
+            }
+        }
+
+        closest
+    }
+
+    fn query(&self, hit: impl Fn(&Aabb) -> bool) -> Vec<VersionedIndex> {
+        let mut result = vec![];
+
+        let mut stack = Vec::from_iter(self.root);
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !hit(node.bounds()) {
+                continue;
+            }
+
+            match node {
+                BvhNode::Leaf(_, entity) => result.push(*entity),
+                BvhNode::Branch(_, left, right) => stack.extend([*left, *right]),
+            }
+        }
+
+        result
+    }
+
+    // splits along the longest axis at the median center
+    fn split(&mut self, boxes: &mut [(VersionedIndex, Aabb)]) -> usize {
+        if let [(entity, bounds)] = boxes {
+            self.nodes.push(BvhNode::Leaf(*bounds, *entity));
+
+            return self.nodes.len() - 1;
+        }
+
+        let bounds = boxes
+            .iter()
+            .skip(1)
+            .fold(boxes[0].1, |bounds, (_, aabb)| bounds.merge(aabb));
+        let extent = bounds.max - bounds.min;
+        let axis = if extent.x >= extent.y { 0 } else { 1 };
+
+        boxes.sort_by(|(_, a), (_, b)| a.center()[axis].total_cmp(&b.center()[axis]));
+
+        let (left, right) = boxes.split_at_mut(boxes.len() / 2);
+        let left = self.split(left);
+        let right = self.split(right);
+
+        self.nodes.push(BvhNode::Branch(bounds, left, right));
+
+        self.nodes.len() - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::EntityBuilder;
+    use crate::test_utils::registry;
+
+    #[test]
+    fn bvh_point_and_ray_queries() {
+        let mut registry = registry();
+
+        // a row of 10x10 boxes at x = 0, 20, 40, ... and one dynamic box
+        let mut boxes = vec![];
+        for i in 0..6 {
+            let entity = EntityBuilder::create(&mut registry.entity_manager)
+                .with(CStatic)
+                .with(CTransform2D {
+                    translate: glm::vec2(i as f32 * 20.0, 0.0),
+                    ..CTransform2D::default()
+                })
+                .with(CQuad {
+                    width: 10.0,
+                    height: 10.0,
+                    center_x: 0.0,
+                    center_y: 0.0,
+                })
+                .build();
+            boxes.push(entity);
+        }
+        EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D::default())
+            .with(CQuad::default())
+            .build();
+
+        let bvh = StaticBvh::build(&registry);
+        assert_eq!(bvh.len(), 6);
+
+        assert_eq!(bvh.query_point(&glm::vec2(41.0, 4.0)), vec![boxes[2]]);
+        assert!(bvh.query_point(&glm::vec2(50.0, 0.0)).is_empty());
+
+        let mut hits = bvh.query_rect(&Aabb::new(glm::vec2(24.0, -1.0), glm::vec2(56.0, 1.0)));
+        hits.sort_by_key(|entity| entity.to_string());
+        assert_eq!(hits, vec![boxes[1], boxes[2], boxes[3]]);
+
+        // from the left, the first box in the way wins
+        let (entity, distance) = bvh
+            .raycast(&glm::vec2(-100.0, 2.0), &glm::vec2(1.0, 0.0), 1000.0)
+            .unwrap();
+        assert_eq!(entity, boxes[0]);
+        assert_eq!(distance, 95.0);
+
+        // from the right
+        let (entity, distance) = bvh
+            .raycast(&glm::vec2(200.0, 0.0), &glm::vec2(-2.0, 0.0), 1000.0)
+            .unwrap();
+        assert_eq!(entity, boxes[5]);
+        assert_eq!(distance, 95.0);
+
+        // too short, and above the row
+        assert!(bvh.raycast(&glm::vec2(-100.0, 0.0), &glm::vec2(1.0, 0.0), 50.0).is_none());
+        assert!(bvh.raycast(&glm::vec2(-100.0, 20.0), &glm::vec2(1.0, 0.0), 1000.0).is_none());
+    }
+}
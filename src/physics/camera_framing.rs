@@ -0,0 +1,197 @@
+use crate::prelude::{
+    qp_assets::RCamera2D,
+    qp_ecs::components::{CCameraTarget, CTransform2D, CWorldTransform2D},
+    GlobalRegistry,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraFraming {
+    /**
+     * world units kept clear between the outermost targets and the edge of
+     * the frame
+     */
+    pub padding: f32,
+
+    pub min_zoom: f32,
+    pub max_zoom: f32,
+
+    /**
+     * how far the camera moves towards the framing on each call. 1.0 snaps
+     */
+    pub lerp_factor: f32,
+}
+
+impl Default for CameraFraming {
+    fn default() -> Self {
+        Self {
+            padding: 50.0,
+            min_zoom: 0.25,
+            max_zoom: 1.0,
+            lerp_factor: 0.1,
+        }
+    }
+}
+
+/**
+* the center and zoom that keep every (position, weight) target in frame, for
+* a camera that shows size world units at zoom 1. None without targets
+*
+* targets further than min_zoom allows are left out of frame
+*/
+pub fn frame_targets(
+    targets: &[(glm::Vec2, f32)],
+    size: glm::Vec2,
+    framing: &CameraFraming,
+) -> Option<(glm::Vec2, f32)> {
+    let (first, _) = targets.first()?;
+
+    let total_weight = targets
+        .iter()
+        .map(|(_, weight)| weight.max(0.0))
+        .sum::<f32>();
+    let center = match total_weight > 0.0 {
+        true => {
+            targets
+                .iter()
+                .map(|(pos, weight)| pos * weight.max(0.0))
+                .sum::<glm::Vec2>()
+                / total_weight
+        }
+
+        // nothing has weight, so center on the middle of them all
+        false => {
+            let (min, max) = targets
+                .iter()
+                .fold((*first, *first), |(min, max), (pos, _)| {
+                    (glm::min2(&min, pos), glm::max2(&max, pos))
+                });
+
+            (min + max) / 2.0
+        }
+    };
+
+    // the center isn't always the middle, so frame the furthest target on
+    // each side
+    let half_extent = targets
+        .iter()
+        .fold(glm::vec2(0.0, 0.0), |extent, (pos, _)| {
+            glm::max2(&extent, &(pos - center).abs())
+        })
+        + glm::vec2(framing.padding, framing.padding);
+
+    let zoom = (size.x / (2.0 * half_extent.x))
+        .min(size.y / (2.0 * half_extent.y))
+        .clamp(framing.min_zoom, framing.max_zoom);
+
+    Some((center, zoom))
+}
+
+/**
+* moves and zooms the camera towards framing every CCameraTarget. does nothing
+* if there are no targets or the camera isn't loaded
+*
+* requires the following components:
+* - CCameraTarget
+* - CTransform2D or CWorldTransform2D
+*/
+pub fn s_frame_camera_targets(registry: &mut GlobalRegistry, camera: u64, framing: &CameraFraming) {
+    let em = &registry.entity_manager;
+    let targets = em
+        .query_all::<CCameraTarget>()
+        .iter()
+        .filter_map(|entity| {
+            let weight = em.get::<CCameraTarget>(entity)?.weight;
+            let pos = em
+                .get::<CWorldTransform2D>(entity)
+                .map(|world| world.0.translate)
+                .or_else(|| em.get::<CTransform2D>(entity).map(|t| t.translate))?;
+
+            Some((pos, weight))
+        })
+        .collect::<Vec<_>>();
+
+    let Some(camera) = registry.asset_manager.get_mut::<RCamera2D>(camera) else {
+        return;
+    };
+
+    let params = camera.params;
+    let size = glm::vec2(params.right - params.left, params.top - params.bottom);
+    let Some((center, zoom)) = frame_targets(&targets, size, framing) else {
+        return;
+    };
+
+    camera.move_to_frame(center, zoom, framing.lerp_factor);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{qp_assets::camera::OrthographicCameraParams, EntityBuilder};
+    use crate::test_utils::registry;
+
+    fn target(registry: &mut GlobalRegistry, x: f32, y: f32, weight: f32) {
+        EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D {
+                translate: glm::vec2(x, y),
+                ..CTransform2D::default()
+            })
+            .with(CCameraTarget { weight })
+            .build();
+    }
+
+    #[test]
+    fn camera_frames_every_target() {
+        let mut registry = registry();
+
+        let camera = RCamera2D::new(
+            OrthographicCameraParams::default(),
+            1.0,
+            CTransform2D::default(),
+        );
+        let camera = registry.asset_manager.load_asset("camera", camera).unwrap();
+
+        // a ship and a boss further apart than the 800x600 frame
+        let ship = glm::vec2(-300.0, 100.0);
+        let boss = glm::vec2(900.0, 700.0);
+        target(&mut registry, ship.x, ship.y, 1.0);
+        target(&mut registry, boss.x, boss.y, 1.0);
+
+        let framing = CameraFraming {
+            lerp_factor: 1.0,
+            ..CameraFraming::default()
+        };
+        s_frame_camera_targets(&mut registry, camera, &framing);
+
+        let camera = registry.asset_manager.get::<RCamera2D>(camera).unwrap();
+        assert!(camera.zoom < 1.0);
+
+        // the middle of the screen is between them
+        let middle = camera.transform.translate + camera.frame_center();
+        assert!((middle - (ship + boss) / 2.0).magnitude() < 1e-3);
+
+        for pos in [ship, boss] {
+            let clip = camera.projection * camera.view * glm::vec4(pos.x, pos.y, 0.0, 1.0);
+            assert!(
+                clip.x.abs() < 1.0 && clip.y.abs() < 1.0,
+                "{:?} is off screen",
+                pos
+            );
+        }
+    }
+
+    #[test]
+    fn camera_framing_leans_to_heavier_target() {
+        let framing = CameraFraming::default();
+        let size = glm::vec2(800.0, 600.0);
+        let targets = [(glm::vec2(0.0, 0.0), 3.0), (glm::vec2(400.0, 0.0), 1.0)];
+
+        let (center, zoom) = frame_targets(&targets, size, &framing).unwrap();
+        assert_eq!(center, glm::vec2(100.0, 0.0));
+
+        // the far target is 300 away, plus padding, which would fit zoomed in
+        // past max_zoom
+        assert_eq!(zoom, 1.0);
+
+        assert!(frame_targets(&[], size, &framing).is_none());
+    }
+}
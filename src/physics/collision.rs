@@ -0,0 +1,155 @@
+use crate::prelude::{
+    qp_ecs::components::{CQuad, CStatic, CTransform2D},
+    GlobalRegistry,
+    VersionedIndex,
+};
+
+use super::sleep::is_sleeping;
+
+/**
+* corners of an oriented box, in world space
+*
+* `half` is the unscaled half width/height of the box. the transform's scale
+* and rotation are applied on top
+*/
+pub fn obb_corners(transform: &CTransform2D, half: glm::Vec2) -> [glm::Vec2; 4] {
+    let (x_axis, y_axis) = axes(transform);
+    let half = half.component_mul(&transform.scale);
+
+    let x = x_axis * half.x;
+    let y = y_axis * half.y;
+    let center = transform.translate;
+
+    [
+        center + x + y, // top right
+        center + x - y, // bottom right
+        center - x - y, // bottom left
+        center - x + y, // top left
+    ]
+}
+
+/**
+* precise overlap test for rotated boxes, using the separating axis theorem
+*
+* the boxes overlap unless one of their 4 edge normals separates them
+*/
+pub fn s_obb_overlap(
+    a: &CTransform2D,
+    a_half: glm::Vec2,
+    b: &CTransform2D,
+    b_half: glm::Vec2,
+) -> bool {
+    let a_corners = obb_corners(a, a_half);
+    let b_corners = obb_corners(b, b_half);
+    let (a_x, a_y) = axes(a);
+    let (b_x, b_y) = axes(b);
+
+    [a_x, a_y, b_x, b_y].iter().all(|axis| {
+        let (a_min, a_max) = project(&a_corners, axis);
+        let (b_min, b_max) = project(&b_corners, axis);
+
+        a_min <= b_max && b_min <= a_max
+    })
+}
+
+/**
+* everything a per-frame broadphase should be rebuilt from: entities with a
+* CQuad and a CTransform2D that aren't CStatic, CSleeping or CDisabled, in
+* spawn order. static entities go in the StaticBvh instead
+*/
+pub fn dynamic_colliders(registry: &GlobalRegistry) -> Vec<VersionedIndex> {
+    let em = &registry.entity_manager;
+
+    em.query_all_in_spawn_order::<CQuad>()
+        .into_iter()
+        .filter(|entity| em.get::<CTransform2D>(entity).is_some())
+        .filter(|entity| !is_static(registry, entity))
+        .filter(|entity| !is_sleeping(registry, entity))
+        .filter(|entity| !em.is_disabled(entity))
+        .collect()
+}
+
+/**
+* every pair of dynamic colliders whose CQuads overlap, each pair once. it
+* checks every pair, so it suits a few hundred colliders at most
+*/
+pub fn s_find_contacts(registry: &GlobalRegistry) -> Vec<(VersionedIndex, VersionedIndex)> {
+    let em = &registry.entity_manager;
+    let colliders = dynamic_colliders(registry)
+        .into_iter()
+        .filter_map(|entity| {
+            let transform = em.get::<CTransform2D>(&entity)?;
+            let quad = em.get::<CQuad>(&entity)?;
+
+            Some((entity, transform, glm::vec2(quad.width, quad.height) / 2.0))
+        })
+        .collect::<Vec<_>>();
+
+    let mut contacts = vec![];
+    for (i, (a, a_transform, a_half)) in colliders.iter().enumerate() {
+        for (b, b_transform, b_half) in colliders.iter().skip(i + 1) {
+            if s_obb_overlap(a_transform, *a_half, b_transform, *b_half) {
+                contacts.push((*a, *b));
+            }
+        }
+    }
+
+    contacts
+}
+
+pub fn is_static(registry: &GlobalRegistry, entity: &VersionedIndex) -> bool {
+    registry.entity_manager.get::<CStatic>(entity).is_some()
+}
+
+fn axes(transform: &CTransform2D) -> (glm::Vec2, glm::Vec2) {
+    let (sin, cos) = transform.rotate.sin_cos();
+
+    (glm::vec2(cos, sin), glm::vec2(-sin, cos))
+}
+
+fn project(corners: &[glm::Vec2; 4], axis: &glm::Vec2) -> (f32, f32) {
+    corners.iter().fold((f32::MAX, f32::MIN), |(min, max), corner| {
+        let distance = corner.dot(axis);
+
+        (min.min(distance), max.max(distance))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform(x: f32, y: f32, rotate: f32) -> CTransform2D {
+        CTransform2D {
+            translate: glm::vec2(x, y),
+            rotate,
+            ..CTransform2D::default()
+        }
+    }
+
+    #[test]
+    fn obb_overlap_rotated() {
+        let half = glm::vec2(10.0, 10.0);
+        let diamond = transform(0.0, 0.0, glm::quarter_pi());
+
+        // overlaps along the world x axis but the diamond's edge separates them
+        let corner = transform(21.0, 21.0, 0.0);
+        let (min, max) = project(&obb_corners(&corner, half), &glm::vec2(1.0, 0.0));
+        assert!(min < 10.0_f32.hypot(10.0) && max > 0.0);
+        assert!(!s_obb_overlap(&diamond, half, &corner, half));
+
+        let touching = transform(20.0, 0.0, glm::quarter_pi());
+        assert!(s_obb_overlap(&diamond, half, &touching, half));
+    }
+
+    #[test]
+    fn obb_overlap_uses_scale() {
+        let half = glm::vec2(10.0, 10.0);
+        let a = transform(0.0, 0.0, 0.3);
+        let mut b = transform(30.0, 0.0, 1.1);
+        assert!(!s_obb_overlap(&a, half, &b, half));
+
+        b.scale = glm::vec2(2.0, 2.0);
+        assert!(s_obb_overlap(&a, half, &b, half));
+    }
+}
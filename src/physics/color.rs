@@ -0,0 +1,27 @@
+use crate::prelude::{
+    qp_ecs::components::{CColorByValue, CSprite},
+    GlobalRegistry,
+};
+
+/**
+* writes the sampled gradient color of every CColorByValue to its sprite
+*
+* requires the following components:
+* - CColorByValue
+* - CSprite
+*/
+pub fn s_color_by_value(registry: &mut GlobalRegistry) {
+    for entity in registry.entity_manager.query_all::<CColorByValue>() {
+        let Some(color) = registry
+            .entity_manager
+            .get::<CColorByValue>(&entity)
+            .and_then(|cmp| cmp.sample())
+        else {
+            continue;
+        };
+
+        if let Some(sprite) = registry.entity_manager.get_mut::<CSprite>(&entity) {
+            sprite.color = color;
+        }
+    }
+}
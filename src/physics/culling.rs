@@ -0,0 +1,77 @@
+use crate::prelude::{
+    qp_ecs::components::{CQuad, CTransform2D},
+    GlobalRegistry, VersionedIndex,
+};
+
+use super::bvh::Aabb;
+
+/**
+* every entity whose bounds overlap the box, eg. a camera's
+* `visible_bounds` for culling or filling a minimap. entities with a CQuad
+* use the box around it, rotation included, and the rest are a point at
+* their translation
+*
+* requires the following components:
+* - CTransform2D
+* - CQuad (optional)
+*/
+pub fn s_entities_in_bounds(registry: &GlobalRegistry, bounds: &Aabb) -> Vec<VersionedIndex> {
+    let em = &registry.entity_manager;
+
+    em.query_all::<CTransform2D>()
+        .into_iter()
+        .filter(|entity| {
+            let Some(transform) = em.get::<CTransform2D>(entity) else {
+                return false;
+            };
+
+            match em.get::<CQuad>(entity) {
+                Some(quad) => Aabb::from_quad(transform, quad).overlaps(bounds),
+                None => bounds.contains(&transform.translate),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::qp_assets::{camera::OrthographicCameraParams, RCamera2D};
+    use crate::test_utils::{collider, registry};
+
+    #[test]
+    fn entities_in_view_of_moved_zoomed_camera() {
+        let mut registry = registry();
+
+        // 800x600 at zoom 2 shows 400x300 around the middle of the frame,
+        // moved 1000 to the right
+        let params = OrthographicCameraParams {
+            left: 0.0,
+            right: 800.0,
+            bottom: 0.0,
+            top: 600.0,
+            ..OrthographicCameraParams::default()
+        };
+        let transform = CTransform2D {
+            translate: glm::vec2(1000.0, 0.0),
+            ..CTransform2D::default()
+        };
+        let camera = RCamera2D::new(params, 2.0, transform);
+        let bounds = camera.visible_bounds();
+        assert_eq!(bounds.min, glm::vec2(1200.0, 150.0));
+        assert_eq!(bounds.max, glm::vec2(1600.0, 450.0));
+        let camera = registry.asset_manager.load_asset("camera", camera).unwrap();
+
+        let inside = collider(&mut registry, 1400.0, 300.0, 20.0).build();
+        let on_edge = collider(&mut registry, 1195.0, 300.0, 20.0).build();
+        let unzoomed_only = collider(&mut registry, 1100.0, 300.0, 20.0).build();
+        let unmoved_only = collider(&mut registry, 400.0, 300.0, 20.0).build();
+
+        let visible = registry.entities_in_view(camera);
+        assert_eq!(visible, vec![inside, on_edge]);
+        assert!(!visible.contains(&unzoomed_only));
+        assert!(!visible.contains(&unmoved_only));
+
+        assert!(registry.entities_in_view(camera + 1).is_empty());
+    }
+}
@@ -0,0 +1,323 @@
+use crate::prelude::{
+    qp_ecs::components::{
+        CCollisionLayer, CDamageOnContact, CDisabled, CHealth, CInvulnerable, CRespawn,
+        CSprite, CTransform2D, CVelocity2D, DEFAULT_COLLISION_LAYER,
+    },
+    GlobalRegistry,
+    VersionedIndex,
+};
+
+/**
+* takes amount off the entity's health and makes it invulnerable for
+* invulnerable_for seconds
+*
+* returns false when the hit was ignored, either because the entity has no
+* CHealth or because it is still invulnerable
+*/
+pub fn s_apply_damage(
+    registry: &mut GlobalRegistry,
+    entity: &VersionedIndex,
+    amount: f32,
+    invulnerable_for: f32,
+) -> bool {
+    if is_invulnerable(registry, entity) {
+        return false;
+    }
+
+    let Some(health) = registry.entity_manager.get_mut::<CHealth>(entity) else {
+        return false;
+    };
+    health.current = (health.current - amount).max(0.0);
+
+    if invulnerable_for > 0.0 {
+        registry.entity_manager.add(
+            entity,
+            CInvulnerable {
+                remaining: invulnerable_for,
+            },
+        );
+    }
+
+    true
+}
+
+/**
+* counts down invulnerability and removes it once it has expired
+*/
+pub fn s_tick_invulnerable(registry: &mut GlobalRegistry, delta: f32) {
+    for entity in registry.entity_manager.query_all::<CInvulnerable>() {
+        let Some(invulnerable) = registry.entity_manager.get_mut::<CInvulnerable>(&entity) else {
+            continue;
+        };
+        invulnerable.remaining -= delta;
+
+        if invulnerable.remaining <= 0.0 {
+            registry.entity_manager.remove::<CInvulnerable>(&entity);
+        }
+    }
+}
+
+/**
+* applies CDamageOnContact for every reported overlap (eg. from
+* `s_find_contacts`), in both directions. a source with destroy_self is
+* deleted on its first contact with an entity in its layer mask, even if
+* that entity has no health or is invulnerable, and doesn't hit anything else.
+* disabled entities neither deal nor take damage
+*
+* requires the following components:
+* - source: CDamageOnContact
+* - target: CHealth, and optionally CCollisionLayer
+*/
+pub fn s_damage_on_contact(
+    registry: &mut GlobalRegistry,
+    contacts: &[(VersionedIndex, VersionedIndex)],
+) {
+    let mut destroyed = vec![];
+
+    for (a, b) in contacts {
+        for (source, target) in [(a, b), (b, a)] {
+            if destroyed.contains(source) || destroyed.contains(target) {
+                continue;
+            }
+
+            let em = &registry.entity_manager;
+            if em.is_disabled(source) || em.is_disabled(target) {
+                continue;
+            }
+            let Some(damage) = em.get::<CDamageOnContact>(source).copied() else {
+                continue;
+            };
+            let layer = em
+                .get::<CCollisionLayer>(target)
+                .map_or(DEFAULT_COLLISION_LAYER, |layer| layer.0);
+            if damage.layer_mask & layer == 0 {
+                continue;
+            }
+
+            s_apply_damage(registry, target, damage.amount, 0.0);
+
+            if damage.destroy_self {
+                registry.entity_manager.set_to_delete(*source);
+                destroyed.push(*source);
+            }
+        }
+    }
+}
+
+/**
+* disables dead entities that can respawn, so they're hidden and out of
+* collision and contact damage, and brings them back once their delay has
+* run out: moved to the spawn point, stopped, enabled again and with full
+* health. returns the entities that respawned this frame
+*
+* requires the following components:
+* - CRespawn
+* - CHealth
+* - CTransform2D, CVelocity2D and CSprite (optional)
+*/
+pub fn s_respawn(registry: &mut GlobalRegistry, delta: f32) -> Vec<VersionedIndex> {
+    let mut respawned = vec![];
+
+    for entity in registry.entity_manager.query_all::<CRespawn>() {
+        let em = &mut registry.entity_manager;
+        if !em.get::<CHealth>(&entity).is_some_and(|health| health.is_dead()) {
+            continue;
+        }
+
+        let Some(respawn) = em.get_mut::<CRespawn>(&entity) else {
+            continue;
+        };
+        respawn.remaining -= delta;
+        let (spawn_point, ready) = (respawn.spawn_point, respawn.remaining <= 0.0);
+        if ready {
+            respawn.remaining = respawn.delay;
+        }
+
+        if let Some(sprite) = em.get_mut::<CSprite>(&entity) {
+            sprite.skip = !ready;
+        }
+        if !ready {
+            if !em.is_disabled(&entity) {
+                em.add(&entity, CDisabled);
+            }
+
+            continue;
+        }
+        em.remove::<CDisabled>(&entity);
+
+        if let Some(health) = em.get_mut::<CHealth>(&entity) {
+            health.current = health.max;
+        }
+        if let Some(transform) = em.get_mut::<CTransform2D>(&entity) {
+            transform.translate = spawn_point;
+        }
+        if let Some(velocity) = em.get_mut::<CVelocity2D>(&entity) {
+            *velocity = CVelocity2D::default();
+        }
+
+        respawned.push(entity);
+    }
+
+    respawned
+}
+
+pub fn is_invulnerable(registry: &GlobalRegistry, entity: &VersionedIndex) -> bool {
+    registry.entity_manager.get::<CInvulnerable>(entity).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{qp_physics::s_find_contacts, EntityBuilder};
+    use crate::test_utils::{collider, registry};
+
+    #[test]
+    fn damage_ignored_while_invulnerable() {
+        let mut registry = registry();
+
+        let entity = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CHealth::new(3.0))
+            .build();
+
+        assert!(s_apply_damage(&mut registry, &entity, 1.0, 1.0));
+        assert!(!s_apply_damage(&mut registry, &entity, 1.0, 1.0));
+        assert_eq!(registry.entity_manager.get::<CHealth>(&entity).unwrap().current, 2.0);
+
+        s_tick_invulnerable(&mut registry, 0.5);
+        assert!(!s_apply_damage(&mut registry, &entity, 1.0, 1.0));
+
+        s_tick_invulnerable(&mut registry, 0.5);
+        assert!(!is_invulnerable(&registry, &entity));
+
+        assert!(s_apply_damage(&mut registry, &entity, 1.0, 1.0));
+        assert_eq!(registry.entity_manager.get::<CHealth>(&entity).unwrap().current, 1.0);
+    }
+
+    #[test]
+    fn damage_on_contact_hits_and_destroys_bullet() {
+        let mut registry = registry();
+
+        let bullet = collider(&mut registry, 0.0, 0.0, 10.0)
+            .with(CDamageOnContact {
+                amount: 1.0,
+                destroy_self: true,
+                layer_mask: 0b10,
+            })
+            .build();
+        let asteroid = collider(&mut registry, 5.0, 0.0, 10.0)
+            .with(CHealth::new(3.0))
+            .with(CCollisionLayer(0b10))
+            .build();
+
+        // touching the bullet too, but not in its layer mask
+        let ship = collider(&mut registry, -5.0, 0.0, 10.0)
+            .with(CHealth::new(3.0))
+            .build();
+
+        let contacts = s_find_contacts(&registry);
+        assert_eq!(contacts.len(), 3);
+
+        s_damage_on_contact(&mut registry, &contacts);
+        registry.flush();
+
+        let health = |registry: &GlobalRegistry, entity| {
+            registry
+                .entity_manager
+                .get::<CHealth>(&entity)
+                .unwrap()
+                .current
+        };
+        assert_eq!(health(&registry, asteroid), 2.0);
+        assert_eq!(health(&registry, ship), 3.0);
+        assert!(registry
+            .entity_manager
+            .get::<CDamageOnContact>(&bullet)
+            .is_none());
+
+        // the bullet is gone, so nothing gets hit again
+        let contacts = s_find_contacts(&registry);
+        s_damage_on_contact(&mut registry, &contacts);
+        assert_eq!(health(&registry, asteroid), 2.0);
+    }
+
+    #[test]
+    fn respawn_after_delay_with_full_health() {
+        let mut registry = registry();
+
+        let spawn_point = glm::vec2(400.0, 300.0);
+        let ship = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CHealth::new(3.0))
+            .with(CRespawn::new(spawn_point, 2.0))
+            .with(CTransform2D {
+                translate: glm::vec2(20.0, -50.0),
+                ..CTransform2D::default()
+            })
+            .with(CVelocity2D { x: 80.0, y: 10.0 })
+            .with(CSprite::default())
+            .build();
+
+        // alive, so nothing happens
+        assert!(s_respawn(&mut registry, 5.0).is_empty());
+
+        s_apply_damage(&mut registry, &ship, 5.0, 0.0);
+        let em = &registry.entity_manager;
+        assert!(em.get::<CHealth>(&ship).unwrap().is_dead());
+
+        assert!(s_respawn(&mut registry, 1.5).is_empty());
+        let em = &registry.entity_manager;
+        assert!(em.get::<CSprite>(&ship).unwrap().skip);
+        assert!(em.is_disabled(&ship));
+        assert_eq!(em.get::<CTransform2D>(&ship).unwrap().translate, glm::vec2(20.0, -50.0));
+
+        assert_eq!(s_respawn(&mut registry, 0.5), vec![ship]);
+        let em = &registry.entity_manager;
+        assert_eq!(*em.get::<CHealth>(&ship).unwrap(), CHealth::new(3.0));
+        assert_eq!(em.get::<CTransform2D>(&ship).unwrap().translate, spawn_point);
+        assert_eq!(*em.get::<CVelocity2D>(&ship).unwrap(), CVelocity2D::default());
+        assert!(!em.get::<CSprite>(&ship).unwrap().skip);
+        assert!(!em.is_disabled(&ship));
+
+        // the full delay again for the next death
+        assert_eq!(em.get::<CRespawn>(&ship).unwrap().remaining, 2.0);
+    }
+
+    #[test]
+    fn dead_entities_waiting_to_respawn_dont_collide() {
+        let mut registry = registry();
+
+        let ship = collider(&mut registry, 0.0, 0.0, 10.0)
+            .with(CHealth::new(1.0))
+            .with(CRespawn::new(glm::vec2(0.0, 0.0), 2.0))
+            .with(CDamageOnContact {
+                amount: 1.0,
+                destroy_self: false,
+                layer_mask: u32::MAX,
+            })
+            .build();
+        let asteroid = collider(&mut registry, 5.0, 0.0, 10.0)
+            .with(CHealth::new(3.0))
+            .with(CDamageOnContact {
+                amount: 1.0,
+                destroy_self: false,
+                layer_mask: u32::MAX,
+            })
+            .build();
+
+        let contacts = s_find_contacts(&registry);
+        s_damage_on_contact(&mut registry, &contacts);
+        s_respawn(&mut registry, 0.5);
+
+        // dead and waiting, so it's out of the broadphase
+        assert!(s_find_contacts(&registry).is_empty());
+
+        // and a contact reported from elsewhere doesn't hurt either of them
+        s_damage_on_contact(&mut registry, &[(ship, asteroid)]);
+        let em = &registry.entity_manager;
+        assert_eq!(em.get::<CHealth>(&asteroid).unwrap().current, 2.0);
+        assert!(em.get::<CHealth>(&ship).unwrap().is_dead());
+
+        // back after the delay, and colliding again
+        assert_eq!(s_respawn(&mut registry, 1.5), vec![ship]);
+        assert_eq!(s_find_contacts(&registry).len(), 1);
+    }
+}
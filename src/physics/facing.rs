@@ -0,0 +1,70 @@
+use crate::prelude::{
+    qp_ecs::components::{CFaceVelocity, CTransform2D, CVelocity2D},
+    GlobalRegistry,
+};
+
+/**
+* rotates every CFaceVelocity entity so its front (the y-axis, see
+* `CTransform2D::direction`) points the way it's moving. entities slower than
+* min_speed keep their rotation, so they don't spin while stopping
+*
+* requires the following components:
+* - CFaceVelocity
+* - CTransform2D
+* - CVelocity2D
+*/
+pub fn s_face_velocity(registry: &mut GlobalRegistry, min_speed: f32) {
+    for entity in registry.entity_manager.query_all::<CFaceVelocity>() {
+        let Some(velocity) = registry.entity_manager.get::<CVelocity2D>(&entity).copied() else {
+            continue;
+        };
+        if glm::vec2(velocity.x, velocity.y).magnitude() <= min_speed {
+            continue;
+        }
+
+        if let Some(transform) = registry.entity_manager.get_mut::<CTransform2D>(&entity) {
+            transform.rotate = (-velocity.x).atan2(velocity.y);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{EntityBuilder, VersionedIndex};
+    use crate::test_utils::registry;
+
+    fn moving(registry: &mut GlobalRegistry, x: f32, y: f32) -> VersionedIndex {
+        EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D {
+                rotate: 1.0,
+                ..CTransform2D::default()
+            })
+            .with(CVelocity2D { x, y })
+            .with(CFaceVelocity)
+            .build()
+    }
+
+    #[test]
+    fn face_velocity_rotates_to_heading() {
+        let mut registry = registry();
+
+        let up_right = moving(&mut registry, 30.0, 30.0);
+        let drifting = moving(&mut registry, 0.01, -0.01);
+
+        s_face_velocity(&mut registry, 0.1);
+
+        let transform = registry
+            .entity_manager
+            .get::<CTransform2D>(&up_right)
+            .unwrap();
+        assert!((transform.rotate + glm::quarter_pi::<f32>()).abs() < 1e-5);
+        assert!((transform.direction() - glm::vec2(1.0, 1.0).normalize()).magnitude() < 1e-5);
+
+        let transform = registry
+            .entity_manager
+            .get::<CTransform2D>(&drifting)
+            .unwrap();
+        assert_eq!(transform.rotate, 1.0);
+    }
+}
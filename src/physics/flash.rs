@@ -0,0 +1,110 @@
+use crate::prelude::{
+    qp_ecs::components::{CFlash, CSprite},
+    GlobalRegistry,
+    VersionedIndex,
+};
+
+/**
+* seconds between each blink of a flash
+*/
+pub const FLASH_INTERVAL: f32 = 0.1;
+
+/**
+* starts flashing an entity, keeping the original color if it's already
+* mid-flash so it's still restored correctly
+*/
+pub fn s_flash(
+    registry: &mut GlobalRegistry,
+    entity: &VersionedIndex,
+    duration: f32,
+    color: glm::Vec4,
+) {
+    let original = registry
+        .entity_manager
+        .get::<CFlash>(entity)
+        .and_then(|flash| flash.original);
+
+    registry.entity_manager.add(
+        entity,
+        CFlash {
+            original,
+            ..CFlash::new(duration, color)
+        },
+    );
+}
+
+/**
+* blinks flashing sprites and restores their color once the flash is over
+*
+* requires the following components:
+* - CFlash
+* - CSprite
+*/
+pub fn s_update_flash(registry: &mut GlobalRegistry, delta: f32) {
+    for entity in registry.entity_manager.query_all::<CFlash>() {
+        let Some(mut flash) = registry.entity_manager.get::<CFlash>(&entity).copied() else {
+            continue;
+        };
+        let Some(sprite) = registry.entity_manager.get_mut::<CSprite>(&entity) else {
+            continue;
+        };
+
+        let original = *flash.original.get_or_insert(sprite.color);
+        flash.remaining -= delta;
+
+        if flash.remaining <= 0.0 {
+            sprite.color = original;
+            registry.entity_manager.remove::<CFlash>(&entity);
+
+            continue;
+        }
+
+        let elapsed = flash.duration - flash.remaining;
+        sprite.color = match (elapsed / FLASH_INTERVAL) as u32 % 2 {
+            0 => flash.color,
+            _ => original,
+        };
+
+        if let Some(cmp) = registry.entity_manager.get_mut::<CFlash>(&entity) {
+            *cmp = flash;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{qp_ecs::components::CQuad, EntityBuilder};
+    use crate::test_utils::registry;
+
+    #[test]
+    fn flash_blinks_and_restores_color() {
+        let mut registry = registry();
+
+        let blue = glm::vec4(0.0, 0.0, 1.0, 1.0);
+        let red = glm::vec4(1.0, 0.0, 0.0, 1.0);
+
+        let quad = CQuad::default();
+        let entity = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CSprite::new(&quad, Some(blue), None))
+            .build();
+        let color = |registry: &GlobalRegistry| registry.entity_manager.get::<CSprite>(&entity).unwrap().color;
+
+        s_flash(&mut registry, &entity, 0.5, red);
+
+        s_update_flash(&mut registry, FLASH_INTERVAL / 2.0);
+        assert_eq!(color(&registry), red);
+
+        s_update_flash(&mut registry, FLASH_INTERVAL);
+        assert_eq!(color(&registry), blue);
+
+        // flashing again mid-flash still restores the real color
+        s_flash(&mut registry, &entity, 0.5, red);
+        s_update_flash(&mut registry, FLASH_INTERVAL / 2.0);
+        assert_eq!(color(&registry), red);
+
+        s_update_flash(&mut registry, 1.0);
+        assert_eq!(color(&registry), blue);
+        assert!(registry.entity_manager.get::<CFlash>(&entity).is_none());
+    }
+}
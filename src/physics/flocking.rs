@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use crate::prelude::{
+    qp_ecs::components::{CTransform2D, CVelocity2D},
+    GlobalRegistry,
+};
+
+use super::spatial_hash::SpatialHash2D;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlockParams {
+    /**
+     * boids further apart than this ignore each other
+     */
+    pub neighbor_radius: f32,
+
+    /**
+     * boids closer than this push apart
+     */
+    pub separation_distance: f32,
+
+    pub separation_weight: f32,
+    pub alignment_weight: f32,
+    pub cohesion_weight: f32,
+
+    pub max_speed: f32,
+}
+
+impl Default for FlockParams {
+    fn default() -> Self {
+        Self {
+            neighbor_radius: 100.0,
+            separation_distance: 30.0,
+            separation_weight: 1.5,
+            alignment_weight: 1.0,
+            cohesion_weight: 1.0,
+            max_speed: 150.0,
+        }
+    }
+}
+
+/**
+* steers every entity with the given tag using separation, alignment and
+* cohesion against its neighbours, writing the result to CVelocity2D.
+* neighbours are found through a `SpatialHash2D` with cells the size of the
+* neighbor radius, so each boid only looks at the boids around it
+*
+* requires the following components:
+* - CTag
+* - CTransform2D
+* - CVelocity2D
+*/
+pub fn s_flock(registry: &mut GlobalRegistry, tag: &str, params: &FlockParams, delta: f32) {
+    let boids = registry
+        .entity_manager
+        .get_by_tag(tag)
+        .into_iter()
+        .filter_map(|entity| {
+            let position = registry.entity_manager.get::<CTransform2D>(&entity)?.translate;
+            let velocity = registry.entity_manager.get::<CVelocity2D>(&entity)?;
+
+            Some((entity, position, glm::vec2(velocity.x, velocity.y)))
+        })
+        .collect::<Vec<_>>();
+
+    let mut grid = SpatialHash2D::new(params.neighbor_radius);
+    let mut slots = HashMap::new();
+    for (i, (entity, position, _)) in boids.iter().enumerate() {
+        grid.insert_point(*entity, *position);
+        slots.insert(*entity, i);
+    }
+
+    for (entity, position, velocity) in boids.iter() {
+        let mut separation = glm::vec2(0.0, 0.0);
+        let mut heading = glm::vec2(0.0, 0.0);
+        let mut center = glm::vec2(0.0, 0.0);
+        let mut neighbors = 0;
+
+        for other in grid.query_radius(*position, params.neighbor_radius) {
+            if other == *entity {
+                continue;
+            }
+
+            let (_, other_position, other_velocity) = boids[slots[&other]];
+            let offset = position - other_position;
+            let distance = offset.magnitude();
+
+            if distance < params.separation_distance && distance > 0.0 {
+                // the closer the neighbour the harder the push
+                separation += offset / distance * (params.separation_distance - distance);
+            }
+
+            heading += other_velocity;
+            center += other_position;
+            neighbors += 1;
+        }
+
+        if neighbors == 0 {
+            continue;
+        }
+
+        let alignment = heading / neighbors as f32 - velocity;
+        let cohesion = center / neighbors as f32 - position;
+
+        let steer = separation * params.separation_weight
+            + alignment * params.alignment_weight
+            + cohesion * params.cohesion_weight;
+
+        let mut new_velocity = velocity + steer * delta;
+        if new_velocity.magnitude() > params.max_speed {
+            new_velocity = new_velocity.normalize() * params.max_speed;
+        }
+
+        if let Some(cmp) = registry.entity_manager.get_mut::<CVelocity2D>(entity) {
+            cmp.x = new_velocity.x;
+            cmp.y = new_velocity.y;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{qp_ecs::components::CTag, VersionedIndex};
+    use crate::test_utils::{body, registry};
+
+    fn boid(registry: &mut GlobalRegistry, x: f32) -> VersionedIndex {
+        body(registry, x, 0.0)
+            .with(CTag {
+                tag: "boid".to_string(),
+            })
+            .build()
+    }
+
+    #[test]
+    fn flock_close_boids_separate() {
+        let mut registry = registry();
+
+        let left = boid(&mut registry, 0.0);
+        let right = boid(&mut registry, 10.0);
+        let loner = boid(&mut registry, 1000.0);
+
+        // only separation, so cohesion can't pull them back together
+        let params = FlockParams {
+            cohesion_weight: 0.0,
+            ..FlockParams::default()
+        };
+        s_flock(&mut registry, "boid", &params, 0.1);
+
+        let em = &registry.entity_manager;
+        assert!(em.get::<CVelocity2D>(&left).unwrap().x < 0.0);
+        assert!(em.get::<CVelocity2D>(&right).unwrap().x > 0.0);
+        assert_eq!(em.get::<CVelocity2D>(&loner), Some(&CVelocity2D::default()));
+    }
+
+    #[test]
+    fn flock_distant_boids_cohere() {
+        let mut registry = registry();
+
+        let left = boid(&mut registry, 0.0);
+        let right = boid(&mut registry, 80.0);
+
+        // either side of a cell boundary of the neighbour grid
+        let across_left = boid(&mut registry, 5000.0 - 40.0);
+        let across_right = boid(&mut registry, 5000.0 + 40.0);
+
+        s_flock(&mut registry, "boid", &FlockParams::default(), 0.1);
+
+        let em = &registry.entity_manager;
+        assert!(em.get::<CVelocity2D>(&left).unwrap().x > 0.0);
+        assert!(em.get::<CVelocity2D>(&right).unwrap().x < 0.0);
+        assert!(em.get::<CVelocity2D>(&across_left).unwrap().x > 0.0);
+        assert!(em.get::<CVelocity2D>(&across_right).unwrap().x < 0.0);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::prelude::{
+    qp_ecs::components::{CForceField, CTransform2D, CVelocity2D},
+    GlobalRegistry,
+};
+
+use super::collision::is_static;
+
+/**
+* adds each force field's impulse to the velocity of every body in range
+*
+* requires the following components:
+* - field: CForceField, CTransform2D
+* - bodies: CVelocity2D, CTransform2D
+*/
+pub fn s_apply_force_fields(registry: &mut GlobalRegistry, delta: f32) {
+    let fields = registry.entity_manager.query_all::<CForceField>();
+    let bodies = registry.entity_manager.query_all::<CVelocity2D>();
+
+    for field_entity in fields.iter() {
+        let (Some(field), Some(center)) = (
+            registry.entity_manager.get::<CForceField>(field_entity).copied(),
+            registry
+                .entity_manager
+                .get::<CTransform2D>(field_entity)
+                .map(|transform| transform.translate),
+        ) else {
+            continue;
+        };
+
+        for body in bodies.iter() {
+            if body == field_entity || is_static(registry, body) {
+                continue;
+            }
+
+            let Some(transform) = registry.entity_manager.get::<CTransform2D>(body) else {
+                continue;
+            };
+
+            let offset = transform.translate - center;
+            let distance = offset.magnitude();
+            let scale = field.scale(distance);
+            if scale == 0.0 || distance == 0.0 {
+                continue;
+            }
+
+            let impulse = offset / distance * field.strength * scale * delta;
+            if let Some(velocity) = registry.entity_manager.get_mut::<CVelocity2D>(body) {
+                velocity.x += impulse.x;
+                velocity.y += impulse.y;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::EntityBuilder;
+    use crate::test_utils::{body, registry};
+
+    #[test]
+    fn force_field_pushes_bodies_in_range() {
+        let mut registry = registry();
+
+        EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D::default())
+            .with(CForceField {
+                radius: 100.0,
+                strength: 10.0,
+                falloff: 1.0,
+            })
+            .build();
+
+        let near = body(&mut registry, 25.0, 0.0).build();
+        let far = body(&mut registry, 0.0, -75.0).build();
+        let outside = body(&mut registry, 150.0, 0.0).build();
+
+        s_apply_force_fields(&mut registry, 1.0);
+
+        let em = &registry.entity_manager;
+        assert_eq!(em.get::<CVelocity2D>(&near), Some(&CVelocity2D { x: 7.5, y: 0.0 }));
+        assert_eq!(em.get::<CVelocity2D>(&far), Some(&CVelocity2D { x: 0.0, y: -2.5 }));
+        assert_eq!(em.get::<CVelocity2D>(&outside), Some(&CVelocity2D::default()));
+    }
+
+    #[test]
+    fn force_field_negative_strength_pulls() {
+        let mut registry = registry();
+
+        EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D::default())
+            .with(CForceField {
+                radius: 100.0,
+                strength: -10.0,
+                falloff: 0.0,
+            })
+            .build();
+
+        let near = body(&mut registry, 0.0, 50.0).build();
+        s_apply_force_fields(&mut registry, 0.5);
+
+        assert_eq!(
+            registry.entity_manager.get::<CVelocity2D>(&near),
+            Some(&CVelocity2D { x: 0.0, y: -5.0 })
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashSet;
+
+use crate::prelude::{
+    qp_core::trig::rotate2d,
+    qp_ecs::{
+        components::{CAttachPoints, CChildren, CTransform2D, CTransformDirty, CWorldTransform2D},
+        EntityManager,
+    },
+    GlobalRegistry,
+    VersionedIndex,
+};
+
+/**
+* flags an entity so it and its subtree are recomputed on the next propagate
+*/
+pub fn s_mark_transform_dirty(registry: &mut GlobalRegistry, entity: &VersionedIndex) {
+    if registry.entity_manager.get::<CTransformDirty>(entity).is_none() {
+        registry.entity_manager.add(entity, CTransformDirty);
+    }
+}
+
+/**
+* recomputes CWorldTransform2D for dirty entities and everything below them,
+* walking down CChildren from the roots. clean branches keep their cached
+* world transforms. returns how many were recomputed
+*/
+pub fn s_propagate_transforms(registry: &mut GlobalRegistry) -> usize {
+    let children = registry
+        .entity_manager
+        .query_all::<CChildren>()
+        .iter()
+        .filter_map(|entity| registry.entity_manager.get::<CChildren>(entity))
+        .flat_map(|children| children.list.iter())
+        .filter_map(|id| registry.entity_manager.resolve(*id))
+        .collect::<HashSet<_>>();
+
+    let mut recomputed = 0;
+    for root in registry.entity_manager.query_all::<CWorldTransform2D>() {
+        if !children.contains(&root) {
+            recomputed += propagate(registry, &root, None, false);
+        }
+    }
+
+    recomputed
+}
+
+/**
+* `parent` applied on top of a local transform
+*/
+pub fn compose(parent: &CTransform2D, local: &CTransform2D) -> CTransform2D {
+    let offset = rotate2d(&local.translate.component_mul(&parent.scale), parent.rotate);
+
+    CTransform2D {
+        translate: parent.translate + offset,
+        rotate: parent.rotate + local.rotate,
+        scale: parent.scale.component_mul(&local.scale),
+    }
+}
+
+/**
+* where the entity is in the world: the cached CWorldTransform2D when it has
+* one, otherwise its CTransform2D. entities outside a hierarchy don't need a
+* world transform, their local one already is
+*/
+pub fn world_transform<'a>(em: &'a EntityManager, entity: &VersionedIndex) -> Option<&'a CTransform2D> {
+    em.get::<CWorldTransform2D>(entity)
+        .map(|world| &world.0)
+        .or_else(|| em.get::<CTransform2D>(entity))
+}
+
+/**
+* the entity's named attach point in world space. uses the cached world
+* transform when the entity has one
+*/
+pub fn attach_point(registry: &GlobalRegistry, entity: &VersionedIndex, name: &str) -> Option<glm::Vec2> {
+    let em = &registry.entity_manager;
+    let transform = world_transform(em, entity)?;
+
+    em.get::<CAttachPoints>(entity)?.resolve(name, transform)
+}
+
+fn propagate(
+    registry: &mut GlobalRegistry,
+    entity: &VersionedIndex,
+    parent: Option<&CTransform2D>,
+    parent_dirty: bool,
+) -> usize {
+    let mut recomputed = 0;
+
+    let dirty = parent_dirty || registry.entity_manager.get::<CTransformDirty>(entity).is_some();
+    if dirty {
+        let local = registry
+            .entity_manager
+            .get::<CTransform2D>(entity)
+            .copied()
+            .unwrap_or_default();
+        let world = match parent {
+            Some(parent) => compose(parent, &local),
+            None => local,
+        };
+
+        if let Some(cached) = registry.entity_manager.get_mut::<CWorldTransform2D>(entity) {
+            cached.0 = world;
+            recomputed += 1;
+        }
+        registry.entity_manager.remove::<CTransformDirty>(entity);
+    }
+
+    let Some(world) = registry
+        .entity_manager
+        .get::<CWorldTransform2D>(entity)
+        .map(|world| world.0)
+    else {
+        return recomputed;
+    };
+
+    let children = registry
+        .entity_manager
+        .get::<CChildren>(entity)
+        .map(|children| children.list.clone())
+        .unwrap_or_default();
+
+    for child in children.iter() {
+        let Some(child) = registry.entity_manager.resolve(*child) else {
+            continue;
+        };
+
+        recomputed += propagate(registry, &child, Some(&world), dirty);
+    }
+
+    recomputed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::EntityBuilder;
+    use crate::test_utils::registry;
+
+    fn node(registry: &mut GlobalRegistry, x: f32, children: Vec<VersionedIndex>) -> VersionedIndex {
+        let children = children
+            .iter()
+            .filter_map(|child| registry.entity_manager.entity_id(child))
+            .collect();
+
+        EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D {
+                translate: glm::vec2(x, 0.0),
+                ..CTransform2D::default()
+            })
+            .with(CWorldTransform2D::default())
+            .with(CTransformDirty)
+            .with(CChildren { list: children })
+            .build()
+    }
+
+    fn world_x(registry: &GlobalRegistry, entity: &VersionedIndex) -> f32 {
+        registry
+            .entity_manager
+            .get::<CWorldTransform2D>(entity)
+            .unwrap()
+            .0
+            .translate
+            .x
+    }
+
+    #[test]
+    fn propagate_only_dirty_branches() {
+        let mut registry = registry();
+
+        let grandchild = node(&mut registry, 1.0, vec![]);
+        let child = node(&mut registry, 10.0, vec![grandchild]);
+        let root = node(&mut registry, 100.0, vec![child]);
+
+        let other_child = node(&mut registry, 20.0, vec![]);
+        let other_root = node(&mut registry, 200.0, vec![other_child]);
+
+        assert_eq!(s_propagate_transforms(&mut registry), 5);
+        assert_eq!(world_x(&registry, &grandchild), 111.0);
+        assert_eq!(world_x(&registry, &other_child), 220.0);
+
+        // nothing moved
+        assert_eq!(s_propagate_transforms(&mut registry), 0);
+
+        // poison the other subtree's cache to prove it isn't recomputed
+        registry
+            .entity_manager
+            .get_mut::<CWorldTransform2D>(&other_child)
+            .unwrap()
+            .0
+            .translate
+            .x = -1.0;
+
+        registry.entity_manager.get_mut::<CTransform2D>(&root).unwrap().translate.x = 50.0;
+        s_mark_transform_dirty(&mut registry, &root);
+
+        assert_eq!(s_propagate_transforms(&mut registry), 3);
+        assert_eq!(world_x(&registry, &root), 50.0);
+        assert_eq!(world_x(&registry, &child), 60.0);
+        assert_eq!(world_x(&registry, &grandchild), 61.0);
+        assert_eq!(world_x(&registry, &other_root), 200.0);
+        assert_eq!(world_x(&registry, &other_child), -1.0);
+    }
+
+    #[test]
+    fn compose_rotates_and_scales_child_offset() {
+        let parent = CTransform2D {
+            translate: glm::vec2(5.0, 5.0),
+            rotate: glm::half_pi(),
+            scale: glm::vec2(2.0, 2.0),
+        };
+        let local = CTransform2D {
+            translate: glm::vec2(1.0, 0.0),
+            ..CTransform2D::default()
+        };
+
+        let world = compose(&parent, &local);
+
+        assert!((world.translate - glm::vec2(5.0, 7.0)).magnitude() < 1e-5);
+        assert_eq!(world.rotate, glm::half_pi());
+        assert_eq!(world.scale, glm::vec2(2.0, 2.0));
+    }
+}
@@ -0,0 +1,126 @@
+use crate::prelude::{
+    qp_ecs::components::{CHoming, CTransform2D, CVelocity2D},
+    GlobalRegistry,
+};
+
+use super::steering::turn_towards;
+
+/**
+* turns every homing entity's velocity towards its target, no faster than its
+* turn rate. when the target is gone it carries on in a straight line
+*
+* requires the following components:
+* - homing: CHoming, CTransform2D, CVelocity2D
+* - target: CTransform2D
+*/
+pub fn s_homing(registry: &mut GlobalRegistry, delta: f32) {
+    for entity in registry.entity_manager.query_all::<CHoming>() {
+        let (Some(homing), Some(transform), Some(velocity)) = (
+            registry.entity_manager.get::<CHoming>(&entity).copied(),
+            registry.entity_manager.get::<CTransform2D>(&entity),
+            registry.entity_manager.get::<CVelocity2D>(&entity),
+        ) else {
+            continue;
+        };
+
+        // a missile that hasn't been launched yet points where it's facing
+        let heading = match glm::vec2(velocity.x, velocity.y) {
+            heading if heading.magnitude() > 0.0 => heading.normalize(),
+            _ => transform.direction(),
+        };
+
+        let heading = match registry.entity_manager.get::<CTransform2D>(&homing.target) {
+            Some(target) => turn_towards(
+                &heading,
+                &(target.translate - transform.translate),
+                homing.turn_rate * delta,
+            ),
+            None => heading,
+        };
+
+        if let Some(velocity) = registry.entity_manager.get_mut::<CVelocity2D>(&entity) {
+            velocity.x = heading.x * homing.speed;
+            velocity.y = heading.y * homing.speed;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{qp_physics::s_apply_velocity_2d, VersionedIndex};
+    use crate::test_utils::{at, registry};
+
+    fn missile(registry: &mut GlobalRegistry, target: VersionedIndex) -> VersionedIndex {
+        at(registry, 0.0, 0.0)
+            .with(CVelocity2D { x: 50.0, y: 0.0 })
+            .with(CHoming {
+                target,
+                turn_rate: 1.0,
+                speed: 50.0,
+            })
+            .build()
+    }
+
+    fn heading(registry: &GlobalRegistry, entity: &VersionedIndex) -> f32 {
+        let velocity = registry.entity_manager.get::<CVelocity2D>(entity).unwrap();
+
+        velocity.y.atan2(velocity.x)
+    }
+
+    fn bearing(registry: &GlobalRegistry, from: &VersionedIndex, to: &VersionedIndex) -> f32 {
+        let from = registry.entity_manager.get::<CTransform2D>(from).unwrap();
+        let to = registry.entity_manager.get::<CTransform2D>(to).unwrap();
+        let offset = to.translate - from.translate;
+
+        offset.y.atan2(offset.x)
+    }
+
+    #[test]
+    fn homing_converges_within_turn_rate() {
+        let mut registry = registry();
+
+        let target = at(&mut registry, 0.0, 200.0).build();
+        let entity = missile(&mut registry, target);
+
+        let delta = 0.1;
+        let mut error = (bearing(&registry, &entity, &target) - heading(&registry, &entity)).abs();
+        for _ in 0..30 {
+            let before = heading(&registry, &entity);
+
+            s_homing(&mut registry, delta);
+            s_apply_velocity_2d(&mut registry, delta);
+
+            let turned = (heading(&registry, &entity) - before).abs();
+            assert!(turned <= 1.0 * delta + 1e-5);
+
+            let velocity = registry.entity_manager.get::<CVelocity2D>(&entity).unwrap();
+            assert!((glm::vec2(velocity.x, velocity.y).magnitude() - 50.0).abs() < 1e-3);
+
+            let next = (bearing(&registry, &entity, &target) - heading(&registry, &entity)).abs();
+            assert!(next <= error + 1e-5);
+            error = next;
+        }
+
+        assert!(error < 0.05);
+    }
+
+    #[test]
+    fn homing_keeps_heading_when_target_deleted() {
+        let mut registry = registry();
+
+        let target = at(&mut registry, 0.0, 200.0).build();
+        let entity = missile(&mut registry, target);
+
+        s_homing(&mut registry, 0.1);
+        let before = heading(&registry, &entity);
+
+        registry.entity_manager.set_to_delete(target);
+        registry.flush();
+
+        for _ in 0..5 {
+            s_homing(&mut registry, 0.1);
+        }
+        assert_eq!(heading(&registry, &entity), before);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::prelude::{
+    qp_ecs::components::{CTimeToLive, CTransform2D},
+    GlobalRegistry, Schema, VersionedIndex,
+};
+use crate::QPResult;
+
+use super::hierarchy::world_transform;
+
+/**
+* counts down every CTimeToLive and deletes the entities that run out,
+* building their prefab first. children of a hierarchy spawn it at their
+* world position. returns the entities that were spawned
+*
+* a prefab that fails to build doesn't stop the other countdowns. its entity
+* is still deleted, and the first such error is returned once every
+* CTimeToLive has been counted down
+*
+* requires the following components:
+* - CTimeToLive
+* - CTransform2D (optional, without one the prefab keeps its own transform)
+*/
+pub fn s_time_to_live(registry: &mut GlobalRegistry, delta: f32) -> QPResult<Vec<VersionedIndex>> {
+    let mut spawned = vec![];
+    let mut failed = None;
+
+    for entity in registry.entity_manager.query_all::<CTimeToLive>() {
+        let em = &mut registry.entity_manager;
+        if em.is_pending_delete(&entity) {
+            continue;
+        }
+
+        let Some(ttl) = em.get_mut::<CTimeToLive>(&entity) else {
+            continue;
+        };
+        ttl.remaining -= delta;
+        if ttl.remaining > 0.0 {
+            continue;
+        }
+
+        let prefab = ttl.prefab.take();
+        let transform = world_transform(em, &entity).copied();
+        em.set_to_delete(entity);
+
+        let Some(mut prefab) = prefab else {
+            continue;
+        };
+        if let Some(CTransform2D {
+            translate, rotate, ..
+        }) = transform
+        {
+            prefab.transform.translate = translate;
+            prefab.transform.rotate = rotate;
+        }
+
+        match prefab.build_entity(registry) {
+            Ok(spawned_entity) => spawned.push(spawned_entity),
+            Err(e) => {
+                failed.get_or_insert(e);
+            }
+        }
+    }
+
+    match failed {
+        Some(e) => Err(e),
+        None => Ok(spawned),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{
+        qp_ecs::components::CTag,
+        qp_schemas::{SchemaSprite, SchemaTextureAtlas},
+        EntityBuilder,
+    };
+    use crate::test_utils::registry;
+
+    #[test]
+    fn time_to_live_spawns_prefab_on_expiry() {
+        let mut registry = registry();
+
+        let burst = SchemaSprite {
+            tag: "burst".to_string(),
+            ..SchemaSprite::default()
+        };
+        let flare = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D {
+                translate: glm::vec2(10.0, 0.0),
+                rotate: 0.5,
+                ..CTransform2D::default()
+            })
+            .with(CTimeToLive::new(1.0).with_prefab(burst))
+            .build();
+
+        let fly = |registry: &mut GlobalRegistry| {
+            let em = &mut registry.entity_manager;
+            em.get_mut::<CTransform2D>(&flare).unwrap().translate += glm::vec2(2.0, 1.0);
+        };
+
+        // still flying
+        for _ in 0..3 {
+            fly(&mut registry);
+            assert!(s_time_to_live(&mut registry, 0.25).unwrap().is_empty());
+        }
+        assert!(!registry.entity_manager.is_pending_delete(&flare));
+
+        fly(&mut registry);
+        let spawned = s_time_to_live(&mut registry, 0.25).unwrap();
+        assert_eq!(spawned.len(), 1);
+        assert!(registry.entity_manager.is_pending_delete(&flare));
+
+        // where the flare ended up, not where it started
+        let em = &registry.entity_manager;
+        let transform = em.get::<CTransform2D>(&spawned[0]).unwrap();
+        assert_eq!(transform.translate, glm::vec2(18.0, 4.0));
+        assert_eq!(transform.rotate, 0.5);
+        assert_eq!(em.get::<CTag>(&spawned[0]).unwrap().tag, "burst");
+
+        // gone for good, nothing more spawns
+        registry.update_deletions();
+        assert!(s_time_to_live(&mut registry, 1.0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn time_to_live_returns_prefab_errors() {
+        let mut registry = registry();
+
+        let broken = SchemaSprite {
+            texture: Some(SchemaTextureAtlas::new("missing")),
+            ..SchemaSprite::default()
+        };
+        let flare = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTimeToLive::new(0.5).with_prefab(broken))
+            .build();
+        let other = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTimeToLive::new(1.0))
+            .build();
+
+        assert!(s_time_to_live(&mut registry, 0.75).is_err());
+
+        // the flare still expires, and the error doesn't hold up the others
+        let em = &registry.entity_manager;
+        assert!(em.is_pending_delete(&flare));
+        assert_eq!(em.get::<CTimeToLive>(&other).unwrap().remaining, 0.25);
+    }
+}
@@ -0,0 +1,88 @@
+use crate::prelude::{
+    qp_ecs::components::{CMagnet, CTransform2D, CVelocity2D},
+    GlobalRegistry,
+};
+
+/**
+* accelerates every magnet towards its nearest target in range
+*
+* requires the following components:
+* - magnet: CMagnet, CTransform2D, CVelocity2D
+* - targets: CTag, CTransform2D
+*/
+pub fn s_apply_magnets(registry: &mut GlobalRegistry, delta: f32) {
+    for entity in registry.entity_manager.query_all::<CMagnet>() {
+        let (Some(magnet), Some(position)) = (
+            registry.entity_manager.get::<CMagnet>(&entity).cloned(),
+            registry
+                .entity_manager
+                .get::<CTransform2D>(&entity)
+                .map(|transform| transform.translate),
+        ) else {
+            continue;
+        };
+
+        let nearest = registry
+            .entity_manager
+            .get_by_tag(&magnet.target_tag)
+            .iter()
+            .filter(|target| **target != entity)
+            .filter_map(|target| registry.entity_manager.get::<CTransform2D>(target))
+            .map(|transform| transform.translate - position)
+            .filter(|offset| offset.magnitude() <= magnet.radius)
+            .min_by(|a, b| a.magnitude().total_cmp(&b.magnitude()));
+
+        let Some(offset) = nearest else {
+            continue;
+        };
+        if offset.magnitude() == 0.0 {
+            continue;
+        }
+
+        let impulse = offset.normalize() * magnet.strength * delta;
+        if let Some(velocity) = registry.entity_manager.get_mut::<CVelocity2D>(&entity) {
+            velocity.x += impulse.x;
+            velocity.y += impulse.y;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{qp_ecs::components::CTag, VersionedIndex};
+    use crate::test_utils::{at, registry};
+
+    fn pickup(registry: &mut GlobalRegistry, x: f32, y: f32) -> VersionedIndex {
+        at(registry, x, y)
+            .with(CVelocity2D::default())
+            .with(CMagnet {
+                target_tag: "ship".to_string(),
+                radius: 100.0,
+                strength: 10.0,
+            })
+            .build()
+    }
+
+    #[test]
+    fn magnet_pulls_towards_nearest_target_in_range() {
+        let mut registry = registry();
+
+        for (x, y) in [(0.0, 0.0), (0.0, 300.0)] {
+            at(&mut registry, x, y)
+                .with(CTag {
+                    tag: "ship".to_string(),
+                })
+                .build();
+        }
+
+        let near = pickup(&mut registry, 0.0, 220.0);
+        let outside = pickup(&mut registry, 500.0, 0.0);
+
+        s_apply_magnets(&mut registry, 0.5);
+
+        let em = &registry.entity_manager;
+        assert_eq!(em.get::<CVelocity2D>(&near), Some(&CVelocity2D { x: 0.0, y: 5.0 }));
+        assert_eq!(em.get::<CVelocity2D>(&outside), Some(&CVelocity2D::default()));
+    }
+}
@@ -1,9 +1,57 @@
+pub mod anchor;
+pub mod animation;
+pub mod bvh;
+pub mod camera_framing;
+pub mod collision;
+pub mod color;
+pub mod culling;
+pub mod damage;
+pub mod facing;
+pub mod flash;
+pub mod flocking;
+pub mod forces;
+pub mod hierarchy;
+pub mod homing;
+pub mod lifetime;
+pub mod magnet;
 pub mod movement;
+pub mod patrol;
 pub mod rotation;
+pub mod shatter;
+pub mod sleep;
+pub mod spatial_hash;
+pub mod steering;
+pub mod velocity;
+pub mod waves;
+pub mod weapon;
 
 pub mod prelude {
     use super::*;
 
+    pub use anchor::*;
+    pub use animation::*;
+    pub use bvh::*;
+    pub use camera_framing::*;
+    pub use collision::*;
+    pub use color::*;
+    pub use culling::*;
+    pub use damage::*;
+    pub use facing::*;
+    pub use flash::*;
+    pub use flocking::*;
+    pub use forces::*;
+    pub use hierarchy::*;
+    pub use homing::*;
+    pub use lifetime::*;
+    pub use magnet::*;
     pub use movement::*;
+    pub use patrol::*;
     pub use rotation::*;
+    pub use shatter::*;
+    pub use sleep::*;
+    pub use spatial_hash::*;
+    pub use steering::*;
+    pub use velocity::*;
+    pub use waves::*;
+    pub use weapon::*;
 }
\ No newline at end of file
@@ -0,0 +1,125 @@
+use crate::prelude::{
+    qp_ecs::components::{CPatrol, CTransform2D, CVelocity2D},
+    GlobalRegistry,
+};
+
+/**
+* how close an entity has to get to a waypoint before moving on to the next
+*/
+pub const PATROL_THRESHOLD: f32 = 2.0;
+
+/**
+* points each patrolling entity's velocity at its current waypoint
+*
+* requires the following components:
+* - CPatrol
+* - CTransform2D
+* - CVelocity2D
+*/
+pub fn s_patrol(registry: &mut GlobalRegistry) {
+    for entity in registry.entity_manager.query_all::<CPatrol>() {
+        let Some(position) = registry
+            .entity_manager
+            .get::<CTransform2D>(&entity)
+            .map(|transform| transform.translate)
+        else {
+            continue;
+        };
+
+        let Some(patrol) = registry.entity_manager.get_mut::<CPatrol>(&entity) else {
+            continue;
+        };
+
+        // skip past every waypoint already in reach, at most one lap so
+        // overlapping waypoints can't spin forever
+        let mut velocity = glm::vec2(0.0, 0.0);
+        for _ in 0..patrol.waypoints.len() {
+            let Some(target) = patrol.target() else {
+                break;
+            };
+            let offset = target - position;
+
+            if offset.magnitude() > PATROL_THRESHOLD {
+                velocity = offset.normalize() * patrol.speed;
+
+                break;
+            }
+
+            patrol.current += 1;
+            if patrol.is_done() && patrol.looping {
+                patrol.current = 0;
+            }
+        }
+
+        if let Some(cmp) = registry.entity_manager.get_mut::<CVelocity2D>(&entity) {
+            cmp.x = velocity.x;
+            cmp.y = velocity.y;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{qp_physics::s_apply_velocity_2d, EntityBuilder, VersionedIndex};
+    use crate::test_utils::registry;
+
+    fn patroller(registry: &mut GlobalRegistry, looping: bool) -> VersionedIndex {
+        let waypoints = vec![
+            glm::vec2(10.0, 0.0),
+            glm::vec2(10.0, 10.0),
+            glm::vec2(0.0, 0.0),
+        ];
+
+        EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D::default())
+            .with(CVelocity2D::default())
+            .with(CPatrol::new(waypoints, 10.0, looping))
+            .build()
+    }
+
+    // steps the simulation and records every waypoint index change
+    fn run(registry: &mut GlobalRegistry, entity: &VersionedIndex, steps: usize) -> Vec<usize> {
+        let mut visited = vec![0];
+
+        for _ in 0..steps {
+            s_patrol(registry);
+            s_apply_velocity_2d(registry, 0.05);
+
+            let current = registry.entity_manager.get::<CPatrol>(entity).unwrap().current;
+            if visited.last() != Some(&current) {
+                visited.push(current);
+            }
+        }
+
+        visited
+    }
+
+    #[test]
+    fn patrol_loops_through_waypoints() {
+        let mut registry = registry();
+        let entity = patroller(&mut registry, true);
+
+        let visited = run(&mut registry, &entity, 200);
+
+        assert_eq!(visited[..7], [0, 1, 2, 0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn patrol_stops_at_the_end() {
+        let mut registry = registry();
+        let entity = patroller(&mut registry, false);
+
+        let visited = run(&mut registry, &entity, 200);
+
+        assert_eq!(visited, [0, 1, 2, 3]);
+        assert!(registry.entity_manager.get::<CPatrol>(&entity).unwrap().is_done());
+        assert_eq!(
+            registry.entity_manager.get::<CVelocity2D>(&entity),
+            Some(&CVelocity2D::default())
+        );
+
+        let position = registry.entity_manager.get::<CTransform2D>(&entity).unwrap().translate;
+        assert!(position.magnitude() <= PATROL_THRESHOLD);
+    }
+}
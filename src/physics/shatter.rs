@@ -0,0 +1,287 @@
+use crate::{
+    prelude::{
+        qp_core::trig::rotate2d,
+        qp_ecs::components::{CQuad, CShard, CSprite, CTag, CTransform2D, CVelocity2D},
+        EntityBuilder, GlobalRegistry, VersionedIndex,
+    },
+    schemas::sprite::TextureAtlas,
+};
+
+use super::hierarchy::world_transform;
+
+/**
+* how `s_shatter` breaks a sprite up. the quad is cut into a grid of cols by
+* rows cells and every cell into two triangles, so there are
+* `cols * rows * 2` shards
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShatterParams {
+    pub cols: usize,
+    pub rows: usize,
+
+    /**
+     * how fast the shards fly away from the center
+     */
+    pub speed: f32,
+
+    /**
+     * radians per second. the two halves of a cell spin opposite ways
+     */
+    pub spin: f32,
+
+    /**
+     * seconds until the shards have faded out and are deleted
+     */
+    pub lifetime: f32,
+}
+
+impl Default for ShatterParams {
+    fn default() -> Self {
+        Self {
+            cols: 2,
+            rows: 2,
+            speed: 60.0,
+            spin: 3.0,
+            lifetime: 0.6,
+        }
+    }
+}
+
+impl ShatterParams {
+    pub fn shard_count(&self) -> usize {
+        self.cols * self.rows * 2
+    }
+}
+
+/**
+* breaks the sprite into triangle shards that fly outwards, spin and fade,
+* see `s_update_shards`. each shard shows its own slice of the sprite's
+* texture, and they all keep the sprite's velocity on top of their own. the
+* sprite itself is left alone, delete or hide it yourself
+*
+* returns the shards, none if the entity isn't a sprite
+*
+* requires the following components:
+* - CSprite
+* - CQuad
+* - CTransform2D or CWorldTransform2D
+*/
+pub fn s_shatter(
+    registry: &mut GlobalRegistry,
+    entity: &VersionedIndex,
+    params: &ShatterParams,
+) -> Vec<VersionedIndex> {
+    let em = &registry.entity_manager;
+    let (Some(sprite), Some(quad), Some(transform)) = (
+        em.get::<CSprite>(entity),
+        em.get::<CQuad>(entity),
+        world_transform(em, entity),
+    ) else {
+        return vec![];
+    };
+    let (sprite, quad, transform) = (sprite.clone(), quad.clone(), *transform);
+    let inherited = em.get::<CVelocity2D>(entity).copied().unwrap_or_default();
+
+    let (cols, rows) = (params.cols.max(1), params.rows.max(1));
+    let cell = glm::vec2(quad.width / cols as f32, quad.height / rows as f32);
+    let origin = glm::vec2(quad.center_x, quad.center_y) - glm::vec2(quad.width, quad.height) / 2.0;
+
+    let mut shards = Vec::with_capacity(params.shard_count());
+    for row in 0..rows {
+        for col in 0..cols {
+            let center =
+                origin + cell.component_mul(&glm::vec2(col as f32 + 0.5, row as f32 + 0.5));
+
+            for upper in [true, false] {
+                // the triangle's centroid is a sixth of the cell off its center
+                let sign = if upper { 1.0 } else { -1.0 };
+                let centroid = center + cell * sign / 6.0;
+
+                let offset = rotate2d(&centroid.component_mul(&transform.scale), transform.rotate);
+                let outward = match offset.magnitude() > f32::EPSILON {
+                    true => offset.normalize() * params.speed,
+                    false => glm::vec2(0.0, 0.0),
+                };
+
+                let shard_quad = CQuad {
+                    width: cell.x,
+                    height: cell.y,
+                    center_x: center.x - centroid.x,
+                    center_y: center.y - centroid.y,
+                };
+                let mut shard_sprite = CSprite::new(
+                    &shard_quad,
+                    Some(sprite.color),
+                    slice(&sprite, cols, rows, col, row),
+                )
+                .into_triangle(upper);
+                shard_sprite.shader = sprite.shader;
+
+                let spin = match upper {
+                    true => params.spin,
+                    false => -params.spin,
+                };
+
+                let shard = EntityBuilder::create(&mut registry.entity_manager)
+                    .with(CTag {
+                        tag: "shard".to_string(),
+                    })
+                    .with(CTransform2D {
+                        translate: transform.translate + offset,
+                        ..transform
+                    })
+                    .with(CVelocity2D {
+                        x: inherited.x + outward.x,
+                        y: inherited.y + outward.y,
+                    })
+                    .with(shard_sprite)
+                    .with(CShard {
+                        lifetime: params.lifetime,
+                        remaining: params.lifetime,
+                        spin,
+                        alpha: sprite.color.w,
+                    })
+                    .build();
+
+                shards.push(shard);
+            }
+        }
+    }
+
+    shards
+}
+
+/**
+* spins shards, fades them out and deletes them once their lifetime is up.
+* they're moved like anything else with a velocity, eg. by
+* `s_apply_velocity_2d`
+*
+* requires the following components:
+* - CShard
+* - CTransform2D
+* - CSprite
+*/
+pub fn s_update_shards(registry: &mut GlobalRegistry, delta: f32) {
+    for entity in registry.entity_manager.query_all::<CShard>() {
+        let Some(shard) = registry.entity_manager.get_mut::<CShard>(&entity) else {
+            continue;
+        };
+
+        shard.remaining -= delta;
+        let shard = *shard;
+
+        if shard.remaining <= 0.0 {
+            registry.entity_manager.set_to_delete(entity);
+
+            continue;
+        }
+
+        if let Some(transform) = registry.entity_manager.get_mut::<CTransform2D>(&entity) {
+            transform.rotate += shard.spin * delta;
+        }
+        if let Some(sprite) = registry.entity_manager.get_mut::<CSprite>(&entity) {
+            sprite.color.w = shard.alpha * shard.remaining / shard.lifetime;
+        }
+    }
+}
+
+/**
+* the part of the sprite's texture under one cell of the grid
+*/
+fn slice(
+    sprite: &CSprite,
+    cols: usize,
+    rows: usize,
+    col: usize,
+    row: usize,
+) -> Option<TextureAtlas> {
+    let mut atlas = sprite.texture_atlas.clone()?;
+    let grid = glm::vec2(cols as f32, rows as f32);
+
+    atlas.texture_dims = atlas.texture_dims.component_mul(&grid);
+    atlas.active_texture =
+        atlas.active_texture.component_mul(&grid) + glm::vec2(col as f32, row as f32);
+
+    Some(atlas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::registry;
+
+    #[test]
+    fn shatter_spawns_outward_shards() {
+        let mut registry = registry();
+
+        let quad = CQuad {
+            width: 40.0,
+            height: 20.0,
+            ..CQuad::default()
+        };
+        let atlas = TextureAtlas {
+            texture: 0,
+            texture_dims: glm::vec2(4.0, 4.0),
+            active_texture: glm::vec2(1.0, 2.0),
+        };
+        let transform = CTransform2D {
+            translate: glm::vec2(100.0, 50.0),
+            rotate: 0.3,
+            ..CTransform2D::default()
+        };
+        let asteroid = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CSprite::new(&quad, None, Some(atlas)))
+            .with(quad)
+            .with(transform)
+            .build();
+
+        let params = ShatterParams {
+            cols: 3,
+            rows: 2,
+            ..ShatterParams::default()
+        };
+        let shards = s_shatter(&mut registry, &asteroid, &params);
+        registry.entity_manager.set_to_delete(asteroid);
+        assert_eq!(shards.len(), 12);
+        assert_eq!(shards.len(), params.shard_count());
+
+        let em = &registry.entity_manager;
+        let mut momentum = glm::vec2(0.0, 0.0);
+        for shard in shards.iter() {
+            let velocity = em.get::<CVelocity2D>(shard).unwrap();
+            let velocity = glm::vec2(velocity.x, velocity.y);
+            let offset = em.get::<CTransform2D>(shard).unwrap().translate - transform.translate;
+
+            assert!((velocity.magnitude() - params.speed).abs() < 1e-3);
+            assert!(velocity.dot(&offset) > 0.0);
+            momentum += velocity;
+
+            // a slice of the asteroid's cell, not the whole texture
+            let atlas = em
+                .get::<CSprite>(shard)
+                .unwrap()
+                .texture_atlas
+                .clone()
+                .unwrap();
+            assert_eq!(atlas.texture_dims, glm::vec2(12.0, 8.0));
+            assert!((3.0..6.0).contains(&atlas.active_texture.x));
+            assert!((4.0..6.0).contains(&atlas.active_texture.y));
+        }
+        assert!(momentum.magnitude() < 1e-3);
+
+        // they spin, fade and go
+        s_update_shards(&mut registry, params.lifetime / 2.0);
+        let em = &registry.entity_manager;
+        let sprite = em.get::<CSprite>(&shards[0]).unwrap();
+        assert!((sprite.color.w - 0.5).abs() < 1e-5);
+        assert_ne!(
+            em.get::<CTransform2D>(&shards[0]).unwrap().rotate,
+            transform.rotate
+        );
+
+        s_update_shards(&mut registry, params.lifetime);
+        assert!(shards
+            .iter()
+            .all(|shard| registry.entity_manager.is_pending_delete(shard)));
+    }
+}
@@ -0,0 +1,104 @@
+use crate::prelude::{
+    qp_ecs::components::{CRestTime, CSleeping, CVelocity2D},
+    GlobalRegistry,
+    VersionedIndex,
+};
+
+/**
+* squared speed below which a body counts as resting
+*/
+pub const SLEEP_VELOCITY_SQUARED: f32 = 0.01;
+
+/**
+* seconds a body has to rest before it is put to sleep
+*/
+pub const SLEEP_AFTER: f32 = 0.5;
+
+/**
+* puts resting bodies to sleep and wakes sleeping bodies whose velocity was set
+*
+* only entities with CRestTime take part
+*/
+pub fn s_update_sleep(registry: &mut GlobalRegistry, delta: f32) {
+    for entity in registry.entity_manager.query_all::<CRestTime>() {
+        let speed = registry
+            .entity_manager
+            .get::<CVelocity2D>(&entity)
+            .map_or(0.0, |velocity| velocity.x.powi(2) + velocity.y.powi(2));
+
+        if speed > SLEEP_VELOCITY_SQUARED {
+            s_wake(registry, &entity);
+
+            continue;
+        }
+
+        let Some(rest_time) = registry.entity_manager.get_mut::<CRestTime>(&entity) else {
+            continue;
+        };
+        rest_time.0 += delta;
+
+        if rest_time.0 >= SLEEP_AFTER && !is_sleeping(registry, &entity) {
+            registry.entity_manager.add(&entity, CSleeping);
+        }
+    }
+}
+
+/**
+* wakes a body up, eg. after it was hit by something
+*/
+pub fn s_wake(registry: &mut GlobalRegistry, entity: &VersionedIndex) {
+    if let Some(rest_time) = registry.entity_manager.get_mut::<CRestTime>(entity) {
+        rest_time.0 = 0.0;
+    }
+
+    if is_sleeping(registry, entity) {
+        registry.entity_manager.remove::<CSleeping>(entity);
+    }
+}
+
+pub fn is_sleeping(registry: &GlobalRegistry, entity: &VersionedIndex) -> bool {
+    registry.entity_manager.get::<CSleeping>(entity).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{
+        qp_ecs::components::{CQuad, CTransform2D},
+        qp_physics::{dynamic_colliders, s_apply_velocity_2d},
+        EntityBuilder,
+    };
+    use crate::test_utils::registry;
+
+    #[test]
+    fn sleep_after_rest_and_wake_on_velocity() {
+        let mut registry = registry();
+
+        let entity = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D::default())
+            .with(CVelocity2D::default())
+            .with(CQuad::default())
+            .with(CRestTime::default())
+            .build();
+
+        s_update_sleep(&mut registry, SLEEP_AFTER / 2.0);
+        assert!(!is_sleeping(&registry, &entity));
+
+        s_update_sleep(&mut registry, SLEEP_AFTER / 2.0);
+        assert!(is_sleeping(&registry, &entity));
+        assert!(dynamic_colliders(&registry).is_empty());
+
+        // sleeping bodies aren't integrated
+        registry.entity_manager.get_mut::<CVelocity2D>(&entity).unwrap().x = 10.0;
+        s_apply_velocity_2d(&mut registry, 1.0);
+        assert_eq!(registry.entity_manager.get::<CTransform2D>(&entity).unwrap().translate.x, 0.0);
+
+        s_update_sleep(&mut registry, 0.016);
+        assert!(!is_sleeping(&registry, &entity));
+        assert_eq!(registry.entity_manager.get::<CRestTime>(&entity), Some(&CRestTime(0.0)));
+
+        s_apply_velocity_2d(&mut registry, 1.0);
+        assert_eq!(registry.entity_manager.get::<CTransform2D>(&entity).unwrap().translate.x, 10.0);
+        assert_eq!(dynamic_colliders(&registry), vec![entity]);
+    }
+}
@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+
+use crate::prelude::{
+    qp_ecs::{
+        components::{CQuad, CTransform2D},
+        Component,
+    },
+    GlobalRegistry, VersionedIndex,
+};
+
+use super::bvh::Aabb;
+
+/**
+* broad-phase grid for moving things. entities are bucketed by their bounds
+* into square cells, so a query only has to look at the cells it touches
+* instead of every entity. an entity whose bounds straddle cells is in all
+* of them
+*
+* pick a cell size around the size of the things being stored. rebuild it
+* every frame, or `update` the entities that moved
+*/
+#[derive(Debug)]
+pub struct SpatialHash2D {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+
+    // cells point into entries by slot. slots freed by a remove are reused
+    entries: Vec<Option<(VersionedIndex, Aabb)>>,
+    slots: HashMap<VersionedIndex, usize>,
+    free: Vec<usize>,
+}
+
+impl SpatialHash2D {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size: cell_size.max(f32::EPSILON),
+            cells: HashMap::new(),
+            entries: vec![],
+            slots: HashMap::new(),
+            free: vec![],
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+        self.slots.clear();
+        self.free.clear();
+    }
+
+    /**
+     * fills the grid with every entity that has a CTransform2D, see
+     * `rebuild_with`
+     */
+    pub fn rebuild(&mut self, registry: &GlobalRegistry) {
+        self.rebuild_with::<CTransform2D>(registry);
+    }
+
+    /**
+     * empties the grid and fills it with every entity that has a C and a
+     * CTransform2D. entities with a CQuad take up the box around it, the
+     * rest are a point at their translation
+     *
+     * requires the following components:
+     * - C
+     * - CTransform2D
+     * - CQuad (optional)
+     */
+    pub fn rebuild_with<C: Component + PartialEq + 'static>(&mut self, registry: &GlobalRegistry) {
+        self.clear();
+
+        let em = &registry.entity_manager;
+        for entity in em.query_all::<C>() {
+            if let Some(bounds) = entity_bounds(registry, &entity) {
+                self.insert(entity, bounds);
+            }
+        }
+    }
+
+    /**
+     * adds the entity, or moves it if it's already in the grid
+     */
+    pub fn insert(&mut self, entity: VersionedIndex, bounds: Aabb) {
+        self.remove(&entity);
+
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.entries[slot] = Some((entity, bounds));
+                slot
+            }
+            None => {
+                self.entries.push(Some((entity, bounds)));
+                self.entries.len() - 1
+            }
+        };
+        self.slots.insert(entity, slot);
+
+        for cell in self.cells_in(&bounds) {
+            self.cells.entry(cell).or_default().push(slot);
+        }
+    }
+
+    pub fn insert_point(&mut self, entity: VersionedIndex, point: glm::Vec2) {
+        self.insert(entity, Aabb::new(point, point));
+    }
+
+    /**
+     * moves an entity to where its components are now, or takes it out if
+     * it no longer has a CTransform2D
+     */
+    pub fn update(&mut self, registry: &GlobalRegistry, entity: &VersionedIndex) {
+        match entity_bounds(registry, entity) {
+            Some(bounds) => self.insert(*entity, bounds),
+            None => self.remove(entity),
+        }
+    }
+
+    pub fn remove(&mut self, entity: &VersionedIndex) {
+        let Some(slot) = self.slots.remove(entity) else {
+            return;
+        };
+        let Some((_, bounds)) = self.entries[slot].take() else {
+            return;
+        };
+
+        for cell in self.cells_in(&bounds) {
+            if let Some(slots) = self.cells.get_mut(&cell) {
+                slots.retain(|s| *s != slot);
+                if slots.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+
+        self.free.push(slot);
+    }
+
+    /**
+     * every entity whose bounds come within radius of pos
+     */
+    pub fn query_radius(&self, pos: glm::Vec2, radius: f32) -> Vec<VersionedIndex> {
+        let reach = glm::vec2(radius, radius);
+        let area = Aabb::new(pos - reach, pos + reach);
+
+        self.query(&area, |bounds| {
+            let closest = glm::clamp_vec(&pos, &bounds.min, &bounds.max);
+
+            glm::distance2(&closest, &pos) <= radius * radius
+        })
+    }
+
+    /**
+     * every entity whose bounds overlap the box from min to max
+     */
+    pub fn query_aabb(&self, min: glm::Vec2, max: glm::Vec2) -> Vec<VersionedIndex> {
+        let area = Aabb::new(min, max);
+
+        self.query(&area, |bounds| bounds.overlaps(&area))
+    }
+
+    fn query(&self, area: &Aabb, hit: impl Fn(&Aabb) -> bool) -> Vec<VersionedIndex> {
+        let mut slots = self
+            .cells_in(area)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+        slots.sort_unstable();
+        slots.dedup();
+
+        slots
+            .into_iter()
+            .filter_map(|slot| self.entries[slot])
+            .filter(|(_, bounds)| hit(bounds))
+            .map(|(entity, _)| entity)
+            .collect()
+    }
+
+    fn cell(&self, point: &glm::Vec2) -> (i32, i32) {
+        (
+            (point.x / self.cell_size).floor() as i32,
+            (point.y / self.cell_size).floor() as i32,
+        )
+    }
+
+    fn cells_in(&self, bounds: &Aabb) -> impl Iterator<Item = (i32, i32)> {
+        let (min, max) = (self.cell(&bounds.min), self.cell(&bounds.max));
+
+        (min.0..=max.0).flat_map(move |x| (min.1..=max.1).map(move |y| (x, y)))
+    }
+}
+
+fn entity_bounds(registry: &GlobalRegistry, entity: &VersionedIndex) -> Option<Aabb> {
+    let em = &registry.entity_manager;
+    let transform = em.get::<CTransform2D>(entity)?;
+
+    Some(match em.get::<CQuad>(entity) {
+        Some(quad) => Aabb::from_quad(transform, quad),
+        None => Aabb::new(transform.translate, transform.translate),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{qp_ecs::components::CTag, EntityBuilder};
+    use crate::test_utils::{at, registry};
+
+    #[test]
+    fn spatial_hash_radius_query_is_exact() {
+        let mut registry = registry();
+
+        // a 10x10 grid of points 10 apart, in cells of 25 so plenty of them
+        // sit right on a cell boundary
+        let mut points = vec![];
+        for x in 0..10 {
+            for y in 0..10 {
+                let pos = glm::vec2(x as f32 * 10.0, y as f32 * 10.0);
+                points.push((at(&mut registry, pos.x, pos.y).build(), pos));
+            }
+        }
+
+        let mut hash = SpatialHash2D::new(25.0);
+        hash.rebuild(&registry);
+        assert_eq!(hash.len(), 100);
+
+        for (center, radius) in [
+            (glm::vec2(50.0, 50.0), 20.0),
+            (glm::vec2(25.0, 25.0), 15.0),
+            (glm::vec2(0.0, 0.0), 10.0),
+            (glm::vec2(74.9, 50.0), 30.0),
+        ] {
+            let mut expected = points
+                .iter()
+                .filter(|(_, pos)| glm::distance(pos, &center) <= radius)
+                .map(|(entity, _)| *entity)
+                .collect::<Vec<_>>();
+            let mut found = hash.query_radius(center, radius);
+            expected.sort_by_key(|entity| entity.to_string());
+            found.sort_by_key(|entity| entity.to_string());
+
+            assert_eq!(found, expected, "radius {} around {:?}", radius, center);
+        }
+
+        let found = hash.query_aabb(glm::vec2(20.0, 20.0), glm::vec2(30.0, 30.0));
+        assert_eq!(found.len(), 4);
+    }
+
+    #[test]
+    fn spatial_hash_finds_straddling_quads() {
+        let mut registry = registry();
+
+        // a wide quad across four cells, with its middle far from the query
+        let wall = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CTransform2D {
+                translate: glm::vec2(50.0, 10.0),
+                ..CTransform2D::default()
+            })
+            .with(CQuad {
+                width: 100.0,
+                height: 4.0,
+                center_x: 0.0,
+                center_y: 0.0,
+            })
+            .with(CTag { tag: "wall".into() })
+            .build();
+        let point = at(&mut registry, 95.0, 40.0).build();
+
+        let mut hash = SpatialHash2D::new(25.0);
+        hash.rebuild_with::<CTag>(&registry);
+        assert_eq!(hash.len(), 1);
+
+        assert_eq!(hash.query_radius(glm::vec2(95.0, 20.0), 9.0), vec![wall]);
+        assert!(hash.query_radius(glm::vec2(95.0, 20.0), 7.0).is_empty());
+
+        // moving things are updated in place
+        hash.update(&registry, &point);
+        assert_eq!(hash.query_radius(glm::vec2(95.0, 35.0), 5.0), vec![point]);
+
+        let transform = registry
+            .entity_manager
+            .get_mut::<CTransform2D>(&point)
+            .unwrap();
+        transform.translate = glm::vec2(-40.0, -40.0);
+        hash.update(&registry, &point);
+        assert!(hash.query_radius(glm::vec2(95.0, 35.0), 5.0).is_empty());
+        assert_eq!(
+            hash.query_aabb(glm::vec2(-50.0, -50.0), glm::vec2(0.0, 0.0)),
+            vec![point]
+        );
+        assert_eq!(hash.len(), 2);
+
+        hash.remove(&wall);
+        assert!(hash.query_radius(glm::vec2(95.0, 20.0), 9.0).is_empty());
+    }
+}
@@ -0,0 +1,98 @@
+use crate::prelude::qp_core::{math2d::angle_between, trig::rotate2d};
+
+/**
+* desired velocity to head straight for the target at full speed
+*/
+pub fn seek(position: &glm::Vec2, target: &glm::Vec2, max_speed: f32) -> glm::Vec2 {
+    let offset = target - position;
+    let distance = offset.magnitude();
+
+    if distance == 0.0 {
+        return glm::vec2(0.0, 0.0);
+    }
+
+    offset / distance * max_speed
+}
+
+/**
+* like seek, but slows down linearly once inside `slow_radius` so it comes to
+* rest on the target instead of overshooting it
+*/
+pub fn arrive(
+    position: &glm::Vec2,
+    target: &glm::Vec2,
+    max_speed: f32,
+    slow_radius: f32,
+) -> glm::Vec2 {
+    let distance = (target - position).magnitude();
+
+    let speed = match distance < slow_radius {
+        true => max_speed * distance / slow_radius,
+        false => max_speed,
+    };
+
+    seek(position, target, speed)
+}
+
+/**
+* rotates heading towards desired by at most max_angle radians, keeping
+* heading's length. a zero desired leaves heading alone
+*/
+pub fn turn_towards(heading: &glm::Vec2, desired: &glm::Vec2, max_angle: f32) -> glm::Vec2 {
+    let turn = angle_between(heading, desired).clamp(-max_angle, max_angle);
+
+    rotate2d(heading, turn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seek_full_speed() {
+        let velocity = seek(&glm::vec2(0.0, 0.0), &glm::vec2(3.0, 4.0), 10.0);
+
+        assert!((velocity - glm::vec2(6.0, 8.0)).magnitude() < 1e-5);
+        assert_eq!(seek(&glm::vec2(1.0, 1.0), &glm::vec2(1.0, 1.0), 10.0), glm::vec2(0.0, 0.0));
+    }
+
+    #[test]
+    fn turn_towards_limited() {
+        let heading = glm::vec2(2.0, 0.0);
+
+        let turned = turn_towards(&heading, &glm::vec2(0.0, 5.0), 0.1);
+        assert!((turned.magnitude() - 2.0).abs() < 1e-5);
+        assert!((turned.y.atan2(turned.x) - 0.1).abs() < 1e-5);
+
+        // clockwise when the target is on the right
+        let turned = turn_towards(&heading, &glm::vec2(0.0, -5.0), 0.1);
+        assert!((turned.y.atan2(turned.x) + 0.1).abs() < 1e-5);
+
+        // lands exactly on it when it's within reach
+        let turned = turn_towards(&heading, &glm::vec2(1.0, 0.05), 0.1);
+        assert!((turned.normalize() - glm::vec2(1.0, 0.05).normalize()).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn arrive_slows_inside_radius() {
+        let target = glm::vec2(100.0, 0.0);
+
+        let outside = arrive(&glm::vec2(0.0, 0.0), &target, 10.0, 50.0);
+        assert!((outside.magnitude() - 10.0).abs() < 1e-5);
+
+        let inside = arrive(&glm::vec2(75.0, 0.0), &target, 10.0, 50.0);
+        assert!((inside.magnitude() - 5.0).abs() < 1e-5);
+
+        // integrate until it settles on the target
+        let mut position = glm::vec2(0.0, 0.0);
+        let mut velocity = glm::vec2(0.0, 0.0);
+        for _ in 0..2000 {
+            velocity = arrive(&position, &target, 10.0, 50.0);
+            position += velocity * 0.1;
+        }
+
+        assert!((target - position).magnitude() < 0.01);
+        assert!(velocity.magnitude() < 0.01);
+        assert!(position.x <= target.x);
+    }
+}
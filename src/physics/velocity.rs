@@ -0,0 +1,123 @@
+use crate::prelude::{
+    qp_ecs::components::{CTransform2D, CVelocity2D, CVelocityClamp2D},
+    GlobalRegistry,
+};
+
+use super::{collision::is_static, sleep::is_sleeping};
+
+/**
+* moves every awake entity by its velocity. CStatic entities never move
+*
+* requires the following components:
+* - CTransform2D
+* - CVelocity2D
+*/
+pub fn s_apply_velocity_2d(registry: &mut GlobalRegistry, delta: f32) {
+    for entity in registry.entity_manager.query_all::<CVelocity2D>() {
+        if is_sleeping(registry, &entity) || is_static(registry, &entity) {
+            continue;
+        }
+
+        let Some(velocity) = registry.entity_manager.get::<CVelocity2D>(&entity).copied() else {
+            continue;
+        };
+        let Some(transform) = registry.entity_manager.get_mut::<CTransform2D>(&entity) else {
+            continue;
+        };
+
+        transform.translate.x += velocity.x * delta;
+        transform.translate.y += velocity.y * delta;
+    }
+}
+
+/**
+* scales every CVelocityClamp2D entity's velocity down to max_speed if it's
+* going faster, keeping its direction. the whole vector is clamped, so moving
+* diagonally is no faster than moving along an axis
+*
+* requires the following components:
+* - CVelocityClamp2D
+* - CVelocity2D
+*/
+pub fn s_clamp_velocity(registry: &mut GlobalRegistry) {
+    for entity in registry.entity_manager.query_all::<CVelocityClamp2D>() {
+        let Some(clamp) = registry
+            .entity_manager
+            .get::<CVelocityClamp2D>(&entity)
+            .copied()
+        else {
+            continue;
+        };
+        let Some(velocity) = registry.entity_manager.get_mut::<CVelocity2D>(&entity) else {
+            continue;
+        };
+
+        let max_speed = clamp.max_speed.max(0.0);
+        let clamped = glm::vec2(velocity.x, velocity.y).cap_magnitude(max_speed);
+        velocity.x = clamped.x;
+        velocity.y = clamped.y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{
+        qp_ecs::components::{CQuad, CStatic},
+        qp_physics::dynamic_colliders,
+        EntityBuilder,
+    };
+    use crate::test_utils::registry;
+
+    #[test]
+    fn velocity_clamp_caps_diagonal_speed() {
+        let mut registry = registry();
+
+        let diagonal = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CVelocity2D { x: 300.0, y: 400.0 })
+            .with(CVelocityClamp2D { max_speed: 200.0 })
+            .build();
+        let slow = EntityBuilder::create(&mut registry.entity_manager)
+            .with(CVelocity2D { x: 60.0, y: -80.0 })
+            .with(CVelocityClamp2D { max_speed: 200.0 })
+            .build();
+
+        s_clamp_velocity(&mut registry);
+
+        // each axis is under max_speed, but together they were going 500
+        let velocity = registry
+            .entity_manager
+            .get::<CVelocity2D>(&diagonal)
+            .unwrap();
+        let velocity = glm::vec2(velocity.x, velocity.y);
+        assert!((velocity.magnitude() - 200.0).abs() < 1e-3);
+        assert!((velocity.normalize() - glm::vec2(0.6, 0.8)).magnitude() < 1e-5);
+
+        let velocity = registry.entity_manager.get::<CVelocity2D>(&slow).unwrap();
+        assert_eq!(*velocity, CVelocity2D { x: 60.0, y: -80.0 });
+    }
+
+    #[test]
+    fn static_bodies_excluded_from_movement_and_broadphase() {
+        let mut registry = registry();
+
+        let body = |registry: &mut GlobalRegistry| {
+            EntityBuilder::create(&mut registry.entity_manager)
+                .with(CTransform2D::default())
+                .with(CVelocity2D { x: 10.0, y: 0.0 })
+                .with(CQuad::default())
+                .build()
+        };
+        let dynamic = body(&mut registry);
+        let fixed = body(&mut registry);
+        registry.entity_manager.add(&fixed, CStatic);
+
+        s_apply_velocity_2d(&mut registry, 1.0);
+
+        let x = |entity| registry.entity_manager.get::<CTransform2D>(&entity).unwrap().translate.x;
+        assert_eq!(x(dynamic), 10.0);
+        assert_eq!(x(fixed), 0.0);
+
+        assert_eq!(dynamic_colliders(&registry), vec![dynamic]);
+    }
+}
@@ -0,0 +1,285 @@
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::{
+    qp_core::random::Random,
+    qp_schemas::SchemaSprite,
+    GlobalRegistry, Schema, VersionedIndex,
+};
+use crate::QPResult;
+
+/**
+* where the entities of a wave go, around the point the scheduler is given
+*/
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SpawnPattern {
+    /**
+     * evenly spaced on a circle, the first one straight to the right
+     */
+    Ring { radius: f32 },
+
+    /**
+     * evenly spaced from one point to another, both relative to the center
+     */
+    Line { from: glm::Vec2, to: glm::Vec2 },
+
+    /**
+     * anywhere in a rect of this size, picked by the scheduler's seed
+     */
+    Scatter { size: glm::Vec2 },
+}
+
+/**
+* count copies of the prefab, spawned time seconds after the scheduler starts
+*/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Wave {
+    pub time: f32,
+    pub prefab: SchemaSprite,
+    pub count: usize,
+    pub pattern: SpawnPattern,
+}
+
+/**
+* spawns authored waves at their times. the same waves and seed always spawn
+* the same things in the same places, given the same centers
+*
+* call `update` every frame with the point the patterns are around, eg. the
+* player
+*/
+pub struct WaveScheduler {
+    waves: Vec<Wave>,
+    seed: u64,
+    rand: Random,
+
+    elapsed: f32,
+    next: usize,
+}
+
+impl WaveScheduler {
+    pub fn new(mut waves: Vec<Wave>, seed: u64) -> Self {
+        waves.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        Self {
+            waves,
+            seed,
+            rand: Random::from_seed(seed),
+            elapsed: 0.0,
+            next: 0,
+        }
+    }
+
+    /**
+     * spawns every wave that's due, returning what was spawned. a long frame
+     * spawns all the waves it skipped over, in order
+     *
+     * a prefab that fails to build stops the update with its error. the
+     * wave it belongs to counts as spawned, so it isn't retried every frame,
+     * and the later due waves spawn on the next update
+     */
+    pub fn update(
+        &mut self,
+        registry: &mut GlobalRegistry,
+        delta: f32,
+        center: glm::Vec2,
+    ) -> QPResult<Vec<VersionedIndex>> {
+        self.elapsed += delta;
+
+        let mut spawned = vec![];
+        while let Some(wave) = self.waves.get(self.next) {
+            if wave.time > self.elapsed {
+                break;
+            }
+            self.next += 1;
+
+            for i in 0..wave.count {
+                let offset = match wave.pattern {
+                    SpawnPattern::Ring { radius } => {
+                        let angle = i as f32 / wave.count as f32 * glm::two_pi::<f32>();
+
+                        glm::vec2(angle.cos(), angle.sin()) * radius
+                    }
+                    SpawnPattern::Line { from, to } => {
+                        let t = match wave.count {
+                            1 => 0.5,
+                            count => i as f32 / (count - 1) as f32,
+                        };
+
+                        glm::lerp(&from, &to, t)
+                    }
+                    SpawnPattern::Scatter { size } => {
+                        let half = size / 2.0;
+
+                        glm::vec2(
+                            (self.rand.random() - 0.5) * 2.0 * half.x,
+                            (self.rand.random() - 0.5) * 2.0 * half.y,
+                        )
+                    }
+                };
+
+                let mut prefab = wave.prefab.clone();
+                prefab.transform.translate = center + offset;
+
+                spawned.push(prefab.build_entity(registry)?);
+            }
+        }
+
+        Ok(spawned)
+    }
+
+    /**
+     * back to the start, with the same seed
+     */
+    pub fn reset(&mut self) {
+        self.rand = Random::from_seed(self.seed);
+        self.elapsed = 0.0;
+        self.next = 0;
+    }
+
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /**
+     * true once every wave has spawned
+     */
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.waves.len()
+    }
+
+    pub fn waves(&self) -> &[Wave] {
+        &self.waves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{
+        qp_ecs::components::{CTag, CTransform2D},
+        qp_schemas::SchemaTextureAtlas,
+    };
+    use crate::test_utils::registry;
+
+    fn prefab(tag: &str) -> SchemaSprite {
+        SchemaSprite {
+            tag: tag.to_string(),
+            ..SchemaSprite::default()
+        }
+    }
+
+    fn waves() -> Vec<Wave> {
+        vec![
+            Wave {
+                time: 2.5,
+                prefab: prefab("big"),
+                count: 5,
+                pattern: SpawnPattern::Scatter {
+                    size: glm::vec2(800.0, 600.0),
+                },
+            },
+            Wave {
+                time: 1.0,
+                prefab: prefab("small"),
+                count: 3,
+                pattern: SpawnPattern::Ring { radius: 100.0 },
+            },
+            Wave {
+                time: 2.5,
+                prefab: prefab("small"),
+                count: 2,
+                pattern: SpawnPattern::Line {
+                    from: glm::vec2(-50.0, 0.0),
+                    to: glm::vec2(50.0, 0.0),
+                },
+            },
+        ]
+    }
+
+    // the tag and position of everything spawned on each frame
+    fn run(seed: u64) -> Vec<Vec<(String, glm::Vec2)>> {
+        let mut registry = registry();
+
+        let mut scheduler = WaveScheduler::new(waves(), seed);
+        let mut frames = vec![];
+        for _ in 0..8 {
+            let spawned = scheduler
+                .update(&mut registry, 0.5, glm::vec2(10.0, 20.0))
+                .unwrap();
+            let em = &registry.entity_manager;
+            frames.push(
+                spawned
+                    .iter()
+                    .map(|entity| {
+                        let tag = em.get::<CTag>(entity);
+                        let transform = em.get::<CTransform2D>(entity).unwrap();
+
+                        (tag.unwrap().tag.clone(), transform.translate)
+                    })
+                    .collect(),
+            );
+        }
+        assert!(scheduler.is_finished());
+
+        frames
+    }
+
+    #[test]
+    fn waves_spawn_on_schedule_from_seed() {
+        let frames = run(1234);
+
+        let counts = frames
+            .iter()
+            .map(|frame| {
+                let count = |tag: &str| frame.iter().filter(|(t, _)| t == tag).count();
+
+                (count("small"), count("big"))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            counts,
+            vec![
+                (0, 0),
+                (3, 0),
+                (0, 0),
+                (0, 0),
+                (2, 5),
+                (0, 0),
+                (0, 0),
+                (0, 0)
+            ]
+        );
+
+        // the ring starts to the right of the center, the line is centered on it
+        let (_, first) = &frames[1][0];
+        assert!(glm::distance(first, &glm::vec2(110.0, 20.0)) < 1e-3);
+        let line = frames[4]
+            .iter()
+            .filter(|(tag, _)| tag == "small")
+            .map(|(_, pos)| *pos)
+            .collect::<Vec<_>>();
+        assert_eq!(line, vec![glm::vec2(-40.0, 20.0), glm::vec2(60.0, 20.0)]);
+
+        // the same seed scatters the same way, another one doesn't
+        assert_eq!(run(1234), frames);
+        assert_ne!(run(4321), frames);
+    }
+
+    #[test]
+    fn waves_return_spawn_errors() {
+        let mut registry = registry();
+
+        let mut broken = prefab("broken");
+        broken.texture = Some(SchemaTextureAtlas::new("missing"));
+        let mut waves = waves();
+        waves[1].prefab = broken;
+
+        let mut scheduler = WaveScheduler::new(waves, 1234);
+        assert!(scheduler.update(&mut registry, 1.0, glm::vec2(0.0, 0.0)).is_err());
+        assert_eq!(registry.entity_manager.count(), 0);
+
+        // the broken wave isn't retried, the rest still spawn
+        let spawned = scheduler.update(&mut registry, 1.5, glm::vec2(0.0, 0.0)).unwrap();
+        assert_eq!(spawned.len(), 7);
+        assert!(scheduler.is_finished());
+    }
+}
@@ -0,0 +1,260 @@
+use crate::prelude::{
+    qp_ecs::{
+        components::{CTransform2D, CVelocity2D},
+        EntityPool,
+    },
+    qp_schemas::SchemaSprite,
+    GlobalRegistry, Schema, VersionedIndex, World,
+};
+use crate::QPResult;
+
+/**
+* fires copies of a projectile prefab out of a muzzle, at most once every
+* cooldown seconds
+*
+* call `tick` every frame, whether or not the trigger is held, so the
+* cooldown keeps running between bursts
+*
+* spent projectiles should be handed back with `release`, firing reuses them
+* before spawning new ones
+*/
+#[derive(Debug, Clone)]
+pub struct Weapon {
+    pub projectile: SchemaSprite,
+    pub speed: f32,
+    pub cooldown: f32,
+    pub pool: EntityPool,
+
+    ready_in: f32,
+}
+
+impl Weapon {
+    pub fn new(projectile: SchemaSprite, speed: f32, cooldown: f32) -> Self {
+        Self {
+            projectile,
+            speed,
+            cooldown,
+            pool: EntityPool::new(),
+            ready_in: 0.0,
+        }
+    }
+
+    /**
+     * keeps at most `size` spent projectiles around for reuse
+     */
+    pub fn with_pool_size(mut self, size: usize) -> Self {
+        self.pool = EntityPool::with_max_free(size);
+        self
+    }
+
+    pub fn tick(&mut self, delta: f32) {
+        self.ready_in = (self.ready_in - delta).max(0.0);
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready_in <= 0.0
+    }
+
+    /**
+     * spawns a projectile at the muzzle, facing and flying the way it points,
+     * reusing a released one if there is one. returns None while cooling down.
+     * a projectile that can't be spawned is an error and doesn't start the
+     * cooldown
+     */
+    pub fn try_fire(
+        &mut self,
+        world: &mut World,
+        muzzle: &CTransform2D,
+    ) -> QPResult<Option<VersionedIndex>> {
+        if !self.is_ready() {
+            return Ok(None);
+        }
+
+        let direction = muzzle.direction();
+        let mut projectile = self.projectile.clone();
+        projectile.transform.translate = muzzle.translate;
+        projectile.transform.rotate = muzzle.rotate;
+        projectile.velocity = Some(CVelocity2D {
+            x: direction.x * self.speed,
+            y: direction.y * self.speed,
+        });
+
+        let spawned = match self.pool.acquire(&mut world.registry.entity_manager) {
+            Some(entity) => {
+                let applied = projectile.apply_to(&mut world.registry, &entity);
+                if applied.is_err() {
+                    // still as it was released, so it waits for the next shot
+                    self.pool
+                        .release(&mut world.registry.entity_manager, entity);
+                }
+
+                applied.map(|_| entity)
+            }
+            None => projectile.build_entity(&mut world.registry),
+        };
+        let entity = spawned?;
+        self.ready_in = self.cooldown;
+
+        Ok(Some(entity))
+    }
+
+    /**
+     * hands a spent projectile back to be fired again
+     */
+    pub fn release(&mut self, registry: &mut GlobalRegistry, projectile: VersionedIndex) {
+        self.pool.release(&mut registry.entity_manager, projectile);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{
+        qp_ecs::components::CSprite, qp_gfx::Viewport, qp_physics::dynamic_colliders,
+        qp_schemas::SchemaTextureAtlas,
+    };
+
+    fn weapon() -> Weapon {
+        Weapon::new(
+            SchemaSprite {
+                tag: "bullet".to_string(),
+                ..SchemaSprite::default()
+            },
+            250.0,
+            0.5,
+        )
+    }
+
+    #[test]
+    fn weapon_respects_cooldown() {
+        let mut world = World::new(Viewport::headless(800, 600, None), 0).unwrap();
+        let mut weapon = weapon();
+        let muzzle = CTransform2D {
+            translate: glm::vec2(10.0, 20.0),
+            rotate: glm::half_pi(),
+            ..CTransform2D::default()
+        };
+
+        // hold the trigger for 20 frames
+        let mut fired = vec![];
+        for frame in 0..20 {
+            weapon.tick(0.125);
+            if let Some(entity) = weapon.try_fire(&mut world, &muzzle).unwrap() {
+                fired.push((frame, entity));
+            }
+        }
+
+        let frames = fired.iter().map(|(frame, _)| *frame).collect::<Vec<_>>();
+        assert_eq!(frames, vec![0, 4, 8, 12, 16]);
+        assert_eq!(world.registry.entity_manager.get_by_tag("bullet").len(), 5);
+
+        let em = &world.registry.entity_manager;
+        let (_, bullet) = fired[0];
+        let transform = em.get::<CTransform2D>(&bullet).unwrap();
+        let velocity = em.get::<CVelocity2D>(&bullet).unwrap();
+        assert_eq!(transform.translate, muzzle.translate);
+        assert_eq!(transform.rotate, muzzle.rotate);
+        assert!(
+            (glm::vec2(velocity.x, velocity.y) - muzzle.direction() * 250.0).magnitude() < 1e-3
+        );
+
+        // the cooldown keeps running while the trigger is released
+        weapon.try_fire(&mut world, &muzzle).unwrap();
+        for _ in 0..4 {
+            weapon.tick(0.125);
+        }
+        assert!(weapon.try_fire(&mut world, &muzzle).unwrap().is_some());
+    }
+
+    #[test]
+    fn weapon_reuses_released_projectiles() {
+        let mut world = World::new(Viewport::headless(800, 600, None), 0).unwrap();
+        let mut weapon = weapon();
+        let muzzle = CTransform2D::default();
+
+        let first = (0..3)
+            .filter_map(|_| {
+                weapon.tick(0.5);
+                weapon.try_fire(&mut world, &muzzle).unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(first.len(), 3);
+
+        // the projectiles expire
+        for projectile in first.iter() {
+            world
+                .registry
+                .entity_manager
+                .get_mut::<CTransform2D>(projectile)
+                .unwrap()
+                .translate = glm::vec2(500.0, 500.0);
+            weapon.release(&mut world.registry, *projectile);
+        }
+        assert_eq!(weapon.pool.len(), 3);
+        let em = &world.registry.entity_manager;
+        assert!(first.iter().all(|p| em.get::<CSprite>(p).unwrap().skip));
+        assert!(first.iter().all(|p| em.get::<CVelocity2D>(p).is_none()));
+
+        // pooled projectiles are out of tag queries and collision
+        assert!(first.iter().all(|p| em.is_disabled(p)));
+        assert!(em.get_by_tag("bullet").is_empty());
+        assert!(dynamic_colliders(&world.registry).is_empty());
+
+        let allocated = world.registry.entity_manager.allocator_size();
+        let muzzle = CTransform2D {
+            translate: glm::vec2(10.0, 20.0),
+            ..CTransform2D::default()
+        };
+        let mut second = (0..3)
+            .filter_map(|_| {
+                weapon.tick(0.5);
+                weapon.try_fire(&mut world, &muzzle).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let em = &world.registry.entity_manager;
+        assert_eq!(em.allocator_size(), allocated);
+        assert!(weapon.pool.is_empty());
+        for projectile in second.iter() {
+            assert!(!em.is_disabled(projectile));
+            assert_eq!(em.get::<CTransform2D>(projectile).unwrap().translate, muzzle.translate);
+            assert!(em.get::<CVelocity2D>(projectile).is_some());
+            assert!(!em.get::<CSprite>(projectile).unwrap().skip);
+        }
+        second.reverse();
+        assert_eq!(second, first);
+
+        // with the pool empty, firing spawns a new one
+        weapon.tick(0.5);
+        let extra = weapon.try_fire(&mut world, &muzzle).unwrap().unwrap();
+        assert!(!first.contains(&extra));
+        assert_eq!(world.registry.entity_manager.allocator_size(), allocated + 1);
+    }
+
+    #[test]
+    fn weapon_keeps_pooled_projectile_when_spawn_fails() {
+        let mut world = World::new(Viewport::headless(800, 600, None), 0).unwrap();
+        let mut weapon = weapon();
+        let muzzle = CTransform2D::default();
+
+        let projectile = weapon.try_fire(&mut world, &muzzle).unwrap().unwrap();
+        weapon.release(&mut world.registry, projectile);
+
+        // a texture that was never loaded
+        weapon.projectile.texture = Some(SchemaTextureAtlas::new("missing"));
+        let allocated = world.registry.entity_manager.allocator_size();
+        for _ in 0..3 {
+            weapon.tick(0.5);
+            assert!(weapon.try_fire(&mut world, &muzzle).is_err());
+        }
+
+        let em = &world.registry.entity_manager;
+        assert_eq!(em.allocator_size(), allocated);
+        assert_eq!(weapon.pool.len(), 1);
+        assert!(em.is_disabled(&projectile));
+        assert!(em.get::<CSprite>(&projectile).unwrap().skip);
+
+        // and a failed shot doesn't use up the cooldown
+        assert!(weapon.is_ready());
+    }
+}
@@ -172,6 +172,15 @@ pub fn vertex_attribute_pointer(
     }
 }
 
+/**
+ * how many instances share each value of the attribute. 0 is per vertex
+ */
+pub fn vertex_attribute_divisor(location: usize, divisor: u32) {
+    unsafe {
+        gl::VertexAttribDivisor(location as gl::types::GLuint, divisor)
+    }
+}
+
 pub fn create_ebo(
     indices: &[u32],
     usage: &BufferUsage
@@ -191,6 +200,27 @@ pub fn clear_buffers(clr: (f32, f32, f32, f32)) {
     }
 }
 
+/**
+ * clears only the buffers asked for. with no color and no depth it doesn't
+ * touch gl at all
+ */
+pub fn clear_buffers_masked(clr: Option<(f32, f32, f32, f32)>, depth: bool) {
+    let mut mask = 0;
+
+    if let Some(clr) = clr {
+        unsafe { gl::ClearColor(clr.0, clr.1, clr.2, clr.3) };
+        mask |= gl::COLOR_BUFFER_BIT;
+    }
+
+    if depth {
+        mask |= gl::DEPTH_BUFFER_BIT;
+    }
+
+    if mask != 0 {
+        unsafe { gl::Clear(mask) };
+    }
+}
+
 // private helpers
 
 impl BufferUsage {
@@ -24,6 +24,34 @@ pub fn gl_draw(
     }
 }
 
+/**
+ * draws the bound elements `instances` times. attributes with a divisor step
+ * once per instance instead of once per vertex
+ */
+pub fn gl_draw_instanced(
+    mode: DrawMode,
+    count: i32,
+    instances: i32
+) {
+    unsafe {
+        gl::DrawElementsInstanced(
+            gl_mode(mode),
+            count,
+            gl::UNSIGNED_INT,
+            std::ptr::null(),
+            instances
+        );
+    }
+}
+
+fn gl_mode(mode: DrawMode) -> gl::types::GLenum {
+    match mode {
+        DrawMode::Triangles => gl::TRIANGLES,
+        DrawMode::Lines => gl::LINES,
+        DrawMode::Points => gl::POINTS
+    }
+}
+
 fn draw_elements(count: i32, mode: DrawMode) {
     unsafe {
         gl::DrawElements(
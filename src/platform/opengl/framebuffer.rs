@@ -0,0 +1,115 @@
+use crate::{errors::QPError, QPResult};
+
+/**
+ * an offscreen color buffer to draw into instead of the window. there is no
+ * depth buffer, the 2d renderers don't use one
+ */
+#[derive(Debug, PartialEq)]
+pub struct FrameBuffer {
+    id: gl::types::GLuint,
+    texture: gl::types::GLuint,
+
+    pub width: i32,
+    pub height: i32,
+}
+
+impl FrameBuffer {
+    pub fn new(width: i32, height: i32) -> QPResult<Self> {
+        let mut id = 0;
+        let mut texture = 0;
+
+        unsafe {
+            gl::GenFramebuffers(1, &mut id);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, id);
+
+            // sampled with nearest so scaling it up keeps hard pixel edges
+            gl::GenTextures(1, &mut texture);
+            gl::BindTexture(gl::TEXTURE_2D, texture);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA8 as i32,
+                width,
+                height,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                std::ptr::null(),
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::TEXTURE_2D,
+                texture,
+                0,
+            );
+        }
+
+        let status = unsafe { gl::CheckFramebufferStatus(gl::FRAMEBUFFER) };
+        let framebuffer = Self {
+            id,
+            texture,
+            width,
+            height,
+        };
+        framebuffer.unbind();
+
+        if status != gl::FRAMEBUFFER_COMPLETE {
+            return Err(QPError::Generic(format!(
+                "framebuffer {}x{} is incomplete: {:#x}",
+                width, height, status
+            )));
+        }
+
+        Ok(framebuffer)
+    }
+
+    /**
+     * draws go into the framebuffer until `unbind`
+     */
+    pub fn bind(&self) {
+        unsafe { gl::BindFramebuffer(gl::FRAMEBUFFER, self.id) }
+    }
+
+    pub fn unbind(&self) {
+        unsafe { gl::BindFramebuffer(gl::FRAMEBUFFER, 0) }
+    }
+
+    /**
+     * copies the whole framebuffer into the (x, y, width, height) rect of
+     * the window, scaled with nearest filtering
+     */
+    pub fn blit_to_window(&self, x: i32, y: i32, width: i32, height: i32) {
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.id);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, 0);
+            gl::BlitFramebuffer(
+                0,
+                0,
+                self.width,
+                self.height,
+                x,
+                y,
+                x + width,
+                y + height,
+                gl::COLOR_BUFFER_BIT,
+                gl::NEAREST,
+            );
+        }
+
+        self.unbind();
+    }
+}
+
+impl Drop for FrameBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.id);
+            gl::DeleteTextures(1, &self.texture);
+        }
+    }
+}
@@ -1,9 +1,11 @@
 pub mod buffer;
 pub mod capabilities;
 pub mod draw;
+pub mod framebuffer;
 pub mod functions;
 pub mod pixel_store;
 pub mod shader;
+pub mod stencil;
 pub mod textures;
 
 mod c_str;
@@ -33,6 +33,18 @@ impl ShaderProgram {
         Self::from_file(name)
     }
 
+    /**
+     * a program with an id but nothing compiled, for tests that run without a
+     * context. it must never be dropped, since dropping deletes the program
+     */
+    #[cfg(test)]
+    pub(crate) fn headless(id: gl::types::GLuint) -> Self {
+        ShaderProgram {
+            id,
+            _shaders: vec![]
+        }
+    }
+
     pub fn from_str(
         vert: &str,
         frag: &str,
@@ -0,0 +1,79 @@
+// https://registry.khronos.org/OpenGL-Refpages/gl4/html/glStencilFunc.xhtml
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GLStencilFunc {
+    Always,
+    Equal,
+    NotEqual,
+}
+
+// https://registry.khronos.org/OpenGL-Refpages/gl4/html/glStencilOp.xhtml
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GLStencilOp {
+    Keep,
+    Zero,
+    Replace,
+}
+
+pub fn gl_stencil_func(func: GLStencilFunc, reference: i32, mask: u32) {
+    unsafe { gl::StencilFunc(func.unwrap(), reference, mask) }
+}
+
+/**
+ * what happens to the stencil value when the stencil test fails, when it
+ * passes but the depth test fails, and when both pass
+ */
+pub fn gl_stencil_op(stencil_fail: GLStencilOp, depth_fail: GLStencilOp, pass: GLStencilOp) {
+    unsafe { gl::StencilOp(stencil_fail.unwrap(), depth_fail.unwrap(), pass.unwrap()) }
+}
+
+/**
+ * which bits of the stencil buffer can be written. 0 makes it read only
+ */
+pub fn gl_stencil_mask(mask: u32) {
+    unsafe { gl::StencilMask(mask) }
+}
+
+pub fn gl_color_mask(write: bool) {
+    let write = match write {
+        true => gl::TRUE,
+        false => gl::FALSE,
+    };
+
+    unsafe { gl::ColorMask(write, write, write, write) }
+}
+
+pub fn gl_depth_mask(write: bool) {
+    unsafe {
+        gl::DepthMask(match write {
+            true => gl::TRUE,
+            false => gl::FALSE,
+        })
+    }
+}
+
+pub fn clear_stencil(value: i32) {
+    unsafe {
+        gl::ClearStencil(value);
+        gl::Clear(gl::STENCIL_BUFFER_BIT);
+    }
+}
+
+impl GLStencilFunc {
+    fn unwrap(&self) -> gl::types::GLenum {
+        match self {
+            GLStencilFunc::Always => gl::ALWAYS,
+            GLStencilFunc::Equal => gl::EQUAL,
+            GLStencilFunc::NotEqual => gl::NOTEQUAL,
+        }
+    }
+}
+
+impl GLStencilOp {
+    fn unwrap(&self) -> gl::types::GLenum {
+        match self {
+            GLStencilOp::Keep => gl::KEEP,
+            GLStencilOp::Zero => gl::ZERO,
+            GLStencilOp::Replace => gl::REPLACE,
+        }
+    }
+}
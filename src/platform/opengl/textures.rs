@@ -72,6 +72,20 @@ impl Texture {
         }
     }
 
+    /**
+     * a texture with an id but no storage, for tests that run without a
+     * context. it must never be dropped, since dropping deletes the texture
+     */
+    #[cfg(test)]
+    pub(crate) fn headless(id: u32, width: i32, height: i32) -> Self {
+        Self {
+            id,
+            width,
+            height,
+            target: gl::TEXTURE_2D
+        }
+    }
+
     pub fn add_image_data(
         &self,
         internal_format: Format,
@@ -1,3 +1,4 @@
 mod window;
 
-pub use window::QPWindow;
\ No newline at end of file
+pub use window::QPWindow;
+pub use window::QPWindowState;
\ No newline at end of file
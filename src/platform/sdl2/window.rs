@@ -1,9 +1,15 @@
-use crate::prelude::QPError;
+use crate::prelude::{
+    qp_core::{to_abs_path, QPImage},
+    QPError,
+};
 use crate::QPResult;
 use sdl2::{
+    controller::GameController,
     event::Event,
+    pixels::PixelFormatEnum,
+    surface::Surface,
     video::{GLContext, GLProfile, Window},
-    Sdl, VideoSubsystem,
+    GameControllerSubsystem, Sdl, VideoSubsystem,
 };
 
 pub struct QPWindow {
@@ -11,6 +17,10 @@ pub struct QPWindow {
     pub video_subsystem: VideoSubsystem,
     pub window: Option<Window>,
     pub gl_ctx: Option<GLContext>,
+
+    // None when SDL couldn't start its gamepad support, the game just won't
+    // see any gamepads
+    pub controller_subsystem: Option<GameControllerSubsystem>,
 }
 
 impl QPWindow {
@@ -20,11 +30,14 @@ impl QPWindow {
             .video()
             .map_err(|e| QPError::Generic(e.to_string()))?;
 
+        let controller_subsystem = sdl_ctx.game_controller().ok();
+
         Ok(Self {
             ctx: sdl_ctx,
             video_subsystem,
             window: None,
             gl_ctx: None,
+            controller_subsystem,
         })
     }
 
@@ -38,6 +51,8 @@ impl QPWindow {
         let gl_attr = self.video_subsystem.gl_attr();
         gl_attr.set_context_profile(GLProfile::Core);
         gl_attr.set_context_version(gl_version.0, gl_version.1);
+        gl_attr.set_depth_size(24);
+        gl_attr.set_stencil_size(8);
 
         #[cfg(debug_assertions)]
         gl_attr.set_context_flags().debug().set();
@@ -79,6 +94,56 @@ impl QPWindow {
         Ok(events)
     }
 
+    /**
+     * opens the gamepads plugged in this frame. SDL also reports the ones
+     * already plugged in at startup this way. pads it can't open are skipped
+     */
+    pub fn open_gamepads(&self, events: &[Event]) -> Vec<GameController> {
+        let Some(subsystem) = &self.controller_subsystem else {
+            return vec![];
+        };
+
+        events
+            .iter()
+            .filter_map(|event| match event {
+                Event::ControllerDeviceAdded { which, .. } => subsystem.open(*which).ok(),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /**
+     * pushes title and icon changes made through a QPWindowState to the window
+     */
+    pub fn apply(&mut self, state: &mut QPWindowState) -> QPResult<()> {
+        let Some(window) = self.window.as_mut() else {
+            return Ok(());
+        };
+
+        if state.title_changed {
+            window
+                .set_title(&state.title)
+                .map_err(|e| QPError::Generic(e.to_string()))?;
+            state.title_changed = false;
+        }
+
+        if let Some(icon) = state.icon.take() {
+            let mut pixels = icon.rgba();
+            let surface = Surface::from_data(
+                &mut pixels,
+                icon.width,
+                icon.height,
+                icon.width * 4,
+                PixelFormatEnum::RGBA32,
+            )
+            .map_err(QPError::Generic)?;
+
+            window.set_icon(surface);
+        }
+
+        Ok(())
+    }
+
     pub fn relative_mouse_mode(&self, on: bool) {
         self.ctx.mouse().set_relative_mouse_mode(on)
     }
@@ -86,3 +151,64 @@ impl QPWindow {
         self.ctx.mouse().relative_mouse_mode()
     }
 }
+
+/**
+ * the window's title and icon as seen by controllers, through `world.window`.
+ * changes are applied to the real window at the start of the next frame
+ */
+#[derive(Default)]
+pub struct QPWindowState {
+    title: String,
+    title_changed: bool,
+    icon: Option<QPImage>,
+}
+
+impl QPWindowState {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        if self.title != title {
+            self.title = title.to_string();
+            self.title_changed = true;
+        }
+    }
+
+    /**
+     * loads an image, relative to the executable like other assets, to use as
+     * the window icon. a missing or broken file is an error and the current
+     * icon is kept
+     */
+    pub fn set_icon(&mut self, path: &str) -> QPResult<()> {
+        self.icon = Some(QPImage::from_file(&to_abs_path(path)?)?);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_state_title_and_icon() {
+        let mut state = QPWindowState::new("space shooter");
+        assert_eq!(state.title(), "space shooter");
+        assert!(!state.title_changed);
+
+        state.set_title("space shooter - 60 fps");
+        assert_eq!(state.title(), "space shooter - 60 fps");
+        assert!(state.title_changed);
+
+        assert!(state.set_icon("assets/textures/does_not_exist.png").is_err());
+        assert!(state.icon.is_none());
+    }
+}
@@ -5,7 +5,12 @@ use std::{
 
 use crate::{
     asset_manager::AssetManager,
-    prelude::{qp_core::StringInterner, qp_ecs::EntityManager},
+    prelude::{
+        qp_assets::RCamera2D,
+        qp_core::StringInterner,
+        qp_ecs::{ComponentStoreStats, EntityManager},
+        qp_physics::s_entities_in_bounds, VersionedIndex,
+    },
     QPResult,
 };
 
@@ -41,12 +46,85 @@ impl GlobalRegistry {
         self.strings.borrow_mut()
     }
 
-    pub fn flush(&mut self) {
+    /**
+     * removes every entity, for restarting a level. assets stay loaded
+     */
+    pub fn clear(&mut self) {
+        self.entity_manager.clear();
+    }
+
+    /**
+     * see `EntityManager::registered_component_names`
+     */
+    pub fn registered_component_names(&self) -> Vec<&'static str> {
+        self.entity_manager.registered_component_names()
+    }
+
+    /**
+     * how many entities there are and how much room they and their
+     * components take, for spotting stores that keep growing
+     */
+    pub fn stats(&self) -> RegistryStats {
+        let em = &self.entity_manager;
+
+        RegistryStats {
+            entities: em.count(),
+            allocator_capacity: em.allocator_capacity(),
+            components: em.component_stats(),
+        }
+    }
+
+    /**
+     * the entities the camera can see, see `s_entities_in_bounds`. empty if
+     * the camera isn't loaded
+     */
+    pub fn entities_in_view(&self, camera: u64) -> Vec<VersionedIndex> {
+        let Some(camera) = self.asset_manager.get::<RCamera2D>(camera) else {
+            return vec![];
+        };
+
+        s_entities_in_bounds(self, &camera.visible_bounds())
+    }
+
+    /**
+     * deletes the entities marked with `set_to_delete`. the app calls this
+     * once a frame, after every controller has updated and before any
+     * renderer draws, so renderers never see an entity that is half way
+     * through being deleted and controllers never lose one mid frame
+     */
+    pub fn update_deletions(&mut self) {
         self.entity_manager.flush();
+    }
+
+    pub fn flush(&mut self) {
+        self.update_deletions();
         self.asset_manager.flush();
     }
 }
 
+/**
+* see `GlobalRegistry::stats`
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryStats {
+    pub entities: usize,
+    pub allocator_capacity: usize,
+    pub components: Vec<ComponentStoreStats>,
+}
+
+impl RegistryStats {
+    /**
+     * the count stores holding the most components, biggest first
+     */
+    pub fn largest(&self, count: usize) -> Vec<&ComponentStoreStats> {
+        let mut stores = self.components.iter().collect::<Vec<_>>();
+        stores.sort_by(|a, b| b.len.cmp(&a.len).then(b.capacity.cmp(&a.capacity)));
+        stores.truncate(count);
+
+        stores
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::prelude::qp_ecs::Component;
@@ -115,4 +193,56 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn registry_registered_component_names() {
+        let mut registry = create_registry();
+        assert_eq!(
+            registry.registered_component_names(),
+            vec!["DrawComponent", "TransformComponent"]
+        );
+
+        crate::prelude::qp_ecs::components::register_components(&mut registry);
+
+        let names = registry.registered_component_names();
+        for name in ["CTransform2D", "CSprite", "CTag", "CVelocity2D", "CHealth"] {
+            assert!(names.contains(&name), "{} is missing", name);
+        }
+        assert_eq!(names.len(), registry.entity_manager.registered_components_len());
+    }
+
+    #[test]
+    fn registry_stats_count_entities_and_components() {
+        let mut registry = create_registry();
+        let em = &mut registry.entity_manager;
+
+        for i in 0..10 {
+            let entity = em.create();
+            em.add(&entity, TransformComponent::default());
+            if i % 2 == 0 {
+                em.add(&entity, DrawComponent { shader_id: None });
+            }
+        }
+        let deleted = em.create();
+        em.add(&deleted, DrawComponent { shader_id: None });
+        em.set_to_delete(deleted);
+        registry.update_deletions();
+
+        let stats = registry.stats();
+        assert_eq!(stats.entities, 10);
+        assert!(stats.allocator_capacity >= 11);
+
+        let names = stats.components.iter().map(|c| c.name).collect::<Vec<_>>();
+        assert_eq!(names, ["DrawComponent", "TransformComponent"]);
+        // the deleted entity's component stays until its slot is reused
+        assert_eq!(stats.components[0].len, 6);
+        assert_eq!(stats.components[1].len, 10);
+        for store in stats.components.iter() {
+            assert!(store.capacity >= 11, "{:?}", store);
+        }
+
+        let largest = stats.largest(1);
+        assert_eq!(largest.len(), 1);
+        assert_eq!(largest[0].name, "TransformComponent");
+    }
 }
@@ -0,0 +1,215 @@
+use crate::prelude::{qp_schemas::SchemaScene2D, GlobalRegistry, QPError, Schema, VersionedIndex};
+use crate::QPResult;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    thread::{self, JoinHandle},
+};
+
+/**
+ * saves a scene to a rotating set of backup files every `interval` seconds,
+ * for the editor. the scene is read from the registry on the main thread,
+ * then written out as yaml on a worker so a big scene doesn't stall the
+ * frame. backups are `{name}.autosave.{slot}.yaml` in dir, and once every
+ * slot is used the oldest one is overwritten
+ *
+ * a save that comes due while the last one is still being written waits
+ * for it, it isn't queued up behind it
+ */
+pub struct AutoSave {
+    dir: PathBuf,
+    name: String,
+    interval: f32,
+    backups: usize,
+
+    elapsed: f32,
+    next_slot: usize,
+    worker: Option<JoinHandle<QPResult<PathBuf>>>,
+    last_saved: Option<PathBuf>,
+}
+
+impl AutoSave {
+    pub fn new(dir: impl Into<PathBuf>, name: &str, interval: f32, backups: usize) -> Self {
+        Self {
+            dir: dir.into(),
+            name: name.to_string(),
+            interval,
+            backups: backups.max(1),
+            elapsed: 0.0,
+            next_slot: 0,
+            worker: None,
+            last_saved: None,
+        }
+    }
+
+    /**
+     * where the backup in slot goes
+     */
+    pub fn backup_path(&self, slot: usize) -> PathBuf {
+        self.dir.join(format!(
+            "{}.autosave.{}.yaml",
+            self.name,
+            slot % self.backups
+        ))
+    }
+
+    /**
+     * the most recent backup that finished writing
+     */
+    pub fn last_saved(&self) -> Option<&Path> {
+        self.last_saved.as_deref()
+    }
+
+    /**
+     * call once a frame. starts a save once the interval has passed and
+     * returns the error of a save that failed since the last call
+     */
+    pub fn update(
+        &mut self,
+        delta: f32,
+        scene: VersionedIndex,
+        registry: &GlobalRegistry,
+    ) -> QPResult<()> {
+        self.elapsed += delta;
+
+        if self
+            .worker
+            .as_ref()
+            .is_some_and(|worker| worker.is_finished())
+        {
+            self.wait()?;
+        }
+
+        if self.elapsed < self.interval || self.worker.is_some() {
+            return Ok(());
+        }
+        self.elapsed = 0.0;
+
+        let Some(schema) = SchemaScene2D::from_entity(scene, registry) else {
+            return Err(QPError::Generic(
+                "[autosave] couldn't read the scene to save".into(),
+            ));
+        };
+
+        let path = self.backup_path(self.next_slot);
+        self.next_slot = (self.next_slot + 1) % self.backups;
+        self.worker = Some(thread::spawn(move || write_backup(&schema, path)));
+
+        Ok(())
+    }
+
+    /**
+     * blocks until the save being written, if any, is done. returns where
+     * it went
+     */
+    pub fn wait(&mut self) -> QPResult<Option<PathBuf>> {
+        let Some(worker) = self.worker.take() else {
+            return Ok(None);
+        };
+
+        let path = worker
+            .join()
+            .map_err(|_| QPError::Generic("[autosave] the save thread panicked".into()))??;
+        self.last_saved = Some(path.clone());
+
+        Ok(Some(path))
+    }
+}
+
+impl Drop for AutoSave {
+    fn drop(&mut self) {
+        // don't leave a half written backup behind
+        let _ = self.wait();
+    }
+}
+
+fn write_backup(schema: &SchemaScene2D, path: PathBuf) -> QPResult<PathBuf> {
+    let yaml = serde_yaml::to_string(schema).map_err(|e| QPError::Generic(e.to_string()))?;
+
+    // write next to the backup and swap it in, so a crash mid write can't
+    // ruin the backup that was there
+    let tmp = path.with_extension("yaml.tmp");
+    fs::write(&tmp, yaml)?;
+    fs::rename(&tmp, &path)?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{
+        qp_ecs::components::{CSprite, CTag, CTransform2D},
+        qp_schemas::SchemaSprite,
+    };
+    use crate::test_utils::registry;
+
+    fn registry_with(schema: &SchemaScene2D) -> (GlobalRegistry, VersionedIndex) {
+        let mut registry = registry();
+        let scene = schema.build_entity(&mut registry).unwrap();
+
+        (registry, scene)
+    }
+
+    fn sprites(registry: &GlobalRegistry) -> Vec<(CTag, CTransform2D)> {
+        let em = &registry.entity_manager;
+
+        em.query_all_in_spawn_order::<CSprite>()
+            .iter()
+            .map(|entity| {
+                (
+                    em.get::<CTag>(entity).unwrap().clone(),
+                    *em.get::<CTransform2D>(entity).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn autosave_writes_backup_after_interval() {
+        let schema = SchemaScene2D {
+            name: "autosaved".to_string(),
+            cameras: vec![],
+            shaders: vec![],
+            textures: vec![],
+            sprites: (0..3)
+                .map(|i| SchemaSprite {
+                    tag: format!("rock_{}", i),
+                    transform: CTransform2D {
+                        translate: glm::vec2(i as f32 * 10.0, 5.0),
+                        ..CTransform2D::default()
+                    },
+                    ..SchemaSprite::default()
+                })
+                .collect(),
+        };
+        let (registry, scene) = registry_with(&schema);
+
+        let dir = std::env::temp_dir().join("quipi_autosave");
+        fs::create_dir_all(&dir).unwrap();
+        let mut autosave = AutoSave::new(&dir, "level", 1.0, 2);
+        for slot in 0..2 {
+            let _ = fs::remove_file(autosave.backup_path(slot));
+        }
+
+        autosave.update(0.6, scene, &registry).unwrap();
+        assert_eq!(autosave.wait().unwrap(), None);
+
+        autosave.update(0.6, scene, &registry).unwrap();
+        let path = autosave.wait().unwrap().unwrap();
+        assert_eq!(path, dir.join("level.autosave.0.yaml"));
+        assert_eq!(autosave.last_saved(), Some(path.as_path()));
+
+        let saved: SchemaScene2D =
+            serde_yaml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let (loaded, _) = registry_with(&saved);
+        assert_eq!(saved.name, "autosaved");
+        assert_eq!(sprites(&loaded), sprites(&registry));
+
+        // the next ones rotate through the slots
+        autosave.update(1.0, scene, &registry).unwrap();
+        assert_eq!(autosave.wait().unwrap(), Some(autosave.backup_path(1)));
+        autosave.update(1.0, scene, &registry).unwrap();
+        assert_eq!(autosave.wait().unwrap(), Some(path));
+    }
+}
@@ -1,3 +1,4 @@
+pub mod autosave;
 pub mod camera2d;
 pub mod scene;
 pub mod scene2d;
@@ -10,10 +11,12 @@ pub mod prelude {
 
     use super::*;
 
+    pub use autosave::AutoSave;
     pub use camera2d::SchemaCamera2D;
     pub use scene2d::SchemaScene2D;
     pub use shader::SchemaShader;
     pub use sprite::SchemaSprite;
+    pub use sprite::SchemaTextureAtlas;
     pub use texture::SchemaTexture;
 
     pub use scene::*;
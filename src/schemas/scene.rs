@@ -32,3 +32,116 @@ pub fn load_scene_2d(name: &str, default: SchemaScene2D) -> QPResult<SchemaScene
 
     Ok(default)
 }
+
+/**
+ * same as `save_scene_2d` but in a compact binary format, which is much
+ * smaller and faster to load for big scenes. saved as `{name}.bin`
+ */
+pub fn save_scene_binary(
+    name: &str,
+    scene: VersionedIndex,
+    registry: &GlobalRegistry,
+) -> QPResult<()> {
+    if let Some(scene) = SchemaScene2D::from_entity(scene, registry) {
+        let path = to_abs_path(&format!("assets/scenes/{}.bin", name))?;
+
+        fs::write(path, scene_to_bytes(&scene)?)?;
+    } else {
+        #[cfg(debug_assertions)]
+        println!("there was a problem saving the scene");
+    }
+
+    Ok(())
+}
+
+pub fn load_scene_binary(name: &str, default: SchemaScene2D) -> QPResult<SchemaScene2D> {
+    let path = to_abs_path(&format!("assets/scenes/{}.bin", name))?;
+    if let Ok(bytes) = fs::read(path) {
+        return scene_from_bytes(&bytes);
+    }
+
+    Ok(default)
+}
+
+pub fn scene_to_bytes(scene: &SchemaScene2D) -> QPResult<Vec<u8>> {
+    bincode::serialize(scene).map_err(|e| QPError::Generic(e.to_string()))
+}
+
+pub fn scene_from_bytes(bytes: &[u8]) -> QPResult<SchemaScene2D> {
+    bincode::deserialize(bytes).map_err(|e| QPError::Generic(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{
+        qp_ecs::components::{CQuad, CSprite, CTag, CTransform2D},
+        qp_schemas::SchemaSprite,
+    };
+    use crate::test_utils::registry;
+
+    fn scene() -> SchemaScene2D {
+        let sprites = (0..500)
+            .map(|i| SchemaSprite {
+                tag: format!("tile_{}", i % 7),
+                transform: CTransform2D {
+                    translate: glm::vec2((i % 25) as f32 * 16.0, (i / 25) as f32 * 16.0),
+                    rotate: i as f32 * 0.1,
+                    ..CTransform2D::default()
+                },
+                quad: CQuad {
+                    width: 16.0,
+                    height: 16.0,
+                    ..CQuad::default()
+                },
+                ..SchemaSprite::default()
+            })
+            .collect();
+
+        SchemaScene2D {
+            name: "tiles".to_string(),
+            cameras: vec![],
+            shaders: vec![],
+            textures: vec![],
+            sprites,
+        }
+    }
+
+    fn build(scene: &SchemaScene2D) -> GlobalRegistry {
+        let mut registry = registry();
+        scene.build_entity(&mut registry).unwrap();
+
+        registry
+    }
+
+    fn sprites(registry: &GlobalRegistry) -> Vec<(CTag, CTransform2D, CQuad, CSprite)> {
+        let em = &registry.entity_manager;
+
+        em.query_all_in_spawn_order::<CSprite>()
+            .iter()
+            .map(|entity| {
+                (
+                    em.get::<CTag>(entity).unwrap().clone(),
+                    *em.get::<CTransform2D>(entity).unwrap(),
+                    em.get::<CQuad>(entity).unwrap().clone(),
+                    em.get::<CSprite>(entity).unwrap().clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn scene_binary_matches_yaml() {
+        let scene = scene();
+
+        let yaml = serde_yaml::to_string(&scene).unwrap();
+        let binary = scene_to_bytes(&scene).unwrap();
+
+        let from_yaml = build(&serde_yaml::from_str::<SchemaScene2D>(&yaml).unwrap());
+        let from_binary = build(&scene_from_bytes(&binary).unwrap());
+
+        assert_eq!(from_binary.entity_manager.count(), 501);
+        assert_eq!(sprites(&from_binary), sprites(&from_yaml));
+        assert!(binary.len() < yaml.len() / 2);
+    }
+}
@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::prelude::Schema;
 use crate::QPResult;
@@ -16,7 +16,7 @@ use crate::{
 
 pub const DEFAULT_RECT_TAG: &str = "default_rect";
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct SchemaSprite {
     pub tag: String,
     pub transform: CTransform2D,
@@ -24,44 +24,22 @@ pub struct SchemaSprite {
 
     pub velocity: Option<CVelocity2D>,
     pub color: glm::Vec4,
-    pub texture: Option<String>,
+    pub texture: Option<SchemaTextureAtlas>,
+
+    /**
+     * sizes the quad to this height, with the width taken from the texture
+     * cell's aspect ratio. the quad's own width and height are ignored
+     */
+    #[serde(default)]
+    pub fit_height: Option<f32>,
 }
 
 impl Schema for SchemaSprite {
     fn build_entity(&self, registry: &mut GlobalRegistry) -> QPResult<VersionedIndex> {
-        let texture_atlas = match &self.texture {
-            Some(id_as_str) => {
-                let Some(id) = registry.asset_manager.get_asset_id(&id_as_str) else {
-                    return Err(QPError::SpriteTextureDoesntExist);
-                };
-
-                let texture = registry.asset_manager.get::<RTexture>(id).unwrap();
-
-                Some(TextureAtlas {
-                    texture: id,
-                    texture_dims: texture.texture_dims,
-                    active_texture: glm::vec2(0.0, 0.0),
-                })
-            }
-            None => None,
-        };
-
+        // resolved first, so a missing texture doesn't leave an empty entity
+        let texture_atlas = self.resolve_atlas(registry)?;
         let entity = registry.entity_manager.create();
-        registry.entity_manager.add(
-            &entity,
-            CTag {
-                tag: self.tag.clone(),
-            },
-        );
-        if let Some(velocity) = self.velocity {
-            registry.entity_manager.add(&entity, velocity);
-        }
-        registry.entity_manager.add(&entity, self.quad.clone());
-        registry.entity_manager.add(&entity, self.transform);
-        registry.entity_manager.add(
-            &entity,
-            CSprite::new(&self.quad, Some(self.color), texture_atlas),
-        );
+        self.attach(registry, &entity, texture_atlas);
 
         Ok(entity)
     }
@@ -81,11 +59,12 @@ impl Schema for SchemaSprite {
                 transform: transform.clone(),
                 quad: quad.clone(),
                 texture: match &sprite.texture_atlas {
-                    Some(atlas) => registry.strings().get_string(atlas.texture),
+                    Some(atlas) => Some(SchemaTextureAtlas::from_atlas(atlas, registry)?),
                     None => None,
                 },
                 color: sprite.color,
                 velocity: registry.entity_manager.get::<CVelocity2D>(&entity).cloned(),
+                fit_height: None,
             };
 
             return Some(schema);
@@ -108,6 +87,70 @@ impl Default for SchemaSprite {
             velocity: None,
             texture: None,
             color: glm::vec4(1.0, 1.0, 1.0, 1.0),
+            fit_height: None,
+        }
+    }
+}
+
+impl SchemaSprite {
+    /**
+     * gives an existing entity the sprite's components, replacing the ones
+     * it has. used to reset pooled entities, see `EntityPool`
+     */
+    pub fn apply_to(&self, registry: &mut GlobalRegistry, entity: &VersionedIndex) -> QPResult<()> {
+        let texture_atlas = self.resolve_atlas(registry)?;
+        self.attach(registry, entity, texture_atlas);
+
+        Ok(())
+    }
+
+    fn resolve_atlas(&self, registry: &mut GlobalRegistry) -> QPResult<Option<TextureAtlas>> {
+        match &self.texture {
+            Some(atlas) => Ok(Some(atlas.resolve(registry)?)),
+            None => Ok(None),
+        }
+    }
+
+    // nothing here can fail, so an entity is never left half set up
+    fn attach(
+        &self,
+        registry: &mut GlobalRegistry,
+        entity: &VersionedIndex,
+        texture_atlas: Option<TextureAtlas>,
+    ) {
+        let quad = self.fitted_quad(registry, texture_atlas.as_ref());
+
+        let em = &mut registry.entity_manager;
+        em.add(
+            entity,
+            CTag {
+                tag: self.tag.clone(),
+            },
+        );
+        match self.velocity {
+            Some(velocity) => em.add(entity, velocity),
+            None if em.get::<CVelocity2D>(entity).is_some() => em.remove::<CVelocity2D>(entity),
+            None => (),
+        }
+        em.add(entity, self.transform);
+        em.add(entity, CSprite::new(&quad, Some(self.color), texture_atlas));
+        em.add(entity, quad);
+    }
+
+    // falls back to the quad as is if the texture isn't loaded as an RTexture
+    fn fitted_quad(&self, registry: &GlobalRegistry, atlas: Option<&TextureAtlas>) -> CQuad {
+        let (Some(height), Some(atlas)) = (self.fit_height, atlas) else {
+            return self.quad.clone();
+        };
+        let Some(texture) = registry.asset_manager.get::<RTexture>(atlas.texture) else {
+            return self.quad.clone();
+        };
+
+        let size = glm::vec2(texture.texture.width as f32, texture.texture.height as f32);
+        CQuad {
+            center_x: self.quad.center_x,
+            center_y: self.quad.center_y,
+            ..CQuad::fit_height(atlas.cell_size(size), height)
         }
     }
 }
@@ -118,3 +161,251 @@ pub struct TextureAtlas {
     pub texture_dims: glm::Vec2,
     pub active_texture: glm::Vec2,
 }
+
+impl TextureAtlas {
+    /**
+     * the size of one cell of a texture `texture_size` pixels big
+     */
+    pub fn cell_size(&self, texture_size: glm::Vec2) -> glm::Vec2 {
+        texture_size.component_div(&self.texture_dims)
+    }
+}
+
+/**
+* the saved form of a TextureAtlas
+*
+* asset ids are only meaningful to the asset manager that handed them out, so
+* the texture is stored by the name it was loaded with and looked up again
+* when the sprite is rebuilt
+*
+* older scenes saved just the texture name, eg. `texture: Player.png`. that
+* still loads, as the first cell of the texture with its own atlas dimensions
+*/
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct SchemaTextureAtlas {
+    pub texture: String,
+
+    /**
+     * None uses the dimensions the texture was loaded with
+     */
+    pub texture_dims: Option<glm::Vec2>,
+    pub active_texture: glm::Vec2,
+}
+
+impl<'de> Deserialize<'de> for SchemaTextureAtlas {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // binary scenes can't tell the two forms apart, and never saved names
+        if !deserializer.is_human_readable() {
+            return SavedAtlas::deserialize(deserializer).map(Self::from);
+        }
+
+        match SavedTextureAtlas::deserialize(deserializer)? {
+            SavedTextureAtlas::Name(texture) => Ok(Self::new(&texture)),
+            SavedTextureAtlas::Atlas(atlas) => Ok(Self::from(atlas)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedTextureAtlas {
+    Name(String),
+    Atlas(SavedAtlas),
+}
+
+#[derive(Deserialize)]
+struct SavedAtlas {
+    texture: String,
+    #[serde(default)]
+    texture_dims: Option<glm::Vec2>,
+    active_texture: glm::Vec2,
+}
+
+impl From<SavedAtlas> for SchemaTextureAtlas {
+    fn from(saved: SavedAtlas) -> Self {
+        Self {
+            texture: saved.texture,
+            texture_dims: saved.texture_dims,
+            active_texture: saved.active_texture,
+        }
+    }
+}
+
+impl SchemaTextureAtlas {
+    /**
+     * the first cell of the texture, using the atlas dimensions it was
+     * loaded with
+     */
+    pub fn new(texture: &str) -> Self {
+        Self {
+            texture: texture.to_string(),
+            texture_dims: None,
+            active_texture: glm::vec2(0.0, 0.0),
+        }
+    }
+
+    pub fn from_atlas(atlas: &TextureAtlas, registry: &GlobalRegistry) -> Option<Self> {
+        Some(Self {
+            texture: registry.strings().get_string(atlas.texture)?,
+            texture_dims: Some(atlas.texture_dims),
+            active_texture: atlas.active_texture,
+        })
+    }
+
+    /**
+     * errors if no asset has been loaded under the texture's name. without
+     * texture_dims the dimensions come from the loaded RTexture, or a single
+     * cell if the asset isn't one
+     */
+    pub fn resolve(&self, registry: &mut GlobalRegistry) -> QPResult<TextureAtlas> {
+        let Some(texture) = registry.asset_manager.get_asset_id(&self.texture) else {
+            return Err(QPError::SpriteTextureDoesntExist);
+        };
+
+        let texture_dims = self.texture_dims.unwrap_or_else(|| {
+            registry
+                .asset_manager
+                .get::<RTexture>(texture)
+                .map_or(glm::vec2(1.0, 1.0), |loaded| loaded.texture_dims)
+        });
+
+        Ok(TextureAtlas {
+            texture,
+            texture_dims,
+            active_texture: self.active_texture,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::qp_assets::RTileMap;
+    use crate::test_utils::registry;
+
+    // textures need a gl context, any asset will do to stand in for one here
+    fn registry_with_texture(name: &str) -> GlobalRegistry {
+        let mut registry = registry();
+
+        let placeholder = RTileMap::new(1, 1, vec![0], glm::vec2(1.0, 1.0)).unwrap();
+        registry.asset_manager.load_asset(name, placeholder).unwrap();
+
+        registry
+    }
+
+    #[test]
+    fn sprite_missing_texture_spawns_nothing() {
+        let mut registry = registry_with_texture("space_tilesheet");
+        let schema = SchemaSprite {
+            texture: Some(SchemaTextureAtlas::new("not_loaded")),
+            ..SchemaSprite::default()
+        };
+
+        assert!(matches!(
+            schema.build_entity(&mut registry),
+            Err(QPError::SpriteTextureDoesntExist)
+        ));
+        assert_eq!(registry.entity_manager.count(), 0);
+        assert_eq!(registry.entity_manager.allocator_size(), 0);
+    }
+
+    #[test]
+    fn sprite_texture_round_trip() {
+        let mut registry = registry_with_texture("space_tilesheet");
+        let texture = registry.asset_manager.get_asset_id("space_tilesheet").unwrap();
+
+        let schema = SchemaSprite {
+            texture: Some(SchemaTextureAtlas {
+                texture: "space_tilesheet".to_string(),
+                texture_dims: Some(glm::vec2(8.0, 6.0)),
+                active_texture: glm::vec2(7.0, 0.0),
+            }),
+            ..SchemaSprite::default()
+        };
+        let entity = schema.build_entity(&mut registry).unwrap();
+        let saved = SchemaSprite::from_entity(entity, &registry).unwrap();
+        let saved = serde_yaml::to_string(&saved).unwrap();
+        assert!(saved.contains("texture: space_tilesheet"));
+
+        let mut fresh = registry_with_texture("space_tilesheet");
+        let loaded = serde_yaml::from_str::<SchemaSprite>(&saved).unwrap();
+        let entity = loaded.build_entity(&mut fresh).unwrap();
+
+        let atlas = fresh
+            .entity_manager
+            .get::<CSprite>(&entity)
+            .and_then(|sprite| sprite.texture_atlas.clone())
+            .unwrap();
+        assert_eq!(Some(atlas.texture), fresh.asset_manager.get_asset_id("space_tilesheet"));
+        assert_eq!(atlas.texture, texture);
+        assert_eq!(atlas.active_texture, glm::vec2(7.0, 0.0));
+    }
+
+    #[test]
+    fn sprite_texture_loads_plain_name() {
+        let schema = SchemaSprite {
+            texture: Some(SchemaTextureAtlas::new("space_tilesheet")),
+            ..SchemaSprite::default()
+        };
+        // how sprites were saved before atlases, with just the texture name
+        let mut old_form = serde_yaml::to_value(&schema).unwrap();
+        old_form["texture"] = "space_tilesheet".into();
+        let old_form = serde_yaml::to_string(&old_form).unwrap();
+        assert!(old_form.contains("texture: space_tilesheet\n"));
+
+        let loaded = serde_yaml::from_str::<SchemaSprite>(&old_form).unwrap();
+        assert_eq!(loaded.texture, schema.texture);
+
+        // the atlas form without dimensions reads the same
+        let atlas = "texture: space_tilesheet\nactive_texture: [2.0, 1.0]";
+        let atlas = serde_yaml::from_str::<SchemaTextureAtlas>(atlas).unwrap();
+        assert_eq!(atlas.texture_dims, None);
+        assert_eq!(atlas.active_texture, glm::vec2(2.0, 1.0));
+
+        // the placeholder asset isn't an RTexture, so it's one cell
+        let mut registry = registry_with_texture("space_tilesheet");
+        let resolved = atlas.resolve(&mut registry).unwrap();
+        assert_eq!(resolved.texture_dims, glm::vec2(1.0, 1.0));
+    }
+
+    #[test]
+    fn sprite_quad_fits_cell_aspect() {
+        // a 4x2 sheet of 64x32 cells
+        let atlas = TextureAtlas {
+            texture: 0,
+            texture_dims: glm::vec2(4.0, 2.0),
+            active_texture: glm::vec2(1.0, 0.0),
+        };
+        let cell = atlas.cell_size(glm::vec2(256.0, 64.0));
+        assert_eq!(cell, glm::vec2(64.0, 32.0));
+
+        let quad = CQuad::fit_height(cell, 48.0);
+        assert_eq!((quad.width, quad.height), (96.0, 48.0));
+        assert_eq!(quad.width / quad.height, 2.0);
+
+        // without an RTexture to measure, the quad is left alone
+        let mut registry = registry_with_texture("space_tilesheet");
+        let schema = SchemaSprite {
+            texture: Some(SchemaTextureAtlas::new("space_tilesheet")),
+            fit_height: Some(48.0),
+            ..SchemaSprite::default()
+        };
+        let entity = schema.build_entity(&mut registry).unwrap();
+        assert_eq!(registry.entity_manager.get::<CQuad>(&entity), Some(&schema.quad));
+    }
+
+    #[test]
+    fn sprite_missing_texture_fails() {
+        let mut registry = registry_with_texture("space_tilesheet");
+
+        let schema = SchemaSprite {
+            texture: Some(SchemaTextureAtlas::new("missing")),
+            ..SchemaSprite::default()
+        };
+
+        assert!(matches!(
+            schema.build_entity(&mut registry),
+            Err(QPError::SpriteTextureDoesntExist)
+        ));
+    }
+}
@@ -0,0 +1,56 @@
+// fixtures shared by the unit tests
+
+use crate::prelude::{
+    qp_ecs::components::{register_components, CQuad, CTransform2D, CVelocity2D},
+    EntityBuilder, GlobalRegistry,
+};
+
+/**
+ * a registry with every built in component registered
+ */
+pub(crate) fn registry() -> GlobalRegistry {
+    let mut registry = GlobalRegistry::init().unwrap();
+    register_components(&mut registry);
+
+    registry
+}
+
+/**
+ * a default transform moved to x, y
+ */
+pub(crate) fn transform_at(x: f32, y: f32) -> CTransform2D {
+    CTransform2D {
+        translate: glm::vec2(x, y),
+        ..CTransform2D::default()
+    }
+}
+
+/**
+ * starts an entity with a transform at x, y
+ */
+pub(crate) fn at(registry: &mut GlobalRegistry, x: f32, y: f32) -> EntityBuilder<'_> {
+    EntityBuilder::create(&mut registry.entity_manager).with(transform_at(x, y))
+}
+
+/**
+ * starts an entity at x, y with a velocity, at rest
+ */
+pub(crate) fn body(registry: &mut GlobalRegistry, x: f32, y: f32) -> EntityBuilder<'_> {
+    at(registry, x, y).with(CVelocity2D::default())
+}
+
+/**
+ * starts an entity at x, y with a size by size quad, so it collides and culls
+ */
+pub(crate) fn collider(
+    registry: &mut GlobalRegistry,
+    x: f32,
+    y: f32,
+    size: f32,
+) -> EntityBuilder<'_> {
+    at(registry, x, y).with(CQuad {
+        width: size,
+        height: size,
+        ..CQuad::default()
+    })
+}
@@ -1,11 +1,14 @@
 use sdl2::event::Event;
 
 use crate::{
+    app::{FixedTimestep, FrameStep},
     core::prelude::{random::Random, Timer},
-    platform::sdl2::QPWindow,
+    input::QPInput,
+    platform::sdl2::{QPWindow, QPWindowState},
     prelude::{
-        qp_ecs::components::register_components,
-        qp_gfx::{QPText, Viewport},
+        qp_ecs::{components::register_components, Archetypes, Component},
+        qp_gfx::{ClearConfig, QPText, RenderInfo, Viewport},
+        VersionedIndex,
     },
     registry::GlobalRegistry,
     QPResult,
@@ -13,13 +16,24 @@ use crate::{
 
 pub struct World {
     pub registry: GlobalRegistry,
+    pub archetypes: Archetypes,
     pub debug_info: DebugInfo,
     pub debug_mode: bool,
+    pub frame_step: FrameStep,
+    pub fixed_step: FixedTimestep,
 
     pub events: Vec<Event>,
+    pub input: QPInput,
+
+    // the frame's events from before any were consumed, see `consume_events`
+    unconsumed_events: Option<Vec<Event>>,
+    // events from frames that ran no update, see `carry_events`
+    carried_events: Vec<Event>,
     pub text_buffer: Vec<QPText>,
 
     pub viewport: Viewport,
+    pub window: QPWindowState,
+    pub clear_config: ClearConfig,
 
     pub delta: f32,
     timer: Timer,
@@ -36,17 +50,25 @@ impl World {
 
         Ok(Self {
             registry,
+            archetypes: Archetypes::new(),
             timer,
             delta,
             rand: Random::from_seed(seed),
 
             debug_info: DebugInfo::default(),
             debug_mode: false,
+            frame_step: FrameStep::default(),
+            fixed_step: FixedTimestep::default(),
 
             events: vec![],
+            input: QPInput::new(),
+            unconsumed_events: None,
+            carried_events: vec![],
             text_buffer: vec![],
 
             viewport,
+            window: QPWindowState::default(),
+            clear_config: ClearConfig::default(),
         })
     }
 
@@ -55,7 +77,17 @@ impl World {
     }
 
     pub fn new_frame(&mut self, winapi: &mut QPWindow) -> QPResult<()> {
-        self.events = winapi.get_event_queue()?;
+        let events = winapi.get_event_queue()?;
+        for gamepad in winapi.open_gamepads(&events) {
+            self.input.connect_gamepad(gamepad);
+        }
+
+        // no update saw the last frame's input, see FixedTimestep
+        match self.fixed_step.skipped() {
+            true => self.input.extend(&events),
+            false => self.input.update(&events),
+        }
+        self.events = events;
         self.delta = self.timer.delta();
 
         self.debug_info.fps = (1.0 / self.delta) as u32;
@@ -64,11 +96,129 @@ impl World {
         Ok(())
     }
 
+    /**
+     * takes the matching events out of `events` for the rest of the frame's
+     * controllers, eg. a menu taking the keys typed into it so the ship
+     * doesn't move. returns the events taken
+     *
+     * `input` hides the consumed keys from everyone too: a consumed key press
+     * stays hidden while the key is held, up to and including its release.
+     * the renderers still see every event
+     */
+    pub fn consume_events(&mut self, consume: impl Fn(&Event) -> bool) -> Vec<Event> {
+        let (consumed, kept): (Vec<Event>, Vec<Event>) =
+            self.events.iter().cloned().partition(|event| consume(event));
+        if consumed.is_empty() {
+            return consumed;
+        }
+        self.input.consume(&consumed);
+
+        let events = std::mem::replace(&mut self.events, kept);
+        self.unconsumed_events.get_or_insert(events);
+
+        consumed
+    }
+
+    /**
+     * puts back the events consumed by this frame's controllers
+     */
+    pub(crate) fn restore_consumed_events(&mut self) {
+        if let Some(events) = self.unconsumed_events.take() {
+            self.events = events;
+        }
+    }
+
+    /**
+     * keeps the frame's events for the next update when the frame runs none.
+     * `events` itself stays per frame, the renderers and frame stepping have
+     * already handled them
+     */
+    pub(crate) fn carry_events(&mut self) {
+        self.carried_events.extend(self.events.iter().cloned());
+    }
+
+    /**
+     * puts the events carried from frames without an update in front of this
+     * frame's for the controllers. returns this frame's own events, to give
+     * back to the renderers once the update is done
+     */
+    pub(crate) fn take_carried_events(&mut self) -> Option<Vec<Event>> {
+        if self.carried_events.is_empty() {
+            return None;
+        }
+
+        let mut events = std::mem::take(&mut self.carried_events);
+        events.extend(self.events.iter().cloned());
+
+        Some(std::mem::replace(&mut self.events, events))
+    }
+
+    /**
+     * starts a frame clean. entity deletions aren't applied here, they wait
+     * for `GlobalRegistry::update_deletions` between the controllers and the
+     * renderers
+     */
     pub fn flush(&mut self) {
-        self.registry.flush();
+        self.registry.asset_manager.flush();
 
         self.text_buffer.clear();
     }
+
+    /**
+     * creates an entity of a registered archetype, see `Archetypes`. None if
+     * the archetype isn't registered
+     */
+    pub fn spawn_default(&mut self, archetype: &str) -> Option<VersionedIndex> {
+        self.archetypes.spawn(archetype, &mut self.registry.entity_manager)
+    }
+
+    /**
+     * shorthand for `world.registry.entity_manager.get`
+     *
+     * ```no_run
+     * # use quipi::prelude::{qp_ecs::components::CTransform2D, VersionedIndex, World};
+     * # fn update(world: &mut World, ship: VersionedIndex) {
+     * // before
+     * let transform = world.registry.entity_manager.get::<CTransform2D>(&ship);
+     *
+     * // after
+     * let transform = world.get_component::<CTransform2D>(&ship);
+     * # }
+     * ```
+     */
+    pub fn get_component<C: Component + PartialEq + 'static>(
+        &self,
+        entity: &VersionedIndex,
+    ) -> Option<&C> {
+        self.registry.entity_manager.get::<C>(entity)
+    }
+
+    /**
+     * shorthand for `world.registry.entity_manager.get_mut`
+     */
+    pub fn get_mut_component<C: Component + PartialEq + 'static>(
+        &mut self,
+        entity: &VersionedIndex,
+    ) -> Option<&mut C> {
+        self.registry.entity_manager.get_mut::<C>(entity)
+    }
+
+    /**
+     * shorthand for `world.registry.asset_manager.get`
+     */
+    pub fn asset<A: Component + std::fmt::Debug + PartialEq + 'static>(&self, id: u64) -> Option<&A> {
+        self.registry.asset_manager.get::<A>(id)
+    }
+
+    /**
+     * shorthand for `world.registry.asset_manager.get_mut`
+     */
+    pub fn asset_mut<A: Component + std::fmt::Debug + PartialEq + 'static>(
+        &mut self,
+        id: u64,
+    ) -> Option<&mut A> {
+        self.registry.asset_manager.get_mut::<A>(id)
+    }
 }
 
 #[derive(Debug, Default)]
@@ -80,4 +230,54 @@ pub struct DebugInfo {
     pub controller_ms: u32,
     pub render_ms: u32,
     pub draw_calls: u32,
+
+    /**
+     * summed over every render pass that keeps count, see `Renderer::render_info`
+     */
+    pub render_info: RenderInfo,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{
+        qp_assets::RTileMap,
+        qp_ecs::components::{CTransform2D, CVelocity2D},
+    };
+
+    #[test]
+    fn world_shorthands_match_registry() {
+        let mut world = World::new(Viewport::headless(800, 600, None), 0).unwrap();
+
+        let entity = world.registry.entity_manager.create();
+        world.registry.entity_manager.add(
+            &entity,
+            CTransform2D {
+                translate: glm::vec2(4.0, 2.0),
+                ..CTransform2D::default()
+            },
+        );
+        world.registry.entity_manager.add(&entity, CVelocity2D { x: 1.0, y: 0.0 });
+
+        let tilemap = RTileMap::new(1, 1, vec![7], glm::vec2(8.0, 8.0)).unwrap();
+        let id = world.registry.asset_manager.load_asset("map", tilemap).unwrap();
+
+        assert_eq!(
+            world.get_component::<CTransform2D>(&entity),
+            world.registry.entity_manager.get::<CTransform2D>(&entity)
+        );
+        assert_eq!(
+            world.asset::<RTileMap>(id),
+            world.registry.asset_manager.get::<RTileMap>(id)
+        );
+
+        world.get_mut_component::<CVelocity2D>(&entity).unwrap().x = 5.0;
+        assert_eq!(
+            world.registry.entity_manager.get::<CVelocity2D>(&entity),
+            Some(&CVelocity2D { x: 5.0, y: 0.0 })
+        );
+
+        world.asset_mut::<RTileMap>(id).unwrap().data[0] = 3;
+        assert_eq!(world.registry.asset_manager.get::<RTileMap>(id).unwrap().data, vec![3]);
+    }
 }